@@ -1,14 +1,30 @@
+pub mod pcalc_angle;
 pub mod pcalc_binary_ops;
 pub mod pcalc_code;
+pub mod pcalc_datetime;
+pub mod pcalc_decimal;
 pub mod pcalc_environment;
+pub mod pcalc_error;
+pub mod pcalc_format;
 pub mod pcalc_function;
 pub mod pcalc_function_table;
 pub mod pcalc_help;
+pub mod pcalc_interval;
 pub mod pcalc_keywords;
 pub mod pcalc_lexer;
+pub mod pcalc_matrix;
+pub mod pcalc_number_theory;
 pub mod pcalc_parser;
+pub mod pcalc_record;
 pub mod pcalc_recursive_check;
 pub mod pcalc_repl;
+pub mod pcalc_special_math;
+pub mod pcalc_suggest;
+pub mod pcalc_ternary_ops;
+pub mod pcalc_tolerance;
 pub mod pcalc_unary_ops;
+pub mod pcalc_uncertain;
+pub mod pcalc_units;
 pub mod pcalc_value;
 pub mod pcalc_variable_table;
+pub mod pcalc_word;
@@ -2,11 +2,24 @@ extern crate clap;
 
 use clap::{App, Arg};
 use prefix_calculator::pcalc_repl::REPL;
+use std::process;
 
 struct Arguments {
     force_int: bool,
     quiet: bool,
     batch: bool,
+    rational: bool,
+    decimal: bool,
+    scale: String,
+    rounding: String,
+    word: String,
+    overflow: String,
+    format: String,
+    strict: bool,
+    truthy: bool,
+    tolerance: String,
+    angle: String,
+    now: String,
     expr: String,
     file: String
 }
@@ -35,6 +48,60 @@ fn parse_args() -> Arguments {
              .short("-b")
              .long("batch")
              .help("Enable batch mode"))
+        .arg(Arg::with_name("rational")
+             .short("r")
+             .long("rational")
+             .help("Enable rational mode. Numeric literals are parsed as exact rational numbers"))
+        .arg(Arg::with_name("decimal")
+             .short("d")
+             .long("decimal")
+             .conflicts_with("rational")
+             .help("Enable decimal mode. Numeric literals are parsed as exact decimal numbers"))
+        .arg(Arg::with_name("scale")
+             .long("scale")
+             .help("Decimal scale: fractional digits kept by decimal division and round, or off")
+             .takes_value(true))
+        .arg(Arg::with_name("rounding")
+             .long("rounding")
+             .help("Decimal rounding mode: half-even (default), half-up or truncate")
+             .takes_value(true))
+        .arg(Arg::with_name("word")
+             .short("w")
+             .long("word")
+             .help("Integer word size for programmer mode: u8, u16, u32, u64, i8, i16, i32 or i64")
+             .takes_value(true))
+        .arg(Arg::with_name("overflow")
+             .long("overflow")
+             .help("Integer overflow mode: check (default) or wrap")
+             .takes_value(true))
+        .arg(Arg::with_name("format")
+             .long("format")
+             .help("Output format settings, e.g. \"fix 2 sep\":\n\
+                    default, fix <n>, sig <n>, sci <n> or eng <n> for numbers,\n\
+                    dec, hex, bin or oct for integers, sep or nosep for thousands separators")
+             .takes_value(true))
+        .arg(Arg::with_name("strict")
+             .short("s")
+             .long("strict")
+             .help("Enable strict numeric mode. Operations producing NaN or infinity are domain errors"))
+        .arg(Arg::with_name("truthy")
+             .short("t")
+             .long("truthy")
+             .help("Enable truthy mode. Logical ops take any value as an if condition does, e.g. and 1 true"))
+        .arg(Arg::with_name("tolerance")
+             .long("tolerance")
+             .help("Tolerance of ~= and !~=, e.g. \"rel 1e-9 abs 1e-12\" (default) for relative\n\
+                    and absolute tolerances, or \"ulp 4\" for a distance in representable doubles")
+             .takes_value(true))
+        .arg(Arg::with_name("angle")
+             .long("angle")
+             .help("Angle mode for trig ops: rad (default), deg or grad")
+             .takes_value(true))
+        .arg(Arg::with_name("now")
+             .short("n")
+             .long("now")
+             .help("Pin now to an ISO-8601 date or datetime (e.g. 2024-03-15T10:30), for repeatable results")
+             .takes_value(true))
         .arg(Arg::with_name("expr")
              .short("e")
              .long("expr")
@@ -55,6 +122,42 @@ fn parse_args() -> Arguments {
         force_int: pargs.is_present("force_int"),
         quiet: pargs.is_present("quiet"),
         batch: pargs.is_present("batch"),
+        rational: pargs.is_present("rational"),
+        decimal: pargs.is_present("decimal"),
+        scale: match pargs.value_of("scale") {
+            Some(s) => String::from(s),
+            None => String::new()
+        },
+        rounding: match pargs.value_of("rounding") {
+            Some(r) => String::from(r),
+            None => String::new()
+        },
+        word: match pargs.value_of("word") {
+            Some(w) => String::from(w),
+            None => String::new()
+        },
+        overflow: match pargs.value_of("overflow") {
+            Some(o) => String::from(o),
+            None => String::new()
+        },
+        format: match pargs.value_of("format") {
+            Some(f) => String::from(f),
+            None => String::new()
+        },
+        strict: pargs.is_present("strict"),
+        truthy: pargs.is_present("truthy"),
+        tolerance: match pargs.value_of("tolerance") {
+            Some(t) => String::from(t),
+            None => String::new()
+        },
+        angle: match pargs.value_of("angle") {
+            Some(a) => String::from(a),
+            None => String::new()
+        },
+        now: match pargs.value_of("now") {
+            Some(n) => String::from(n),
+            None => String::new()
+        },
         expr: match pargs.value_of("expr") {
             Some(e) => String::from(e),
             None => String::new()
@@ -68,6 +171,36 @@ fn parse_args() -> Arguments {
 
 fn run_repl(args: &Arguments) {
     let mut repl = REPL::new(args.batch);
+    repl.set_rational(args.rational);
+    if args.decimal {
+        repl.set_decimal(true);
+    }
+    if !args.scale.is_empty() && !repl.set_decimal_option("scale", &args.scale) {
+        process::exit(1);
+    }
+    if !args.rounding.is_empty() && !repl.set_decimal_option("rounding", &args.rounding) {
+        process::exit(1);
+    }
+    if !args.word.is_empty() && !repl.set_word_option("size", &args.word) {
+        process::exit(1);
+    }
+    if !args.overflow.is_empty() && !repl.set_word_option("overflow", &args.overflow) {
+        process::exit(1);
+    }
+    if !args.format.is_empty() && !repl.set_format(&args.format) {
+        process::exit(1);
+    }
+    repl.set_strict(args.strict);
+    repl.set_truthy(args.truthy);
+    if !args.tolerance.is_empty() && !repl.set_tolerance(&args.tolerance) {
+        process::exit(1);
+    }
+    if !args.angle.is_empty() && !repl.set_angle(&args.angle) {
+        process::exit(1);
+    }
+    if !args.now.is_empty() && !repl.pin_now(&args.now) {
+        process::exit(1);
+    }
     if !args.quiet {
         repl.display_startup_msg();
     }
@@ -0,0 +1,141 @@
+use std::f64::consts;
+
+// --------------------------------------------------------------------------------
+// Angle - Unit of trig op inputs and inverse trig op outputs
+
+// Radians per degree and per gradian, split in a high and a low part for extra precision
+const RAD_PER_DEG: (f64, f64) = (0.017_453_292_519_943_295, 2.948_652_270_870_168_7e-19);
+const RAD_PER_GRAD: (f64, f64) = (0.015_707_963_267_948_967, -7.754_553_812_077_691e-19);
+
+// Degrees and gradians per radian, split the same way
+const DEG_PER_RAD: (f64, f64) = (57.295_779_513_082_32, -1.987_849_567_057_628_3e-15);
+const GRAD_PER_RAD: (f64, f64) = (63.661_977_236_758_13, 9.492_459_733_141_914e-16);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Angle {
+    #[default]
+    Radians,
+    Degrees,
+    Gradians
+}
+
+impl Angle {
+    pub fn parse(name: &str) -> Option<Angle> {
+        match name {
+            "rad" => Some(Angle::Radians),
+            "deg" => Some(Angle::Degrees),
+            "grad" => Some(Angle::Gradians),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Angle::Radians => "rad",
+            Angle::Degrees => "deg",
+            Angle::Gradians => "grad"
+        }
+    }
+
+    #[inline(always)]
+    pub fn to_radians(&self, x: f64) -> f64 {
+        match self {
+            Angle::Radians => x,
+            Angle::Degrees => scale(x, RAD_PER_DEG),
+            Angle::Gradians => scale(x, RAD_PER_GRAD)
+        }
+    }
+
+    #[inline(always)]
+    pub fn from_radians(&self, x: f64) -> f64 {
+        match self {
+            Angle::Radians => x,
+            Angle::Degrees => scale(x, DEG_PER_RAD),
+            Angle::Gradians => scale(x, GRAD_PER_RAD)
+        }
+    }
+
+    // Sine and cosine of x in this unit. Degrees and gradians are reduced to within half a
+    // right angle exactly, so multiples of a right angle give exact zeros and ones.
+    pub fn sin_cos(&self, x: f64) -> (f64, f64) {
+        let (right, per_unit) = match self {
+            Angle::Radians => return x.sin_cos(),
+            Angle::Degrees => (90.0, RAD_PER_DEG),
+            Angle::Gradians => (100.0, RAD_PER_GRAD)
+        };
+        let turn = x % (4.0 * right);
+        let quadrant = (turn / right).round();
+        let rest = turn - quadrant * right;
+
+        // Radians of the rest as hi + lo, and the first order correction for lo
+        let hi = rest * per_unit.0;
+        let lo = rest.mul_add(per_unit.0, -hi) + rest * per_unit.1;
+        let (sin, cos) = if rest.abs() == right / 2.0 {
+            // Equal magnitudes, so e.g. tan 45 is exactly 1
+            (consts::FRAC_1_SQRT_2.copysign(rest), consts::FRAC_1_SQRT_2)
+        } else {
+            let (sin, cos) = hi.sin_cos();
+            (sin + cos * lo, cos - sin * lo)
+        };
+
+        // Adding 0.0 turns negated zeros positive
+        match (quadrant as i64).rem_euclid(4) {
+            0 => (sin, cos),
+            1 => (cos, -sin + 0.0),
+            2 => (-sin + 0.0, -cos),
+            _ => (-cos, sin)
+        }
+    }
+}
+
+// x times the constant hi + lo, rounded once
+#[inline(always)]
+fn scale(x: f64, (hi, lo): (f64, f64)) -> f64 {
+    let prod = x * hi;
+    prod + (x.mul_add(hi, -prod) + x * lo)
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle_parse() {
+        assert_eq!(Angle::parse("deg"), Some(Angle::Degrees));
+        assert_eq!(Angle::parse("degrees"), None);
+        assert_eq!(Angle::Gradians.name(), "grad");
+        assert_eq!(Angle::default(), Angle::Radians);
+    }
+
+    #[test]
+    fn test_angle_convert() {
+        assert_eq!(Angle::Degrees.to_radians(180.0), consts::PI);
+        assert_eq!(Angle::Gradians.to_radians(100.0), consts::FRAC_PI_2);
+        assert_eq!(Angle::Degrees.from_radians(consts::FRAC_PI_2), 90.0);
+        assert_eq!(Angle::Gradians.from_radians(consts::PI), 200.0);
+        assert_eq!(Angle::Radians.to_radians(2.5), 2.5);
+        assert_eq!(Angle::Degrees.from_radians(consts::TAU), 360.0);
+    }
+
+    #[test]
+    fn test_angle_sin_cos() {
+        let deg = Angle::Degrees;
+        assert_eq!(deg.sin_cos(0.0), (0.0, 1.0));
+        assert_eq!(deg.sin_cos(90.0), (1.0, 0.0));
+        assert_eq!(deg.sin_cos(180.0), (0.0, -1.0));
+        assert_eq!(deg.sin_cos(-90.0), (-1.0, 0.0));
+        assert_eq!(deg.sin_cos(720.0), (0.0, 1.0));
+        assert_eq!(deg.sin_cos(30.0).0, 0.5);
+        assert_eq!(deg.sin_cos(60.0).1, 0.5);
+        assert_eq!(deg.sin_cos(210.0).0, -0.5);
+        let (sin, cos) = deg.sin_cos(45.0);
+        assert_eq!(sin / cos, 1.0);
+
+        assert_eq!(Angle::Gradians.sin_cos(100.0), (1.0, 0.0));
+        assert_eq!(Angle::Gradians.sin_cos(200.0), (0.0, -1.0));
+        assert_eq!(Angle::Radians.sin_cos(1.0), 1.0f64.sin_cos());
+        assert!(deg.sin_cos(f64::INFINITY).0.is_nan());
+    }
+}
@@ -1,73 +1,619 @@
+use crate::pcalc_angle::Angle;
+use crate::pcalc_error::ErrorKind;
+use crate::pcalc_interval::Interval;
 use crate::pcalc_keywords as keywords;
-use crate::pcalc_value::{Value, ValueResult};
+use crate::pcalc_matrix::MatrixError;
+use crate::pcalc_number_theory as ntheory;
+use crate::pcalc_special_math as special;
+use crate::pcalc_tolerance::Tolerance;
+use crate::pcalc_unary_ops::angle_from_radians;
+use crate::pcalc_uncertain::Uncertain;
+use crate::pcalc_units::UnitError;
+use crate::pcalc_value::{Value, ValueError, ValueResult, MAX_RATIONAL_BITS};
+use crate::pcalc_word as word;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{Pow, Zero};
+use std::cmp;
+
+// Largest list built by range
+const MAX_RANGE_LEN: i128 = 1_000_000;
 
 #[inline(always)]
 pub fn add(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()? + rhs.to_num()?))
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::ADD, *l, *r, i128::checked_add),
+        (Value::DateTime(dt), Value::Duration(dur)) | (Value::Duration(dur), Value::DateTime(dt)) => Ok(Value::from_datetime(dt.add(dur)?)),
+        (Value::Duration(l), Value::Duration(r)) => Ok(Value::from_duration(l.add(r)?)),
+        _ if linalg_pair(lhs, rhs) => elementwise(keywords::ADD, lhs, rhs, |x, y| x + y),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.add(&rhs.to_decimal()?)?)),
+        _ if quantity_pair(lhs, rhs) => Ok(Value::from_quantity(lhs.to_quantity()?.add(&rhs.to_quantity()?)?)),
+        _ if interval_pair(lhs, rhs) => Ok(Value::from_interval(lhs.to_interval()?.add(&rhs.to_interval()?))),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.add(&rhs.to_uncertain()?))),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()? + rhs.to_rational()?)),
+        _ if complex_pair(lhs, rhs) => Ok(Value::from_complex(lhs.to_complex()? + rhs.to_complex()?)),
+        _ => Ok(Value::from_num(lhs.to_num()? + rhs.to_num()?))
+    }
 }
 
 #[inline(always)]
 pub fn subtract(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()? - rhs.to_num()?))
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::SUBTRACT, *l, *r, i128::checked_sub),
+        (Value::DateTime(dt), Value::Duration(dur)) => Ok(Value::from_datetime(dt.sub(dur)?)),
+        (Value::DateTime(l), Value::DateTime(r)) => Ok(Value::from_duration(l.since(r))),
+        (Value::Duration(l), Value::Duration(r)) => Ok(Value::from_duration(l.sub(r)?)),
+        _ if linalg_pair(lhs, rhs) => elementwise(keywords::SUBTRACT, lhs, rhs, |x, y| x - y),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.sub(&rhs.to_decimal()?)?)),
+        _ if quantity_pair(lhs, rhs) => Ok(Value::from_quantity(lhs.to_quantity()?.sub(&rhs.to_quantity()?)?)),
+        _ if interval_pair(lhs, rhs) => Ok(Value::from_interval(lhs.to_interval()?.sub(&rhs.to_interval()?))),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.sub(&rhs.to_uncertain()?))),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()? - rhs.to_rational()?)),
+        _ if complex_pair(lhs, rhs) => Ok(Value::from_complex(lhs.to_complex()? - rhs.to_complex()?)),
+        _ => Ok(Value::from_num(lhs.to_num()? - rhs.to_num()?))
+    }
 }
 
 #[inline(always)]
 pub fn multiply(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()? * rhs.to_num()?))
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::MULTIPLY, *l, *r, int_mul),
+        (Value::Duration(dur), Value::Int(n)) | (Value::Int(n), Value::Duration(dur)) => Ok(Value::from_duration(dur.mul(*n)?)),
+        (Value::Duration(dur), num) | (num, Value::Duration(dur)) if num.is_num() => Ok(Value::from_duration(dur.scale(num.to_num()?)?)),
+        // Matrix products, with vectors as columns on the right and rows on the left
+        (Value::Matrix(l), Value::Matrix(r)) => Ok(Value::from_matrix(l.mul(r)?)),
+        (Value::Matrix(l), Value::Vector(r)) => Ok(Value::from_vector(l.mul_vec(r)?)),
+        (Value::Vector(l), Value::Matrix(r)) => Ok(Value::from_vector(r.vec_mul(l)?)),
+        _ if linalg_pair(lhs, rhs) => elementwise(keywords::MULTIPLY, lhs, rhs, |x, y| x * y),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.mul(&rhs.to_decimal()?)?)),
+        _ if quantity_pair(lhs, rhs) => Ok(Value::from_quantity(lhs.to_quantity()?.mul(&rhs.to_quantity()?)?)),
+        _ if interval_pair(lhs, rhs) => Ok(Value::from_interval(lhs.to_interval()?.mul(&rhs.to_interval()?))),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.mul(&rhs.to_uncertain()?))),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()? * rhs.to_rational()?)),
+        _ if complex_pair(lhs, rhs) => Ok(Value::from_complex(lhs.to_complex()? * rhs.to_complex()?)),
+        _ => Ok(Value::from_num(lhs.to_num()? * rhs.to_num()?))
+    }
 }
 
 #[inline(always)]
 pub fn divide(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()? / rhs.to_num()?))
+    match (lhs, rhs) {
+        // Stay integral only when the division is exact
+        (Value::Int(l), Value::Int(r)) if int_divides(*l, *r) => int_result(keywords::DIVIDE, *l, *r, i128::checked_div),
+        (Value::Duration(l), Value::Duration(r)) => Ok(Value::from_num(l.ratio(r))),
+        (Value::Duration(dur), _) if rhs.is_num() => Ok(Value::from_duration(dur.div(rhs.to_num()?)?)),
+        _ if linalg_pair(lhs, rhs) => elementwise(keywords::DIVIDE, lhs, rhs, |x, y| x / y),
+        // Decimal division applies the decimal scale and rounding mode
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.div(&rhs.to_decimal()?)?)),
+        _ if quantity_pair(lhs, rhs) => Ok(Value::from_quantity(lhs.to_quantity()?.div(&rhs.to_quantity()?)?)),
+        _ if interval_pair(lhs, rhs) => Ok(Value::from_interval(lhs.to_interval()?.div(&rhs.to_interval()?)?)),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.div(&rhs.to_uncertain()?))),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()? / nonzero(rhs.to_rational()?)?)),
+        _ if complex_pair(lhs, rhs) => Ok(Value::from_complex(lhs.to_complex()? / rhs.to_complex()?)),
+        _ => Ok(Value::from_num(lhs.to_num()? / rhs.to_num()?))
+    }
 }
 
 #[inline(always)]
 pub fn remainder(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()? % rhs.to_num()?))
+    match (lhs, rhs) {
+        (Value::Int(_), Value::Int(0)) => Err(ValueError::new(ErrorKind::Domain, "Division by zero")),
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::REMAINDER, *l, *r, i128::checked_rem),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.rem(&rhs.to_decimal()?)?)),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.rem(&rhs.to_uncertain()?)?)),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()? % nonzero(rhs.to_rational()?)?)),
+        _ => Ok(Value::from_num(lhs.to_num()? % rhs.to_num()?))
+    }
 }
 
 #[inline(always)]
 pub fn power(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(f64::powf(lhs.to_num()?, rhs.to_num()?)))
+    match (lhs, rhs) {
+        // Negative exponents produce fractions, so leave those to f64
+        (Value::Int(l), Value::Int(r)) if word::widen(*r) >= 0 => int_result(keywords::POWER, *l, *r, int_pow),
+        // Decimals stay exact for integral exponents only
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.powi(rhs.to_int()?)?)),
+        // Units are raised to small integral exponents only
+        (Value::Quantity(q), _) => match i8::try_from(rhs.to_int()?) {
+            Ok(exp) => Ok(Value::from_quantity(q.powi(exp)?)),
+            Err(_) => Err(ValueError::from_string(ErrorKind::Domain, format!("Exponent too large for unit - '{}'", rhs)))
+        },
+        _ if interval_pair(lhs, rhs) => {
+            let base = lhs.to_interval()?;
+            match rhs.to_int().ok().and_then(|e| i32::try_from(e).ok()) {
+                Some(exp) => Ok(Value::from_interval(base.powi(exp)?)),
+                None => Ok(Value::from_interval(base.pow(&rhs.to_interval()?)?))
+            }
+        }
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.pow(&rhs.to_uncertain()?)?)),
+        // Rationals stay exact for integral exponents
+        _ if rational_pair(lhs, rhs) && rhs.to_int().is_ok_and(|e| i32::try_from(e).is_ok()) => {
+            let base = lhs.to_rational()?;
+            let exp = rhs.to_int()? as i32;
+            if exp < 0 && base.is_zero() {
+                return Err(ValueError::new(ErrorKind::Domain, "Division by zero"));
+            }
+            // 0, 1 and -1 stay small for any exponent
+            let bits = cmp::max(base.numer().bits(), base.denom().bits());
+            if bits > 1 && u64::from(exp.unsigned_abs()).saturating_mul(bits) > MAX_RATIONAL_BITS {
+                return Err(ValueError::from_string(
+                    ErrorKind::Domain,
+                    format!("Rational overflow - '{} {} {}'", keywords::POWER, lhs, rhs)
+                ));
+            }
+            Ok(Value::from_rational(base.pow(exp)))
+        }
+        _ if complex_pair(lhs, rhs) => match rhs.to_int().ok().and_then(|e| i32::try_from(e).ok()) {
+            Some(exp) => Ok(Value::from_complex(lhs.to_complex()?.powi(exp))),
+            None => Ok(Value::from_complex(lhs.to_complex()?.powc(rhs.to_complex()?)))
+        },
+        _ => {
+            let (base, exp) = (lhs.to_num()?, rhs.to_num()?);
+            if base < 0.0 && exp.is_finite() && exp.fract() != 0.0 {
+                // Fractional power of a negative number has a complex principal value
+                Ok(Value::from_complex(Complex64::new(base, 0.0).powf(exp)))
+            } else {
+                Ok(Value::from_num(f64::powf(base, exp)))
+            }
+        }
+    }
 }
 
 #[inline(always)]
 pub fn maximum(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()?.max(rhs.to_num()?)))
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => Ok(Value::from_int(cmp::max_by_key(*l, *r, |i| word::widen(*i)))),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.max(rhs.to_decimal()?))),
+        _ if quantity_pair(lhs, rhs) => quantity_pick(lhs, rhs, cmp::Ordering::Greater),
+        _ if uncertain_pair(lhs, rhs) => uncertain_pick(lhs, rhs, cmp::Ordering::Greater),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()?.max(rhs.to_rational()?))),
+        _ => Ok(Value::from_num(lhs.to_num()?.max(rhs.to_num()?)))
+    }
 }
 
 #[inline(always)]
 pub fn minimum(lhs: &Value, rhs: &Value) -> ValueResult {
-    Ok(Value::from_num(lhs.to_num()?.min(rhs.to_num()?)))
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => Ok(Value::from_int(cmp::min_by_key(*l, *r, |i| word::widen(*i)))),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.min(rhs.to_decimal()?))),
+        _ if quantity_pair(lhs, rhs) => quantity_pick(lhs, rhs, cmp::Ordering::Less),
+        _ if uncertain_pair(lhs, rhs) => uncertain_pick(lhs, rhs, cmp::Ordering::Less),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational(lhs.to_rational()?.min(rhs.to_rational()?))),
+        _ => Ok(Value::from_num(lhs.to_num()?.min(rhs.to_num()?)))
+    }
+}
+
+// Angle of the point (x, y), with y first, e.g. atan2 1 -1 is 3pi/4
+#[inline(always)]
+pub fn atan2(lhs: &Value, rhs: &Value) -> ValueResult {
+    if uncertain_pair(lhs, rhs) {
+        return uncertain_binary(keywords::ATAN2, lhs, rhs, f64::atan2, |y, x| {
+            let r2 = x * x + y * y;
+            (x / r2, -y / r2)
+        });
+    }
+    Ok(Value::from_num(lhs.to_num()?.atan2(rhs.to_num()?)))
+}
+
+// Atan2 in degrees or gradians
+#[inline(always)]
+pub fn angle_atan2(lhs: &Value, rhs: &Value, angle: Angle) -> ValueResult {
+    angle_from_radians(&atan2(lhs, rhs)?, angle)
+}
+
+#[inline(always)]
+pub fn hypot(lhs: &Value, rhs: &Value) -> ValueResult {
+    if uncertain_pair(lhs, rhs) {
+        return uncertain_binary(keywords::HYPOT, lhs, rhs, f64::hypot, |x, y| {
+            let h = x.hypot(y);
+            (x / h, y / h)
+        });
+    }
+    Ok(Value::from_num(lhs.to_num()?.hypot(rhs.to_num()?)))
+}
+
+// Logarithm of lhs in base rhs, e.g. logb 8 2
+#[inline(always)]
+pub fn log_base(lhs: &Value, rhs: &Value) -> ValueResult {
+    if uncertain_pair(lhs, rhs) {
+        return uncertain_binary(keywords::LOGB, lhs, rhs, log_in_base, |x, base| {
+            let ln_base = base.ln();
+            (1.0 / (x * ln_base), -x.ln() / (base * ln_base * ln_base))
+        });
+    }
+    Ok(Value::from_num(log_in_base(lhs.to_num()?, rhs.to_num()?)))
+}
+
+// The rhs-th root of lhs, real for negative lhs and odd rhs, e.g. root -27 3 is -3
+#[inline(always)]
+pub fn root(lhs: &Value, rhs: &Value) -> ValueResult {
+    if uncertain_pair(lhs, rhs) {
+        return uncertain_binary(keywords::ROOT, lhs, rhs, real_root, |x, n| {
+            let r = real_root(x, n);
+            (r / (n * x), -r * x.abs().ln() / (n * n))
+        });
+    }
+    let (x, n) = (lhs.to_num()?, rhs.to_num()?);
+    if n == 0.0 {
+        return Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Invalid root - '{} {} {}'", keywords::ROOT, lhs, rhs)
+        ));
+    }
+    let result = real_root(x, n);
+    if result.is_nan() && !x.is_nan() {
+        // Even and fractional roots of negative numbers have complex principal values
+        return power(lhs, &Value::from_num(1.0 / n));
+    }
+    // Integral roots of integers stay integral when exact
+    if let (Value::Int(i), Value::Int(exp)) = (lhs, rhs) {
+        if let Ok(r) = u32::try_from(*exp) {
+            let candidate = result.round() as i128;
+            if candidate.checked_pow(r) == Some(word::widen(*i)) {
+                if let Some(i) = word::fit(candidate) {
+                    return Ok(Value::from_int(i));
+                }
+            }
+        }
+    }
+    Ok(Value::from_num(result))
+}
+
+// Quotient rounded toward negative infinity, e.g. idiv -7 2 is -4
+#[inline(always)]
+pub fn floor_divide(lhs: &Value, rhs: &Value) -> ValueResult {
+    match (lhs, rhs) {
+        (Value::Int(_), Value::Int(0)) => Err(ValueError::new(ErrorKind::Domain, "Division by zero")),
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::IDIV, *l, *r, int_floor_div),
+        _ if decimal_pair(lhs, rhs) => Ok(Value::from_decimal(lhs.to_decimal()?.div(&rhs.to_decimal()?)?.floor())),
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.floor_div(&rhs.to_uncertain()?)?)),
+        _ if rational_pair(lhs, rhs) => Ok(Value::from_rational((lhs.to_rational()? / nonzero(rhs.to_rational()?)?).floor())),
+        _ => Ok(Value::from_num((lhs.to_num()? / rhs.to_num()?).floor()))
+    }
+}
+
+// Remainder with the sign of rhs, e.g. mod -7 3 is 2, where % -7 3 is -1
+#[inline(always)]
+pub fn modulo(lhs: &Value, rhs: &Value) -> ValueResult {
+    match (lhs, rhs) {
+        (Value::Int(_), Value::Int(0)) => Err(ValueError::new(ErrorKind::Domain, "Division by zero")),
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::MOD, *l, *r, int_floor_mod),
+        _ if decimal_pair(lhs, rhs) => {
+            let (l, r) = (lhs.to_decimal()?, rhs.to_decimal()?);
+            let rem = l.rem(&r)?;
+            if !rem.is_zero() && rem.is_negative() != r.is_negative() {
+                Ok(Value::from_decimal(rem.add(&r)?))
+            } else {
+                Ok(Value::from_decimal(rem))
+            }
+        }
+        _ if uncertain_pair(lhs, rhs) => Ok(Value::from_uncertain(lhs.to_uncertain()?.modulo(&rhs.to_uncertain()?)?)),
+        _ if rational_pair(lhs, rhs) => {
+            let (l, r) = (lhs.to_rational()?, nonzero(rhs.to_rational()?)?);
+            let quot = (&l / &r).floor();
+            Ok(Value::from_rational(l - r * quot))
+        }
+        _ => {
+            let (l, r) = (lhs.to_num()?, rhs.to_num()?);
+            let rem = l % r;
+            if rem != 0.0 && (rem < 0.0) != (r < 0.0) {
+                Ok(Value::from_num(rem + r))
+            } else {
+                Ok(Value::from_num(rem))
+            }
+        }
+    }
+}
+
+// Magnitude of lhs with the sign of rhs
+#[inline(always)]
+pub fn copysign(lhs: &Value, rhs: &Value) -> ValueResult {
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => int_result(
+            keywords::COPYSIGN,
+            *l,
+            *r,
+            |l, r| {
+                if r < 0 {
+                    l.checked_abs().map(|a| -a)
+                } else {
+                    l.checked_abs()
+                }
+            }
+        ),
+        // The sign flip makes the slope -1 when the signs differ
+        _ if uncertain_pair(lhs, rhs) => uncertain_binary(keywords::COPYSIGN, lhs, rhs, f64::copysign, |x, y| (x.signum() * 1.0_f64.copysign(y), 0.0)),
+        _ => Ok(Value::from_num(lhs.to_num()?.copysign(rhs.to_num()?)))
+    }
+}
+
+// Positive difference, lhs - rhs when lhs is greater, else 0
+#[inline(always)]
+pub fn positive_difference(lhs: &Value, rhs: &Value) -> ValueResult {
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => int_result(keywords::FDIM, *l, *r, |l, r| if l > r { l.checked_sub(r) } else { Some(0) }),
+        _ if uncertain_pair(lhs, rhs) => uncertain_binary(
+            keywords::FDIM,
+            lhs,
+            rhs,
+            |x, y| if x > y { x - y } else { 0.0 },
+            |x, y| if x > y { (1.0, -1.0) } else { (0.0, 0.0) }
+        ),
+        _ => {
+            let (l, r) = (lhs.to_num()?, rhs.to_num()?);
+            match l.partial_cmp(&r) {
+                Some(cmp::Ordering::Greater) => Ok(Value::from_num(l - r)),
+                Some(_) => Ok(Value::from_num(0.0)),
+                None => Ok(Value::from_num(f64::NAN))
+            }
+        }
+    }
+}
+
+// Greatest common divisor, which is never negative
+#[inline(always)]
+pub fn gcd(lhs: &Value, rhs: &Value) -> ValueResult {
+    int_result(keywords::GCD, lhs.to_int()?, rhs.to_int()?, |l, r| {
+        i128::try_from(ntheory::gcd(l.unsigned_abs(), r.unsigned_abs())).ok()
+    })
+}
+
+// Least common multiple, which is never negative
+#[inline(always)]
+pub fn lcm(lhs: &Value, rhs: &Value) -> ValueResult {
+    int_result(keywords::LCM, lhs.to_int()?, rhs.to_int()?, |l, r| {
+        ntheory::lcm(l.unsigned_abs(), r.unsigned_abs()).and_then(|m| i128::try_from(m).ok())
+    })
+}
+
+// Binomial coefficient, e.g. choose 5 2 is 10
+#[inline(always)]
+pub fn choose(lhs: &Value, rhs: &Value) -> ValueResult {
+    let (n, k) = natural_operands(keywords::CHOOSE, lhs, rhs)?;
+    int_result(keywords::CHOOSE, n, k, |n, k| {
+        ntheory::choose(n as u128, k as u128).and_then(|c| i128::try_from(c).ok())
+    })
+}
+
+// Arrangements of rhs items out of lhs, e.g. perm 5 2 is 20
+#[inline(always)]
+pub fn permutations(lhs: &Value, rhs: &Value) -> ValueResult {
+    let (n, k) = natural_operands(keywords::PERM, lhs, rhs)?;
+    int_result(keywords::PERM, n, k, |n, k| {
+        ntheory::permutations(n as u128, k as u128).and_then(|p| i128::try_from(p).ok())
+    })
+}
+
+// Inverse of lhs modulo rhs, e.g. modinv 3 11 is 4
+#[inline(always)]
+pub fn modular_inverse(lhs: &Value, rhs: &Value) -> ValueResult {
+    let m = modulus_operand(keywords::MODINV, rhs)?;
+    let a = residue(lhs, m)?;
+    match ntheory::mod_inverse(a, m) {
+        Some(inv) => Ok(Value::from_int(word::from_bits(inv))),
+        None => Err(no_inverse(lhs, m))
+    }
+}
+
+// base^exp modulo m, with negative exponents raising the inverse of base, e.g. modpow 4 13 497
+pub fn modular_power(base: &Value, exp: &Value, modulus: &Value) -> ValueResult {
+    let m = modulus_operand(keywords::MODPOW, modulus)?;
+    let (b, e) = (residue(base, m)?, word::widen(exp.to_int()?));
+    let b = if e < 0 {
+        ntheory::mod_inverse(b, m).ok_or_else(|| no_inverse(base, m))?
+    } else {
+        b
+    };
+    Ok(Value::from_int(word::from_bits(ntheory::mod_pow(b, e.unsigned_abs() as u64, m))))
+}
+
+#[inline(always)]
+pub fn beta(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_num(special::beta(lhs.to_num()?, rhs.to_num()?)))
+}
+
+// Regularized lower incomplete gamma P(a, x), with a first
+#[inline(always)]
+pub fn gamma_lower(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_num(special::gamma_p(gamma_shape(keywords::GAMMAINC, lhs)?, rhs.to_num()?)))
+}
+
+// Regularized upper incomplete gamma Q(a, x), with a first
+#[inline(always)]
+pub fn gamma_upper(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_num(special::gamma_q(gamma_shape(keywords::GAMMAINCC, lhs)?, rhs.to_num()?)))
+}
+
+#[inline(always)]
+pub fn rectangular(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_complex(Complex64::new(lhs.to_num()?, rhs.to_num()?)))
+}
+
+#[inline(always)]
+pub fn polar(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_complex(Complex64::from_polar(lhs.to_num()?, rhs.to_num()?)))
+}
+
+#[inline(always)]
+pub fn interval(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_interval(Interval::new(lhs.to_num()?, rhs.to_num()?)?))
+}
+
+// Value lhs with standard uncertainty rhs, e.g. pm 9.81 0.02
+#[inline(always)]
+pub fn plus_minus(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_uncertain(Uncertain::new(lhs.to_num()?, rhs.to_num()?)?))
+}
+
+// Convert lhs to the unit of rhs, e.g. to 5_km _mi
+#[inline(always)]
+pub fn convert(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_quantity(lhs.to_quantity()?.convert(&rhs.to_quantity()?)?))
+}
+
+#[inline(always)]
+pub fn concat(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_string(format!("{}{}", lhs.to_str()?, rhs.to_str()?)))
+}
+
+#[inline(always)]
+pub fn nth(lhs: &Value, rhs: &Value) -> ValueResult {
+    // Vector components and matrix rows
+    match lhs {
+        Value::Vector(vec) => return Ok(Value::from_num(vec.items()[checked_index(rhs, vec.len())?])),
+        Value::Matrix(mat) => return Ok(Value::from_vector(mat.row(checked_index(rhs, mat.rows())?))),
+        _ => {}
+    }
+    let items = lhs.to_list()?;
+    let index = rhs.to_int()?;
+    match usize::try_from(index).ok().and_then(|idx| items.get(idx)) {
+        Some(item) => Ok(item.clone()),
+        None => Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Index {} out of range for list of length {}", index, items.len())
+        ))
+    }
+}
+
+#[inline(always)]
+pub fn push(lhs: &Value, rhs: &Value) -> ValueResult {
+    let mut items = lhs.to_list()?.to_vec();
+    items.push(rhs.clone());
+    Ok(Value::from_list(items))
+}
+
+// Integers from lhs up to, but excluding, rhs.
+#[inline(always)]
+pub fn range(lhs: &Value, rhs: &Value) -> ValueResult {
+    let (start, end) = (lhs.to_int()?, rhs.to_int()?);
+    if (end as i128) - (start as i128) > MAX_RANGE_LEN {
+        return Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Range too large - 'range {} {}'", start, end)
+        ));
+    }
+    Ok(Value::from_list((start..end).map(Value::from_int).collect()))
+}
+
+#[inline(always)]
+pub fn dot(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_num(lhs.to_vector()?.dot(&rhs.to_vector()?)?))
+}
+
+#[inline(always)]
+pub fn cross(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_vector(lhs.to_vector()?.cross(&rhs.to_vector()?)?))
+}
+
+// x such that lhs * x is rhs
+#[inline(always)]
+pub fn solve(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_vector(lhs.to_matrix()?.solve(&rhs.to_vector()?)?))
+}
+
+// Fallback rhs when lhs is none
+#[inline(always)]
+pub fn default(lhs: &Value, rhs: &Value) -> ValueResult {
+    if lhs.is_none() {
+        Ok(rhs.clone())
+    } else {
+        Ok(lhs.clone())
+    }
+}
+
+// Calendar days from lhs to rhs
+#[inline(always)]
+pub fn days_between(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_int(lhs.to_datetime()?.days_between(&rhs.to_datetime()?)))
+}
+
+#[inline(always)]
+pub fn add_months(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_datetime(lhs.to_datetime()?.add_months(rhs.to_int()?)?))
 }
 
 #[inline(always)]
 pub fn equal(lhs: &Value, rhs: &Value) -> ValueResult {
+    if unknown_pair(lhs, rhs) {
+        return Ok(Value::Unknown);
+    }
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::equal);
+    }
     Ok(Value::from_bool(lhs == rhs))
 }
 
 #[inline(always)]
 pub fn not_equal(lhs: &Value, rhs: &Value) -> ValueResult {
+    if unknown_pair(lhs, rhs) {
+        return Ok(Value::Unknown);
+    }
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::not_equal);
+    }
     Ok(Value::from_bool(lhs != rhs))
 }
 
+// Numbers are equal within the tolerance: complex numbers part by part, quantities by SI
+// value (with matching units), uncertain values by nominal value, and vectors and matrices of
+// the same shape component by component. Other values are only equal when equal.
+#[inline(always)]
+pub fn approx_equal(lhs: &Value, rhs: &Value, tol: &Tolerance) -> ValueResult {
+    match approx_numbers(lhs, rhs, tol)? {
+        Some(eq) => Ok(Value::from_bool(eq)),
+        None => equal(lhs, rhs)
+    }
+}
+
+#[inline(always)]
+pub fn not_approx_equal(lhs: &Value, rhs: &Value, tol: &Tolerance) -> ValueResult {
+    match approx_numbers(lhs, rhs, tol)? {
+        Some(eq) => Ok(Value::from_bool(!eq)),
+        None => not_equal(lhs, rhs)
+    }
+}
+
 #[inline(always)]
 pub fn less(lhs: &Value, rhs: &Value) -> ValueResult {
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::less);
+    }
     Ok(Value::from_bool(lhs < rhs))
 }
 
 #[inline(always)]
 pub fn less_equal(lhs: &Value, rhs: &Value) -> ValueResult {
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::less_equal);
+    }
     Ok(Value::from_bool(lhs <= rhs))
 }
 
 #[inline(always)]
 pub fn greater(lhs: &Value, rhs: &Value) -> ValueResult {
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::greater);
+    }
     Ok(Value::from_bool(lhs > rhs))
 }
 
 #[inline(always)]
 pub fn greater_equal(lhs: &Value, rhs: &Value) -> ValueResult {
+    decimal_check(lhs, rhs)?;
+    unit_check(lhs, rhs)?;
+    if interval_pair(lhs, rhs) {
+        return interval_compare(lhs, rhs, Interval::greater_equal);
+    }
     Ok(Value::from_bool(lhs >= rhs))
 }
 
@@ -81,6 +627,415 @@ pub fn logical_or(lhs: &Value, rhs: &Value) -> ValueResult {
     Ok(Value::from_bool(lhs.to_bool()? || rhs.to_bool()?))
 }
 
+#[inline(always)]
+pub fn logical_xor(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_bool(lhs.to_bool()? != rhs.to_bool()?))
+}
+
+#[inline(always)]
+pub fn logical_nand(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_bool(!(lhs.to_bool()? && rhs.to_bool()?)))
+}
+
+#[inline(always)]
+pub fn logical_nor(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_bool(!(lhs.to_bool()? || rhs.to_bool()?)))
+}
+
+#[inline(always)]
+pub fn logical_implies(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_bool(!lhs.to_bool()? || rhs.to_bool()?))
+}
+
+// Result of a logical op decided by its left operand alone, e.g. and false is false
+// whatever the right operand. None when the right operand is needed.
+#[inline(always)]
+pub fn logical_short_circuit(name: &str, lhs: bool) -> Option<bool> {
+    match (name, lhs) {
+        (keywords::AND, false) => Some(false),
+        (keywords::NAND, false) => Some(true),
+        (keywords::OR, true) => Some(true),
+        (keywords::NOR, true) => Some(false),
+        (keywords::IMPLIES, false) => Some(true),
+        _ => None
+    }
+}
+
+#[inline(always)]
+pub fn is_logical(name: &str) -> bool {
+    matches!(
+        name,
+        keywords::AND | keywords::OR | keywords::XOR | keywords::NAND | keywords::NOR | keywords::IMPLIES
+    )
+}
+
+// Bitwise operations work on the bit patterns of the current word, and always wrap
+
+#[inline(always)]
+pub fn bit_and(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_int(word::from_bits(word::pattern(lhs.to_int()?) & word::pattern(rhs.to_int()?))))
+}
+
+#[inline(always)]
+pub fn bit_or(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_int(word::from_bits(word::pattern(lhs.to_int()?) | word::pattern(rhs.to_int()?))))
+}
+
+#[inline(always)]
+pub fn bit_xor(lhs: &Value, rhs: &Value) -> ValueResult {
+    Ok(Value::from_int(word::from_bits(word::pattern(lhs.to_int()?) ^ word::pattern(rhs.to_int()?))))
+}
+
+#[inline(always)]
+pub fn shift_left(lhs: &Value, rhs: &Value) -> ValueResult {
+    let bits = word::pattern(lhs.to_int()?);
+    Ok(Value::from_int(word::from_bits(bits.checked_shl(shift_count(rhs)?).unwrap_or(0))))
+}
+
+// Arithmetic shift for signed words, and logical shift for unsigned words
+#[inline(always)]
+pub fn shift_right(lhs: &Value, rhs: &Value) -> ValueResult {
+    let size = word::context().word();
+    let bits = word::pattern(lhs.to_int()?);
+    let count = shift_count(rhs)?;
+    if size.is_signed() {
+        Ok(Value::from_int(size.from_bits(bits) >> count.min(63)))
+    } else {
+        Ok(Value::from_int(size.from_bits(bits.checked_shr(count).unwrap_or(0))))
+    }
+}
+
+#[inline(always)]
+pub fn rotate_left(lhs: &Value, rhs: &Value) -> ValueResult {
+    rotate(lhs, shift_count(rhs)?)
+}
+
+#[inline(always)]
+pub fn rotate_right(lhs: &Value, rhs: &Value) -> ValueResult {
+    let bits = word::context().word().bits();
+    rotate(lhs, bits - shift_count(rhs)? % bits)
+}
+
+// Exact operands with at least one rational, so the result can stay rational.
+#[inline(always)]
+fn rational_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_exact() && rhs.is_exact() && (lhs.is_rational() || rhs.is_rational())
+}
+
+// Approximate equality of numeric values, or None for other values
+#[inline(always)]
+fn approx_numbers(lhs: &Value, rhs: &Value, tol: &Tolerance) -> Result<Option<bool>, ValueError> {
+    let all_approx = |l: &[f64], r: &[f64]| l.len() == r.len() && l.iter().zip(r).all(|(x, y)| tol.approx_eq(*x, *y));
+    match (lhs, rhs) {
+        _ if lhs.is_num() && rhs.is_num() => Ok(Some(tol.approx_eq(lhs.to_num()?, rhs.to_num()?))),
+        (Value::Vector(l), Value::Vector(r)) => Ok(Some(all_approx(l.items(), r.items()))),
+        (Value::Matrix(l), Value::Matrix(r)) => {
+            let same_shape = l.rows() == r.rows() && l.cols() == r.cols();
+            Ok(Some(same_shape && (0..l.rows()).all(|row| all_approx(l.row(row).items(), r.row(row).items()))))
+        }
+        _ if quantity_pair(lhs, rhs) => match (lhs.to_quantity(), rhs.to_quantity()) {
+            (Ok(l), Ok(r)) if l.dim() != r.dim() => Err(UnitError::incompatible(&l, &r)),
+            (Ok(l), Ok(r)) => Ok(Some(tol.approx_eq(l.value(), r.value()))),
+            _ => Ok(None)
+        },
+        _ if uncertain_pair(lhs, rhs) => match (lhs.to_uncertain(), rhs.to_uncertain()) {
+            (Ok(l), Ok(r)) => Ok(Some(tol.approx_eq(l.value(), r.value()))),
+            _ => Ok(None)
+        },
+        _ if complex_pair(lhs, rhs) && !decimal_pair(lhs, rhs) => {
+            let (l, r) = (lhs.to_complex()?, rhs.to_complex()?);
+            Ok(Some(tol.approx_eq(l.re, r.re) && tol.approx_eq(l.im, r.im)))
+        }
+        _ => Ok(None)
+    }
+}
+
+#[inline(always)]
+fn complex_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_complex() || rhs.is_complex()
+}
+
+#[inline(always)]
+fn log_in_base(x: f64, base: f64) -> f64 {
+    match base {
+        // Exact for powers of the common bases
+        2.0 => x.log2(),
+        10.0 => x.log10(),
+        _ => x.ln() / base.ln()
+    }
+}
+
+// Real n-th root, NaN for a zero n and for even or fractional roots of negative x
+#[inline(always)]
+fn real_root(x: f64, n: f64) -> f64 {
+    let odd = n.fract() == 0.0 && n % 2.0 != 0.0;
+    if n == 0.0 || (x < 0.0 && !odd) {
+        return f64::NAN;
+    }
+    match n {
+        2.0 => x.sqrt(),
+        3.0 => x.cbrt(),
+        _ => x.signum() * x.abs().powf(1.0 / n)
+    }
+}
+
+#[inline(always)]
+fn nonzero(divisor: BigRational) -> Result<BigRational, ValueError> {
+    if divisor.is_zero() {
+        Err(ValueError::new(ErrorKind::Domain, "Division by zero"))
+    } else {
+        Ok(divisor)
+    }
+}
+
+#[inline(always)]
+fn decimal_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_decimal() || rhs.is_decimal()
+}
+
+// Decimals only compare with decimals and integers; floats and rationals need an explicit conversion.
+#[inline(always)]
+fn decimal_check(lhs: &Value, rhs: &Value) -> Result<(), ValueError> {
+    if decimal_pair(lhs, rhs) {
+        for value in [lhs, rhs] {
+            if matches!(value, Value::Num(_) | Value::Rational(_)) {
+                value.to_decimal()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Quantities only compare with quantities of the same dimension, like + and -
+#[inline(always)]
+fn unit_check(lhs: &Value, rhs: &Value) -> Result<(), ValueError> {
+    if quantity_pair(lhs, rhs) {
+        if let (Ok(l), Ok(r)) = (lhs.to_quantity(), rhs.to_quantity()) {
+            if l.dim() != r.dim() {
+                return Err(UnitError::incompatible(&l, &r));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn linalg_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_vector() || lhs.is_matrix() || rhs.is_vector() || rhs.is_matrix()
+}
+
+// Element-wise ftn of equal shape vectors or matrices, applying real operands to every element.
+#[inline(always)]
+fn elementwise(op: &str, lhs: &Value, rhs: &Value, ftn: fn(f64, f64) -> f64) -> ValueResult {
+    match (lhs, rhs) {
+        (Value::Vector(l), Value::Vector(r)) => Ok(Value::from_vector(l.zip(op, r, ftn)?)),
+        (Value::Matrix(l), Value::Matrix(r)) => Ok(Value::from_matrix(l.zip(op, r, ftn)?)),
+        (Value::Vector(v), Value::Matrix(m)) => Err(MatrixError::shape_mismatch(op, &v.shape(), &m.shape())),
+        (Value::Matrix(m), Value::Vector(v)) => Err(MatrixError::shape_mismatch(op, &m.shape(), &v.shape())),
+        (Value::Vector(l), _) => {
+            let y = rhs.to_num()?;
+            Ok(Value::from_vector(l.map(|x| ftn(x, y))))
+        }
+        (Value::Matrix(l), _) => {
+            let y = rhs.to_num()?;
+            Ok(Value::from_matrix(l.map(|x| ftn(x, y))))
+        }
+        (_, Value::Vector(r)) => {
+            let x = lhs.to_num()?;
+            Ok(Value::from_vector(r.map(|y| ftn(x, y))))
+        }
+        (_, Value::Matrix(r)) => {
+            let x = lhs.to_num()?;
+            Ok(Value::from_matrix(r.map(|y| ftn(x, y))))
+        }
+        _ => Ok(Value::from_num(ftn(lhs.to_num()?, rhs.to_num()?)))
+    }
+}
+
+#[inline(always)]
+fn checked_index(index: &Value, len: usize) -> Result<usize, ValueError> {
+    let idx = index.to_int()?;
+    match usize::try_from(idx) {
+        Ok(i) if i < len => Ok(i),
+        _ => Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Index {} out of range for length {}", idx, len)
+        ))
+    }
+}
+
+#[inline(always)]
+fn quantity_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_quantity() || rhs.is_quantity()
+}
+
+#[inline(always)]
+fn interval_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_interval() || rhs.is_interval()
+}
+
+#[inline(always)]
+fn uncertain_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_uncertain() || rhs.is_uncertain()
+}
+
+// Apply ftn to the nominal values, propagating uncertainty through its partial derivatives deriv
+#[inline(always)]
+fn uncertain_binary(op: &str, lhs: &Value, rhs: &Value, ftn: fn(f64, f64) -> f64, deriv: fn(f64, f64) -> (f64, f64)) -> ValueResult {
+    Ok(Value::from_uncertain(lhs.to_uncertain()?.map2(&rhs.to_uncertain()?, op, ftn, deriv)?))
+}
+
+// Pick lhs when its nominal value is preferred to rhs, else rhs.
+#[inline(always)]
+fn uncertain_pick(lhs: &Value, rhs: &Value, preferred: cmp::Ordering) -> ValueResult {
+    let (l, r) = (lhs.to_uncertain()?, rhs.to_uncertain()?);
+    match l.partial_cmp(&r) {
+        Some(ord) if ord == preferred || ord == cmp::Ordering::Equal => Ok(Value::from_uncertain(l)),
+        _ => Ok(Value::from_uncertain(r))
+    }
+}
+
+// Whether unknown is equal to anything is itself unknown
+#[inline(always)]
+fn unknown_pair(lhs: &Value, rhs: &Value) -> bool {
+    lhs.is_unknown() || rhs.is_unknown()
+}
+
+// true, false or unknown, when the answer depends on the points chosen from overlapping intervals
+#[inline(always)]
+fn interval_compare(lhs: &Value, rhs: &Value, cmp: fn(&Interval, &Interval) -> Option<bool>) -> ValueResult {
+    Ok(Value::from_truth(cmp(&lhs.to_interval()?, &rhs.to_interval()?)))
+}
+
+// Pick lhs when it compares as preferred to rhs, else rhs; units must be compatible.
+#[inline(always)]
+fn quantity_pick(lhs: &Value, rhs: &Value, preferred: cmp::Ordering) -> ValueResult {
+    let (l, r) = (lhs.to_quantity()?, rhs.to_quantity()?);
+    match l.partial_cmp(&r) {
+        Some(ord) if ord == preferred || ord == cmp::Ordering::Equal => Ok(lhs.clone()),
+        Some(_) => Ok(rhs.clone()),
+        None => Err(UnitError::incompatible(&l, &r))
+    }
+}
+
+#[inline(always)]
+fn shift_count(count: &Value) -> Result<u32, ValueError> {
+    match count.to_int()? {
+        n if n < 0 => Err(ValueError::from_string(ErrorKind::Domain, format!("Invalid shift count - '{}'", n))),
+        n => Ok(u32::try_from(n).unwrap_or(u32::MAX))
+    }
+}
+
+// Rotate the bit pattern of val left by count bits, within the current word
+#[inline(always)]
+fn rotate(val: &Value, count: u32) -> ValueResult {
+    let size = word::context().word();
+    let (bits, count) = (word::pattern(val.to_int()?), count % size.bits());
+    if count == 0 {
+        Ok(Value::from_int(size.from_bits(bits)))
+    } else {
+        Ok(Value::from_int(size.from_bits((bits << count) | (bits >> (size.bits() - count)))))
+    }
+}
+
+// Integer operands of the counting ops, which must not be negative
+#[inline(always)]
+fn natural_operands(op: &str, lhs: &Value, rhs: &Value) -> Result<(i64, i64), ValueError> {
+    let (l, r) = (lhs.to_int()?, rhs.to_int()?);
+    if word::widen(l) < 0 || word::widen(r) < 0 {
+        Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Negative operand - '{} {} {}'", op, word::widen(l), word::widen(r))
+        ))
+    } else {
+        Ok((l, r))
+    }
+}
+
+#[inline(always)]
+fn modulus_operand(op: &str, modulus: &Value) -> Result<u64, ValueError> {
+    match word::widen(modulus.to_int()?) {
+        m if m > 0 => Ok(m as u64),
+        m => Err(ValueError::from_string(ErrorKind::Domain, format!("Invalid modulus for {} - '{}'", op, m)))
+    }
+}
+
+// The incomplete gamma functions are only defined for a > 0
+#[inline(always)]
+fn gamma_shape(op: &str, a: &Value) -> Result<f64, ValueError> {
+    match a.to_num()? {
+        a if a > 0.0 => Ok(a),
+        a => Err(ValueError::from_string(ErrorKind::Domain, format!("Invalid shape for {} - '{}'", op, a)))
+    }
+}
+
+// Integer val reduced to the range 0 to m - 1
+#[inline(always)]
+fn residue(val: &Value, m: u64) -> Result<u64, ValueError> {
+    Ok(word::widen(val.to_int()?).rem_euclid(i128::from(m)) as u64)
+}
+
+#[inline(always)]
+fn no_inverse(val: &Value, m: u64) -> ValueError {
+    ValueError::from_string(ErrorKind::Domain, format!("No inverse of {} modulo {}", val, m))
+}
+
+// Integer arithmetic is exact in i128, and the result is fit to the word size
+#[inline(always)]
+fn int_result(op: &str, lhs: i64, rhs: i64, ftn: fn(i128, i128) -> Option<i128>) -> ValueResult {
+    let (l, r) = (word::widen(lhs), word::widen(rhs));
+    match ftn(l, r).and_then(word::fit) {
+        Some(i) => Ok(Value::from_int(i)),
+        None => Err(ValueError::from_string(ErrorKind::Domain, format!("Integer overflow - '{} {} {}'", op, l, r)))
+    }
+}
+
+#[inline(always)]
+fn int_divides(lhs: i64, rhs: i64) -> bool {
+    let (l, r) = (word::widen(lhs), word::widen(rhs));
+    r != 0 && l % r == 0
+}
+
+#[inline(always)]
+fn int_floor_div(l: i128, r: i128) -> Option<i128> {
+    let quot = l.checked_div(r)?;
+    if l % r != 0 && (l < 0) != (r < 0) {
+        Some(quot - 1)
+    } else {
+        Some(quot)
+    }
+}
+
+#[inline(always)]
+fn int_floor_mod(l: i128, r: i128) -> Option<i128> {
+    let rem = l.checked_rem(r)?;
+    if rem != 0 && (rem < 0) != (r < 0) {
+        Some(rem + r)
+    } else {
+        Some(rem)
+    }
+}
+
+// Products of u64 words and large powers can overflow i128, so wrap mode wraps those in i128.
+#[inline(always)]
+fn int_mul(l: i128, r: i128) -> Option<i128> {
+    l.checked_mul(r).or_else(|| word::is_wrapping().then(|| l.wrapping_mul(r)))
+}
+
+#[inline(always)]
+fn int_pow(l: i128, r: i128) -> Option<i128> {
+    // Powers of 1, 0 and -1 stay small for any exponent
+    match l {
+        1 => Some(1),
+        0 => Some(if r == 0 { 1 } else { 0 }),
+        -1 => Some(if r % 2 == 0 { 1 } else { -1 }),
+        _ => {
+            let exp = u32::try_from(r).ok()?;
+            l.checked_pow(exp).or_else(|| word::is_wrapping().then(|| l.wrapping_pow(exp)))
+        }
+    }
+}
+
 // --------------------------------------------------------------------------------
 
 pub type BinaryFtn = fn(&Value, &Value) -> ValueResult;
@@ -95,6 +1050,22 @@ pub fn bop2ftn(name: &str) -> Option<BinaryFtn> {
         keywords::POWER => Some(power),
         keywords::MAX => Some(maximum),
         keywords::MIN => Some(minimum),
+        keywords::ATAN2 => Some(atan2),
+        keywords::HYPOT => Some(hypot),
+        keywords::LOGB => Some(log_base),
+        keywords::ROOT => Some(root),
+        keywords::IDIV => Some(floor_divide),
+        keywords::MOD => Some(modulo),
+        keywords::COPYSIGN => Some(copysign),
+        keywords::FDIM => Some(positive_difference),
+        keywords::GCD => Some(gcd),
+        keywords::LCM => Some(lcm),
+        keywords::CHOOSE => Some(choose),
+        keywords::PERM => Some(permutations),
+        keywords::MODINV => Some(modular_inverse),
+        keywords::BETA => Some(beta),
+        keywords::GAMMAINC => Some(gamma_lower),
+        keywords::GAMMAINCC => Some(gamma_upper),
         keywords::EQUAL => Some(equal),
         keywords::NOT_EQUAL => Some(not_equal),
         keywords::LESS => Some(less),
@@ -103,6 +1074,42 @@ pub fn bop2ftn(name: &str) -> Option<BinaryFtn> {
         keywords::GREATER_EQUAL => Some(greater_equal),
         keywords::AND => Some(logical_and),
         keywords::OR => Some(logical_or),
+        keywords::XOR => Some(logical_xor),
+        keywords::NAND => Some(logical_nand),
+        keywords::NOR => Some(logical_nor),
+        keywords::IMPLIES => Some(logical_implies),
+        keywords::RECT => Some(rectangular),
+        keywords::POLAR => Some(polar),
+        keywords::TO => Some(convert),
+        keywords::INTERVAL => Some(interval),
+        keywords::PM => Some(plus_minus),
+        keywords::CONCAT => Some(concat),
+        keywords::NTH => Some(nth),
+        keywords::PUSH => Some(push),
+        keywords::RANGE => Some(range),
+        keywords::DAYS_BETWEEN => Some(days_between),
+        keywords::ADD_MONTHS => Some(add_months),
+        keywords::DOT => Some(dot),
+        keywords::CROSS => Some(cross),
+        keywords::SOLVE => Some(solve),
+        keywords::DEFAULT => Some(default),
+        keywords::BAND => Some(bit_and),
+        keywords::BOR => Some(bit_or),
+        keywords::BXOR => Some(bit_xor),
+        keywords::SHL => Some(shift_left),
+        keywords::SHR => Some(shift_right),
+        keywords::ROTL => Some(rotate_left),
+        keywords::ROTR => Some(rotate_right),
+        _ => None
+    }
+}
+
+pub type AngleBinaryFtn = fn(&Value, &Value, Angle) -> ValueResult;
+
+// Binary trig ops that depend on the angle mode, used instead of bop2ftn's when not in radians
+pub fn bop2angle_ftn(name: &str) -> Option<AngleBinaryFtn> {
+    match name {
+        keywords::ATAN2 => Some(angle_atan2),
         _ => None
     }
 }
@@ -112,6 +1119,10 @@ pub fn bop2ftn(name: &str) -> Option<BinaryFtn> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pcalc_datetime::{DateTime, Duration};
+    use crate::pcalc_decimal::Decimal;
+    use crate::pcalc_matrix::{Matrix, Vector};
+    use crate::pcalc_units::{Quantity, Unit};
 
     #[test]
     fn test_binop_add() {
@@ -175,6 +1186,143 @@ mod tests {
         assert!(power(&six, &yes).is_err());
     }
 
+    #[test]
+    fn test_binop_int_arithmetic() {
+        let seven = Value::from_int(7);
+        let two = Value::from_int(2);
+        let half = Value::from_num(0.5);
+
+        assert!(add(&seven, &two).unwrap().is_int());
+        assert!(subtract(&seven, &two).unwrap().is_int());
+        assert!(multiply(&seven, &two).unwrap().is_int());
+        assert!(remainder(&seven, &two).unwrap().is_int());
+        assert!(power(&seven, &two).unwrap().is_int());
+        assert!(divide(&Value::from_int(8), &two).unwrap().is_int());
+
+        assert_eq!(add(&seven, &two).unwrap(), Value::from_int(9));
+        assert_eq!(subtract(&two, &seven).unwrap(), Value::from_int(-5));
+        assert_eq!(multiply(&seven, &two).unwrap(), Value::from_int(14));
+        assert_eq!(remainder(&Value::from_int(17), &Value::from_int(5)).unwrap(), Value::from_int(2));
+        assert_eq!(power(&two, &Value::from_int(62)).unwrap(), Value::from_int(4611686018427387904));
+        assert_eq!(divide(&Value::from_int(8), &two).unwrap(), Value::from_int(4));
+
+        // Promotion to f64
+        assert!(!add(&seven, &half).unwrap().is_int());
+        assert_eq!(add(&seven, &half).unwrap(), Value::from_num(7.5));
+        assert!(!divide(&seven, &two).unwrap().is_int());
+        assert_eq!(divide(&seven, &two).unwrap(), Value::from_num(3.5));
+        assert_eq!(power(&two, &Value::from_int(-1)).unwrap(), Value::from_num(0.5));
+        assert_eq!(format!("{}", remainder(&seven, &Value::from_int(0)).unwrap_err()), "Division by zero");
+        assert!(remainder(&Value::from_num(7.0), &Value::from_int(0)).unwrap().to_num().unwrap().is_nan());
+        assert_eq!(maximum(&seven, &two).unwrap(), Value::from_int(7));
+        assert!(maximum(&seven, &two).unwrap().is_int());
+        assert_eq!(minimum(&seven, &half).unwrap(), Value::from_num(0.5));
+    }
+
+    #[test]
+    fn test_binop_int_overflow() {
+        let max = Value::from_int(i64::MAX);
+        let min = Value::from_int(i64::MIN);
+        let one = Value::from_int(1);
+        let two = Value::from_int(2);
+        assert!(add(&max, &one).is_err());
+        assert!(subtract(&min, &one).is_err());
+        assert!(multiply(&max, &two).is_err());
+        assert_eq!(format!("{}", power(&two, &Value::from_int(64)).unwrap_err()), "Integer overflow - '^ 2 64'");
+        let huge = Value::from_int(5_000_000_000);
+        assert_eq!(power(&Value::from_int(1), &huge).unwrap(), Value::from_int(1));
+        assert_eq!(power(&Value::from_int(0), &huge).unwrap(), Value::from_int(0));
+        assert_eq!(power(&Value::from_int(0), &Value::from_int(0)).unwrap(), Value::from_int(1));
+        assert_eq!(power(&Value::from_int(-1), &huge).unwrap(), Value::from_int(1));
+        assert_eq!(power(&Value::from_int(-1), &Value::from_int(5_000_000_001)).unwrap(), Value::from_int(-1));
+        assert!(power(&two, &huge).is_err());
+        assert_eq!(remainder(&min, &Value::from_int(-1)).unwrap(), Value::from_int(0));
+    }
+
+    #[test]
+    fn test_binop_rational_arithmetic() {
+        let rat = |n: i64, d: i64| Value::from_rational(BigRational::new(n.into(), d.into()));
+        let tenth = rat(1, 10);
+        let fifth = rat(1, 5);
+        let two = Value::from_int(2);
+
+        assert_eq!(add(&tenth, &fifth).unwrap(), rat(3, 10));
+        assert_eq!(subtract(&tenth, &fifth).unwrap(), rat(-1, 10));
+        assert_eq!(multiply(&tenth, &fifth).unwrap(), rat(1, 50));
+        assert_eq!(divide(&tenth, &fifth).unwrap(), rat(1, 2));
+        assert_eq!(remainder(&rat(7, 2), &two).unwrap(), rat(3, 2));
+        assert_eq!(power(&fifth, &two).unwrap(), rat(1, 25));
+        assert_eq!(power(&fifth, &Value::from_int(-2)).unwrap(), rat(25, 1));
+        assert_eq!(add(&tenth, &two).unwrap(), rat(21, 10));
+        assert_eq!(maximum(&tenth, &fifth).unwrap(), fifth);
+        assert_eq!(minimum(&tenth, &two).unwrap(), tenth);
+        for ftn in [add, subtract, multiply, divide, remainder, power, maximum, minimum] {
+            assert!(ftn(&tenth, &two).unwrap().is_rational());
+        }
+
+        // Promotion to f64
+        assert!(!add(&tenth, &Value::from_num(0.5)).unwrap().is_rational());
+        assert_eq!(add(&rat(1, 2), &Value::from_num(0.25)).unwrap(), Value::from_num(0.75));
+        assert!(!power(&two, &rat(1, 2)).unwrap().is_rational());
+        assert!(!divide(&Value::from_int(1), &Value::from_int(3)).unwrap().is_rational());
+
+        // Rationals have no infinity
+        assert!(divide(&tenth, &rat(0, 1)).is_err());
+        assert!(divide(&tenth, &Value::from_int(0)).is_err());
+        assert!(remainder(&tenth, &Value::from_int(0)).is_err());
+        assert!(power(&rat(0, 1), &Value::from_int(-1)).is_err());
+
+        // Powers are bounded in size, except for 0, 1 and -1
+        let big = Value::from_int(2_000_000_000);
+        assert_eq!(format!("{}", power(&rat(10, 1), &big).unwrap_err()), "Rational overflow - '^ 10 2000000000'");
+        assert!(power(&tenth, &Value::from_int(-2_000_000_000)).is_err());
+        assert_eq!(power(&rat(1, 1), &big).unwrap(), rat(1, 1));
+        assert_eq!(power(&rat(-1, 1), &Value::from_int(2_000_000_001)).unwrap(), rat(-1, 1));
+        assert_eq!(power(&rat(0, 1), &big).unwrap(), rat(0, 1));
+    }
+
+    #[test]
+    fn test_binop_complex_arithmetic() {
+        let cpx = |re: f64, im: f64| Value::from_complex(Complex64::new(re, im));
+        let z1 = cpx(3.0, 4.0);
+        let z2 = cpx(1.0, -2.0);
+        let two = Value::from_int(2);
+
+        assert_eq!(add(&z1, &z2).unwrap(), cpx(4.0, 2.0));
+        assert_eq!(subtract(&z1, &z2).unwrap(), cpx(2.0, 6.0));
+        assert_eq!(multiply(&z1, &z2).unwrap(), cpx(11.0, -2.0));
+        assert_eq!(divide(&z1, &two).unwrap(), cpx(1.5, 2.0));
+        assert_eq!(add(&two, &z1).unwrap(), cpx(5.0, 4.0));
+
+        assert_eq!(power(&z1, &two).unwrap(), cpx(-7.0, 24.0));
+        assert_eq!(power(&cpx(0.0, 1.0), &two).unwrap(), cpx(-1.0, 0.0));
+        let sqrt_i = power(&cpx(0.0, 1.0), &Value::from_num(0.5)).unwrap().to_complex().unwrap();
+        assert!((sqrt_i - Complex64::new(0.5f64.sqrt(), 0.5f64.sqrt())).norm() < 1e-10);
+        let root = power(&Value::from_int(-4), &Value::from_num(0.5)).unwrap().to_complex().unwrap();
+        assert!((root - Complex64::new(0.0, 2.0)).norm() < 1e-10);
+
+        assert!(remainder(&z1, &two).is_err());
+        assert!(maximum(&z1, &two).is_err());
+        assert!(add(&z1, &Value::from_bool(true)).is_err());
+        assert_eq!(equal(&z1, &cpx(3.0, 4.0)).unwrap(), Value::from_bool(true));
+        assert_eq!(less(&z1, &z2).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_binop_rect_polar() {
+        let z = rectangular(&Value::from_int(3), &Value::from_int(4)).unwrap();
+        assert_eq!(z, Value::from_complex(Complex64::new(3.0, 4.0)));
+
+        let z = polar(&Value::from_int(2), &Value::from_num(std::f64::consts::FRAC_PI_2))
+            .unwrap()
+            .to_complex()
+            .unwrap();
+        assert!((z - Complex64::new(0.0, 2.0)).norm() < 1e-10);
+
+        assert!(rectangular(&Value::from_complex(Complex64::i()), &Value::from_int(1)).is_err());
+        assert!(polar(&Value::from_bool(true), &Value::from_int(1)).is_err());
+    }
+
     #[test]
     fn test_binop_maximum() {
         let six = Value::from_num(6.0);
@@ -197,6 +1345,133 @@ mod tests {
         assert!(minimum(&yes, &no).is_err());
     }
 
+    #[test]
+    fn test_binop_atan2_hypot() {
+        let one = Value::from_int(1);
+        let angle = atan2(&one, &Value::from_int(-1)).unwrap().to_num().unwrap();
+        assert!((angle - 3.0 * std::f64::consts::FRAC_PI_4).abs() < 1e-10);
+        assert_eq!(atan2(&Value::from_int(0), &one).unwrap(), Value::from_num(0.0));
+        assert_eq!(hypot(&Value::from_int(3), &Value::from_int(4)).unwrap(), Value::from_num(5.0));
+        assert!(hypot(&Value::from_bool(true), &one).is_err());
+    }
+
+    #[test]
+    fn test_binop_angle_atan2() {
+        let one = Value::from_num(1.0);
+        assert_eq!(angle_atan2(&one, &one, Angle::Degrees).unwrap(), Value::from_num(45.0));
+        assert_eq!(angle_atan2(&one, &Value::from_int(-1), Angle::Gradians).unwrap(), Value::from_num(150.0));
+        assert_eq!(angle_atan2(&one, &one, Angle::Radians).unwrap(), Value::from_num(std::f64::consts::FRAC_PI_4));
+    }
+
+    #[test]
+    fn test_binop_log_base_root() {
+        assert_eq!(log_base(&Value::from_int(8), &Value::from_int(2)).unwrap(), Value::from_num(3.0));
+        assert_eq!(log_base(&Value::from_int(1000), &Value::from_int(10)).unwrap(), Value::from_num(3.0));
+        assert_eq!(log_base(&Value::from_int(81), &Value::from_int(3)).unwrap(), Value::from_num(4.0));
+        assert!(log_base(&Value::from_int(-1), &Value::from_int(2)).unwrap().to_num().unwrap().is_nan());
+
+        assert_eq!(root(&Value::from_int(27), &Value::from_int(3)).unwrap(), Value::from_int(3));
+        assert_eq!(root(&Value::from_int(-27), &Value::from_int(3)).unwrap(), Value::from_int(-3));
+        assert_eq!(root(&Value::from_int(1024), &Value::from_int(10)).unwrap(), Value::from_int(2));
+        assert_eq!(root(&Value::from_int(-32), &Value::from_num(5.0)).unwrap(), Value::from_num(-2.0));
+        assert_eq!(root(&Value::from_int(2), &Value::from_int(2)).unwrap(), Value::from_num(2.0_f64.sqrt()));
+        assert_eq!(root(&Value::from_int(-8), &Value::from_int(-3)).unwrap(), Value::from_num(-0.5));
+        assert!(root(&Value::from_int(-4), &Value::from_int(2)).unwrap().is_complex());
+        assert_eq!(root(&Value::from_int(4), &Value::from_int(0)).unwrap_err().kind(), ErrorKind::Domain);
+    }
+
+    #[test]
+    fn test_binop_floor_divide_modulo() {
+        let int = Value::from_int;
+        assert_eq!(floor_divide(&int(7), &int(2)).unwrap(), int(3));
+        assert_eq!(floor_divide(&int(-7), &int(2)).unwrap(), int(-4));
+        assert_eq!(floor_divide(&int(7), &int(-2)).unwrap(), int(-4));
+        assert_eq!(floor_divide(&int(-8), &int(2)).unwrap(), int(-4));
+        assert_eq!(floor_divide(&Value::from_num(-7.5), &int(2)).unwrap(), Value::from_num(-4.0));
+        assert_eq!(floor_divide(&int(1), &int(0)).unwrap_err().kind(), ErrorKind::Domain);
+        assert!(floor_divide(&int(i64::MIN), &int(-1)).is_err());
+
+        assert_eq!(modulo(&int(7), &int(3)).unwrap(), int(1));
+        assert_eq!(modulo(&int(-7), &int(3)).unwrap(), int(2));
+        assert_eq!(modulo(&int(7), &int(-3)).unwrap(), int(-2));
+        assert_eq!(modulo(&int(-6), &int(3)).unwrap(), int(0));
+        assert_eq!(remainder(&int(-7), &int(3)).unwrap(), int(-1));
+        assert_eq!(modulo(&Value::from_num(-7.5), &int(2)).unwrap(), Value::from_num(0.5));
+        assert_eq!(modulo(&int(1), &int(0)).unwrap_err().kind(), ErrorKind::Domain);
+
+        let rat = |n: i64, d: i64| Value::from_rational(BigRational::new(n.into(), d.into()));
+        assert_eq!(floor_divide(&rat(-7, 2), &int(1)).unwrap(), rat(-4, 1));
+        assert_eq!(modulo(&rat(-1, 2), &int(3)).unwrap(), rat(5, 2));
+
+        let dec = |text: &str| Value::from_decimal(Decimal::parse(text).unwrap());
+        assert_eq!(floor_divide(&dec("-7.5"), &int(2)).unwrap(), dec("-4"));
+        assert_eq!(modulo(&dec("-7.5"), &int(2)).unwrap(), dec("0.5"));
+    }
+
+    #[test]
+    fn test_binop_copysign_fdim() {
+        let int = Value::from_int;
+        assert_eq!(copysign(&int(3), &int(-1)).unwrap(), int(-3));
+        assert_eq!(copysign(&int(-3), &int(0)).unwrap(), int(3));
+        assert_eq!(copysign(&Value::from_num(2.5), &Value::from_num(-0.0)).unwrap(), Value::from_num(-2.5));
+        assert!(copysign(&int(i64::MIN), &int(1)).is_err());
+
+        assert_eq!(positive_difference(&int(5), &int(3)).unwrap(), int(2));
+        assert_eq!(positive_difference(&int(3), &int(5)).unwrap(), int(0));
+        assert_eq!(positive_difference(&Value::from_num(1.5), &int(1)).unwrap(), Value::from_num(0.5));
+        assert!(positive_difference(&Value::from_num(f64::NAN), &int(1)).unwrap().to_num().unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_binop_number_theory() {
+        let int = Value::from_int;
+        assert_eq!(gcd(&int(-12), &int(18)).unwrap(), int(6));
+        assert_eq!(gcd(&Value::from_num(12.0), &int(8)).unwrap(), int(4));
+        assert_eq!(lcm(&int(4), &int(-6)).unwrap(), int(12));
+        assert_eq!(choose(&int(52), &int(5)).unwrap(), int(2598960));
+        assert_eq!(choose(&int(3), &int(5)).unwrap(), int(0));
+        assert_eq!(permutations(&int(5), &int(2)).unwrap(), int(20));
+        assert_eq!(modular_inverse(&int(3), &int(11)).unwrap(), int(4));
+        assert_eq!(modular_inverse(&int(-3), &int(11)).unwrap(), int(7));
+        assert_eq!(modular_power(&int(4), &int(13), &int(497)).unwrap(), int(445));
+        assert_eq!(modular_power(&int(3), &int(-1), &int(11)).unwrap(), int(4));
+        assert_eq!(modular_power(&int(-2), &int(3), &int(5)).unwrap(), int(2));
+
+        let err = gcd(&Value::from_num(2.5), &int(5)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+        assert_eq!(format!("{}", err), "2.5 not an integer");
+        assert_eq!(
+            format!("{}", gcd(&Value::from_num(1e20), &int(2)).unwrap_err()),
+            "100000000000000000000 out of i64 range"
+        );
+        assert_eq!(format!("{}", choose(&int(-1), &int(2)).unwrap_err()), "Negative operand - 'choose -1 2'");
+        assert_eq!(format!("{}", choose(&int(100), &int(50)).unwrap_err()), "Integer overflow - 'choose 100 50'");
+        assert_eq!(format!("{}", modular_inverse(&int(2), &int(4)).unwrap_err()), "No inverse of 2 modulo 4");
+        assert_eq!(
+            format!("{}", modular_inverse(&int(2), &int(0)).unwrap_err()),
+            "Invalid modulus for modinv - '0'"
+        );
+        assert!(modular_power(&int(2), &int(-1), &int(4)).is_err());
+    }
+
+    #[test]
+    fn test_binop_special_math() {
+        let num = |n: f64| Value::from_num(n);
+        assert!((beta(&Value::from_int(2), &Value::from_int(3)).unwrap().to_num().unwrap() - 1.0 / 12.0).abs() < 1e-15);
+        let p = gamma_lower(&Value::from_int(1), &num(2.0)).unwrap().to_num().unwrap();
+        let q = gamma_upper(&Value::from_int(1), &num(2.0)).unwrap().to_num().unwrap();
+        assert!((p - (1.0 - (-2.0f64).exp())).abs() < 1e-15);
+        assert!((p + q - 1.0).abs() < 1e-15);
+        let err = gamma_lower(&num(-1.0), &num(2.0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Domain);
+        assert_eq!(format!("{}", err), "Invalid shape for gammainc - '-1'");
+        assert_eq!(
+            format!("{}", gamma_upper(&Value::from_int(0), &num(1.0)).unwrap_err()),
+            "Invalid shape for gammaincc - '0'"
+        );
+        assert!(beta(&Value::from_bool(true), &num(2.0)).is_err());
+    }
+
     #[test]
     fn test_binop_equal() {
         let one1 = Value::from_num(1.0);
@@ -253,6 +1528,53 @@ mod tests {
         assert_eq!(greater_equal(&one1, &two).unwrap(), Value::from_bool(false));
     }
 
+    #[test]
+    fn test_binop_approx_equal() {
+        let tol = Tolerance::new();
+        let sum = add(&Value::from_num(0.1), &Value::from_num(0.2)).unwrap();
+        assert_eq!(approx_equal(&sum, &Value::from_num(0.3), &tol).unwrap(), Value::from_bool(true));
+        assert_eq!(
+            approx_equal(&Value::from_int(1), &Value::from_num(1.001), &tol).unwrap(),
+            Value::from_bool(false)
+        );
+        let z = Value::from_complex(Complex64::new(1.0, sum.to_num().unwrap()));
+        assert_eq!(
+            approx_equal(&z, &Value::from_complex(Complex64::new(1.0, 0.3)), &tol).unwrap(),
+            Value::from_bool(true)
+        );
+        let abc = Value::from_string(String::from("abc"));
+        assert_eq!(approx_equal(&abc, &abc, &tol).unwrap(), Value::from_bool(true));
+
+        assert_eq!(not_approx_equal(&sum, &Value::from_num(0.3), &tol).unwrap(), Value::from_bool(false));
+        assert_eq!(not_approx_equal(&abc, &Value::from_int(1), &tol).unwrap(), Value::from_bool(true));
+        let ival = Value::from_interval(Interval::new(1.0, 3.0).unwrap());
+        assert!(approx_equal(&ival, &Value::from_int(2), &tol).unwrap().is_unknown());
+
+        let yes = Value::from_bool(true);
+        let no = Value::from_bool(false);
+        let qty = |magnitude: f64, unit: &str| Value::from_quantity(Quantity::new(magnitude, Unit::parse(unit).unwrap()));
+        assert_eq!(approx_equal(&qty(1.0, "m"), &qty(1.0000000000001, "m"), &tol).unwrap(), yes);
+        assert_eq!(approx_equal(&qty(1.0, "km"), &qty(1000.0000000001, "m"), &tol).unwrap(), yes);
+        assert!(approx_equal(&qty(1.0, "m"), &qty(1.0, "s"), &tol).is_err());
+        assert!(approx_equal(&qty(1.0, "m"), &Value::from_num(1.0), &tol).is_err());
+
+        let u = Value::from_uncertain(Uncertain::new(1.0, 0.1).unwrap());
+        assert_eq!(approx_equal(&u, &Value::from_num(1.0000000000001), &tol).unwrap(), yes);
+
+        let vec = |items: Vec<f64>| Value::from_vector(Vector::new(items).unwrap());
+        let sum_num = sum.to_num().unwrap();
+        assert_eq!(approx_equal(&vec(vec![sum_num, 1.0]), &vec(vec![0.3, 1.0]), &tol).unwrap(), yes);
+        assert_eq!(approx_equal(&vec(vec![0.3, 1.0]), &vec(vec![0.3, 1.1]), &tol).unwrap(), no);
+        assert_eq!(approx_equal(&vec(vec![1.0, 2.0]), &vec(vec![1.0, 2.0, 3.0]), &tol).unwrap(), no);
+
+        let mat = |rows: Vec<Vec<f64>>| Value::from_matrix(Matrix::new(rows).unwrap());
+        let m = mat(vec![vec![sum_num, 1.0], vec![2.0, 3.0]]);
+        assert_eq!(approx_equal(&m, &mat(vec![vec![0.3, 1.0], vec![2.0, 3.0]]), &tol).unwrap(), yes);
+        assert_eq!(approx_equal(&m, &mat(vec![vec![0.3, 1.0], vec![2.0, 3.5]]), &tol).unwrap(), no);
+        let row = mat(vec![vec![1.0, 2.0]]);
+        assert_eq!(approx_equal(&row, &mat(vec![vec![1.0], vec![2.0]]), &tol).unwrap(), no);
+    }
+
     #[test]
     fn test_binop_logical_and() {
         let yes = Value::from_bool(true);
@@ -288,4 +1610,419 @@ mod tests {
         assert!(logical_or(&zero, &one).is_err());
         assert!(logical_or(&zero, &zero).is_err());
     }
+
+    #[test]
+    fn test_binop_logical_xor_nand_nor_implies() {
+        let yes = Value::from_bool(true);
+        let no = Value::from_bool(false);
+        let one = Value::from_num(1.0);
+
+        assert_eq!(logical_xor(&yes, &no).unwrap(), yes);
+        assert_eq!(logical_xor(&yes, &yes).unwrap(), no);
+        assert_eq!(logical_nand(&yes, &yes).unwrap(), no);
+        assert_eq!(logical_nand(&yes, &no).unwrap(), yes);
+        assert_eq!(logical_nor(&no, &no).unwrap(), yes);
+        assert_eq!(logical_nor(&no, &yes).unwrap(), no);
+        assert_eq!(logical_implies(&no, &no).unwrap(), yes);
+        assert_eq!(logical_implies(&yes, &no).unwrap(), no);
+        assert!(logical_xor(&one, &yes).is_err());
+
+        assert_eq!(logical_short_circuit("and", false), Some(false));
+        assert_eq!(logical_short_circuit("and", true), None);
+        assert_eq!(logical_short_circuit("nor", true), Some(false));
+        assert_eq!(logical_short_circuit("implies", false), Some(true));
+        assert_eq!(logical_short_circuit("xor", true), None);
+        assert!(is_logical("implies"));
+        assert!(!is_logical("bxor"));
+    }
+
+    #[test]
+    fn test_binop_string() {
+        let s = |text: &str| Value::from_string(String::from(text));
+
+        assert_eq!(concat(&s("foo"), &s("bar")).unwrap(), s("foobar"));
+        assert_eq!(concat(&s(""), &s("bar")).unwrap(), s("bar"));
+        assert!(concat(&s("foo"), &Value::from_int(1)).is_err());
+        assert!(concat(&Value::from_int(1), &s("foo")).is_err());
+        assert!(add(&s("foo"), &s("bar")).is_err());
+
+        assert_eq!(equal(&s("abc"), &s("abc")).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&s("abc"), &s("ABC")).unwrap(), Value::from_bool(false));
+        assert_eq!(equal(&s("5"), &Value::from_int(5)).unwrap(), Value::from_bool(false));
+        assert_eq!(not_equal(&s("abc"), &s("abd")).unwrap(), Value::from_bool(true));
+        assert_eq!(less(&s("abc"), &s("abd")).unwrap(), Value::from_bool(true));
+        assert_eq!(less_equal(&s("abc"), &s("abc")).unwrap(), Value::from_bool(true));
+        assert_eq!(greater(&s("b"), &s("abc")).unwrap(), Value::from_bool(true));
+        assert_eq!(greater_equal(&s("a"), &s("b")).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_binop_list() {
+        let ints = |v: Vec<i64>| Value::from_list(v.into_iter().map(Value::from_int).collect());
+        let list = ints(vec![10, 20, 30]);
+
+        assert_eq!(nth(&list, &Value::from_int(0)).unwrap(), Value::from_int(10));
+        assert_eq!(nth(&list, &Value::from_int(2)).unwrap(), Value::from_int(30));
+        assert_eq!(
+            format!("{}", nth(&list, &Value::from_int(3)).unwrap_err()),
+            "Index 3 out of range for list of length 3"
+        );
+        assert!(nth(&list, &Value::from_int(-1)).is_err());
+        assert!(nth(&list, &Value::from_num(0.5)).is_err());
+        assert!(nth(&Value::from_int(1), &Value::from_int(0)).is_err());
+
+        assert_eq!(push(&list, &Value::from_int(40)).unwrap(), ints(vec![10, 20, 30, 40]));
+        assert_eq!(push(&ints(vec![]), &list).unwrap(), Value::from_list(vec![list.clone()]));
+        assert!(push(&Value::from_int(1), &Value::from_int(2)).is_err());
+
+        assert_eq!(range(&Value::from_int(0), &Value::from_int(4)).unwrap(), ints(vec![0, 1, 2, 3]));
+        assert_eq!(range(&Value::from_int(-2), &Value::from_int(1)).unwrap(), ints(vec![-2, -1, 0]));
+        assert_eq!(range(&Value::from_int(3), &Value::from_int(1)).unwrap(), ints(vec![]));
+        assert!(range(&Value::from_num(0.5), &Value::from_int(2)).is_err());
+        assert!(range(&Value::from_int(0), &Value::from_int(i64::MAX)).is_err());
+
+        assert_eq!(equal(&list, &ints(vec![10, 20, 30])).unwrap(), Value::from_bool(true));
+        assert_eq!(less(&ints(vec![1, 2]), &ints(vec![1, 3])).unwrap(), Value::from_bool(true));
+    }
+
+    #[test]
+    fn test_binop_quantity() {
+        let qty = |magnitude: f64, unit: &str| Value::from_quantity(Quantity::new(magnitude, Unit::parse(unit).unwrap()));
+        let (one_m, one_s, one_km) = (qty(1.0, "m"), qty(1.0, "s"), qty(1.0, "km"));
+
+        assert_eq!(add(&one_km, &qty(500.0, "m")).unwrap().to_string(), "1.5 km");
+        assert_eq!(subtract(&one_km, &one_m).unwrap(), qty(999.0, "m"));
+        assert_eq!(format!("{}", add(&one_m, &one_s).unwrap_err()), "Incompatible units - 'm' and 's'");
+        assert_eq!(format!("{}", add(&one_m, &Value::from_int(1)).unwrap_err()), "Incompatible units - 'm' and '1'");
+
+        assert_eq!(multiply(&Value::from_int(3), &one_km).unwrap().to_string(), "3 km");
+        assert_eq!(
+            divide(&multiply(&Value::from_int(3), &one_m).unwrap(), &multiply(&Value::from_int(2), &one_s).unwrap())
+                .unwrap()
+                .to_string(),
+            "1.5 m/s"
+        );
+        assert_eq!(divide(&one_km, &one_m).unwrap(), Value::from_num(1000.0));
+        assert!(divide(&one_km, &one_m).unwrap().is_num());
+
+        assert_eq!(power(&qty(2.0, "m"), &Value::from_int(3)).unwrap().to_string(), "8 m^3");
+        assert!(power(&one_m, &Value::from_num(0.5)).is_err());
+        assert!(power(&one_m, &Value::from_int(1000)).is_err());
+        assert!(power(&Value::from_int(2), &one_m).is_err());
+        assert!(remainder(&one_m, &one_m).is_err());
+
+        assert_eq!(maximum(&one_km, &qty(900.0, "m")).unwrap(), one_km);
+        assert_eq!(minimum(&one_km, &qty(900.0, "m")).unwrap(), qty(900.0, "m"));
+        assert!(maximum(&one_km, &one_s).is_err());
+
+        assert_eq!(convert(&qty(1.609344, "km"), &qty(1.0, "mi")).unwrap().to_string(), "1 mi");
+        assert_eq!(convert(&qty(100.0, "degC"), &qty(1.0, "degF")).unwrap().to_string(), "212 degF");
+        assert!(convert(&one_m, &one_s).is_err());
+        assert!(convert(&Value::from_int(1), &one_s).is_err());
+
+        assert_eq!(equal(&one_km, &qty(1000.0, "m")).unwrap(), Value::from_bool(true));
+        assert_eq!(less(&one_m, &one_km).unwrap(), Value::from_bool(true));
+        assert_eq!(format!("{}", less(&one_m, &one_s).unwrap_err()), "Incompatible units - 'm' and 's'");
+        assert!(equal(&one_m, &one_s).is_err());
+        assert!(not_equal(&one_m, &Value::from_int(1)).is_err());
+    }
+
+    #[test]
+    fn test_binop_interval() {
+        let ival = |lo: f64, hi: f64| Value::from_interval(Interval::new(lo, hi).unwrap());
+        let (yes, no, unknown) = (Value::from_bool(true), Value::from_bool(false), Value::Unknown);
+
+        assert_eq!(interval(&Value::from_int(1), &Value::from_num(2.5)).unwrap(), ival(1.0, 2.5));
+        assert_eq!(
+            format!("{}", interval(&Value::from_int(2), &Value::from_int(1)).unwrap_err()),
+            "Invalid interval bounds - '2 1'"
+        );
+        assert!(interval(&Value::from_bool(true), &Value::from_int(1)).is_err());
+
+        assert_eq!(add(&ival(1.0, 2.0), &Value::from_int(1)).unwrap(), ival(2.0, 3.0));
+        assert_eq!(subtract(&Value::from_int(1), &ival(1.0, 2.0)).unwrap(), ival(-1.0, 0.0));
+        assert_eq!(multiply(&ival(-1.0, 2.0), &Value::from_int(3)).unwrap(), ival(-3.0, 6.0));
+        assert_eq!(divide(&Value::from_int(1), &ival(2.0, 4.0)).unwrap(), ival(0.25, 0.5));
+        assert!(divide(&Value::from_int(1), &ival(-1.0, 1.0)).is_err());
+        assert_eq!(power(&ival(-1.0, 2.0), &Value::from_int(2)).unwrap(), ival(0.0, 4.0));
+        assert!(power(&ival(4.0, 9.0), &Value::from_num(0.5)).unwrap().to_interval().unwrap().contains(3.0));
+        assert!(add(&ival(1.0, 2.0), &Value::from_complex(Complex64::i())).is_err());
+
+        assert_eq!(less(&ival(1.0, 2.0), &ival(3.0, 4.0)).unwrap(), yes);
+        assert_eq!(less(&ival(3.0, 4.0), &ival(1.0, 2.0)).unwrap(), no);
+        assert!(less(&ival(1.0, 3.0), &ival(2.0, 4.0)).unwrap().is_unknown());
+        assert_eq!(less_equal(&ival(1.0, 2.0), &Value::from_int(2)).unwrap(), yes);
+        assert_eq!(greater(&Value::from_int(5), &ival(1.0, 2.0)).unwrap(), yes);
+        assert!(greater_equal(&ival(1.0, 3.0), &Value::from_int(2)).unwrap().is_unknown());
+        assert_eq!(equal(&ival(1.0, 2.0), &Value::from_int(3)).unwrap(), no);
+        assert!(equal(&ival(1.0, 2.0), &ival(1.0, 2.0)).unwrap().is_unknown());
+        assert_eq!(not_equal(&ival(1.0, 2.0), &Value::from_int(3)).unwrap(), yes);
+        assert!(equal(&unknown, &unknown).unwrap().is_unknown());
+        assert!(not_equal(&unknown, &no).unwrap().is_unknown());
+    }
+
+    #[test]
+    fn test_binop_datetime() {
+        let date = |text: &str| Value::from_datetime(DateTime::parse(text).unwrap());
+        let dur = |secs: i64| Value::from_duration(Duration::new(secs));
+        let day = 86_400;
+
+        assert_eq!(add(&date("2024-02-28"), &dur(day)).unwrap().to_string(), "2024-02-29");
+        assert_eq!(add(&dur(3600), &date("2024-02-28")).unwrap().to_string(), "2024-02-28T01:00:00");
+        assert_eq!(add(&dur(3600), &dur(60)).unwrap(), dur(3660));
+        assert_eq!(subtract(&date("2024-03-01"), &dur(day)).unwrap().to_string(), "2024-02-29");
+        assert_eq!(subtract(&date("2024-03-02T04:12"), &date("2024-02-28")).unwrap().to_string(), "3d 4h 12m");
+        assert_eq!(subtract(&dur(3600), &dur(60)).unwrap(), dur(3540));
+        assert_eq!(multiply(&dur(3600), &Value::from_int(3)).unwrap(), dur(10800));
+        assert_eq!(multiply(&Value::from_num(0.5), &dur(3600)).unwrap(), dur(1800));
+        let big = (1 << 60) + 1;
+        assert_eq!(multiply(&Value::from_int(1), &dur(big)).unwrap(), dur(big));
+        assert_eq!(divide(&dur(big), &Value::from_int(1)).unwrap(), dur(big));
+        assert_eq!(format!("{}", multiply(&dur(big), &Value::from_int(16)).unwrap_err()), "Duration out of range");
+        assert_eq!(divide(&dur(day), &dur(3600)).unwrap(), Value::from_num(24.0));
+        assert_eq!(divide(&dur(day), &Value::from_int(4)).unwrap(), dur(21600));
+        assert_eq!(format!("{}", divide(&dur(day), &Value::from_int(0)).unwrap_err()), "Division by zero");
+        assert_eq!(divide(&dur(day), &Value::from_num(1.5)).unwrap(), dur(16 * 3600));
+        assert!(divide(&dur(1), &Value::from_int(3)).is_err());
+        assert_eq!(format!("{}", add(&date("9999-12-31"), &dur(day)).unwrap_err()), "Date out of range");
+
+        assert!(add(&date("2024-01-01"), &date("2024-01-01")).is_err());
+        assert!(add(&date("2024-01-01"), &Value::from_int(1)).is_err());
+        assert!(subtract(&dur(60), &date("2024-01-01")).is_err());
+        assert!(multiply(&dur(60), &dur(60)).is_err());
+        assert!(divide(&Value::from_int(1), &dur(60)).is_err());
+
+        assert_eq!(days_between(&date("2024-01-01"), &date("2024-12-25")).unwrap(), Value::from_int(359));
+        assert_eq!(days_between(&date("2024-01-02T23:00"), &date("2024-01-01")).unwrap(), Value::from_int(-1));
+        assert!(days_between(&date("2024-01-01"), &Value::from_int(1)).is_err());
+        assert_eq!(add_months(&date("2024-01-31"), &Value::from_int(1)).unwrap().to_string(), "2024-02-29");
+        assert_eq!(add_months(&date("2024-01-31"), &Value::from_int(-2)).unwrap().to_string(), "2023-11-30");
+        assert!(add_months(&date("2024-01-31"), &Value::from_num(1.5)).is_err());
+
+        assert_eq!(less(&date("2024-01-01"), &date("2024-01-02")).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&dur(60), &dur(60)).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&dur(60), &Value::from_int(60)).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_binop_uncertain() {
+        let sigma = |v: ValueResult| v.unwrap().to_uncertain().unwrap().sigma();
+        let approx = |x: f64, y: f64| (x - y).abs() < 1e-12;
+        let x = plus_minus(&Value::from_num(10.0), &Value::from_num(0.3)).unwrap();
+        let y = plus_minus(&Value::from_int(5), &Value::from_num(0.4)).unwrap();
+        let two = Value::from_int(2);
+
+        assert_eq!(x.to_string(), "10 ± 0.3");
+        assert_eq!(
+            format!("{}", plus_minus(&Value::from_int(1), &Value::from_int(-1)).unwrap_err()),
+            "Invalid uncertainty - '-1'"
+        );
+        assert!(plus_minus(&Value::from_bool(true), &Value::from_int(1)).is_err());
+
+        assert_eq!(add(&x, &y).unwrap(), Value::from_num(15.0));
+        assert!(approx(sigma(add(&x, &y)), 0.5));
+        assert!(approx(sigma(subtract(&x, &y)), 0.5));
+        assert!(approx(sigma(multiply(&x, &two)), 0.6));
+        assert!(approx(sigma(divide(&x, &two)), 0.15));
+        assert!(approx(sigma(remainder(&x, &Value::from_int(3))), 0.3));
+        assert!(approx(sigma(power(&x, &two)), 6.0));
+        assert_eq!(
+            format!(
+                "{}",
+                power(&Value::from_int(-1), &plus_minus(&Value::from_num(0.5), &Value::from_num(0.1)).unwrap()).unwrap_err()
+            ),
+            "Uncertain value outside ^ domain - '-1 ± 0 0.5 ± 0.1'"
+        );
+
+        // Same source used twice is correlated
+        assert_eq!(sigma(subtract(&x, &x)), 0.0);
+        assert!(approx(sigma(add(&x, &x)), 0.6));
+        assert_eq!(sigma(divide(&x, &x)), 0.0);
+
+        assert_eq!(maximum(&x, &y).unwrap().to_string(), "10 ± 0.3");
+        assert_eq!(minimum(&x, &y).unwrap().to_string(), "5 ± 0.4");
+        assert_eq!(maximum(&x, &Value::from_int(20)).unwrap().to_string(), "20 ± 0");
+        assert_eq!(less(&y, &x).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&x, &Value::from_int(10)).unwrap(), Value::from_bool(true));
+        assert!(add(&x, &Value::from_complex(Complex64::i())).is_err());
+    }
+
+    #[test]
+    fn test_binop_uncertain_functions() {
+        let sigma = |v: ValueResult| v.unwrap().to_uncertain().unwrap().sigma();
+        let approx = |x: f64, y: f64| (x - y).abs() < 1e-12;
+        let pm = |x: f64, s: f64| plus_minus(&Value::from_num(x), &Value::from_num(s)).unwrap();
+        let int = Value::from_int;
+
+        let a = atan2(&pm(1.0, 0.1), &int(1)).unwrap();
+        assert!(approx(a.to_uncertain().unwrap().value(), std::f64::consts::FRAC_PI_4));
+        assert!(approx(sigma(Ok(a)), 0.05));
+        assert!(approx(sigma(hypot(&pm(3.0, 0.1), &int(4))), 0.06));
+        assert!(approx(sigma(log_base(&pm(8.0, 0.8), &int(2))), 0.1 / 2.0_f64.ln()));
+        assert!(approx(sigma(log_base(&int(100), &pm(10.0, 0.1))), 0.02 / 10.0_f64.ln()));
+        assert!(approx(sigma(root(&pm(27.0, 2.7), &int(3))), 0.1));
+        assert!(approx(sigma(root(&pm(-27.0, 2.7), &int(3))), 0.1));
+        assert_eq!(
+            format!("{}", root(&pm(-4.0, 0.1), &int(2)).unwrap_err()),
+            "Uncertain value outside root domain - '-4 ± 0.1 2 ± 0'"
+        );
+        assert_eq!(floor_divide(&pm(7.5, 0.1), &int(2)).unwrap().to_string(), "3 ± 0");
+        assert_eq!(modulo(&pm(-7.0, 0.1), &int(3)).unwrap().to_string(), "2 ± 0.1");
+        assert!(approx(sigma(modulo(&int(7), &pm(3.0, 0.1))), 0.2));
+        assert_eq!(format!("{}", floor_divide(&pm(1.0, 0.1), &int(0)).unwrap_err()), "Division by zero");
+        assert_eq!(format!("{}", modulo(&pm(1.0, 0.1), &int(0)).unwrap_err()), "Division by zero");
+        assert_eq!(copysign(&pm(2.0, 0.1), &int(-1)).unwrap().to_string(), "-2 ± 0.1");
+        assert_eq!(positive_difference(&pm(5.0, 0.3), &pm(2.0, 0.4)).unwrap().to_string(), "3 ± 0.5");
+        assert_eq!(positive_difference(&pm(1.0, 0.3), &int(2)).unwrap().to_string(), "0 ± 0");
+    }
+
+    #[test]
+    fn test_binop_decimal() {
+        let dec = |text: &str| Value::from_decimal(Decimal::parse(text).unwrap());
+        let int = Value::from_int;
+
+        assert_eq!(add(&dec("0.1"), &dec("0.2")).unwrap(), dec("0.3"));
+        assert_eq!(add(&dec("19.99"), &int(1)).unwrap().to_string(), "20.99");
+        assert_eq!(subtract(&int(1), &dec("0.01")).unwrap().to_string(), "0.99");
+        assert_eq!(multiply(&dec("19.99"), &int(3)).unwrap().to_string(), "59.97");
+        assert_eq!(divide(&int(1), &dec("3")).unwrap().to_string(), "0.3333333333333333333333333333");
+        assert_eq!(divide(&dec("10"), &dec("4")).unwrap().to_string(), "2.5");
+        assert_eq!(format!("{}", divide(&dec("1"), &int(0)).unwrap_err()), "Division by zero");
+        assert_eq!(remainder(&dec("10.5"), &int(3)).unwrap().to_string(), "1.5");
+        assert_eq!(power(&dec("1.1"), &int(2)).unwrap().to_string(), "1.21");
+        assert!(power(&dec("2"), &dec("0.5")).is_err());
+        assert_eq!(maximum(&dec("1.5"), &int(1)).unwrap().to_string(), "1.5");
+        assert_eq!(minimum(&dec("1.5"), &int(1)).unwrap().to_string(), "1");
+
+        // Mixing with floats and rationals needs an explicit conversion
+        assert_eq!(
+            format!("{}", add(&dec("1.5"), &Value::from_num(0.5)).unwrap_err()),
+            "0.5 not a decimal - convert explicitly with dec"
+        );
+        assert!(multiply(&Value::from_rational(BigRational::from_integer(2.into())), &dec("1.5")).is_err());
+        assert!(less(&dec("1.5"), &Value::from_num(2.0)).is_err());
+        assert!(equal(&Value::from_num(1.5), &dec("1.5")).is_err());
+
+        assert_eq!(equal(&dec("1.50"), &dec("1.5")).unwrap(), Value::from_bool(true));
+        assert_eq!(less(&dec("1.5"), &int(2)).unwrap(), Value::from_bool(true));
+        assert_eq!(greater_equal(&int(2), &dec("1.5")).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&dec("1.5"), &Value::from_string(String::from("1.5"))).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_binop_vector_matrix() {
+        let list = |items: &[f64]| Value::from_list(items.iter().map(|x| Value::from_num(*x)).collect());
+        let vec = |items: &[f64]| Value::from_vector(list(items).to_vector().unwrap());
+        let mat = |rows: &[&[f64]]| Value::from_matrix(Value::from_list(rows.iter().map(|row| list(row)).collect()).to_matrix().unwrap());
+        let a = mat(&[&[1.0, 2.0], &[3.0, 4.0]]);
+
+        assert_eq!(add(&vec(&[1.0, 2.0]), &vec(&[3.0, 4.0])).unwrap(), vec(&[4.0, 6.0]));
+        assert_eq!(subtract(&a, &a).unwrap(), mat(&[&[0.0, 0.0], &[0.0, 0.0]]));
+        assert_eq!(add(&a, &Value::from_int(1)).unwrap(), mat(&[&[2.0, 3.0], &[4.0, 5.0]]));
+        assert_eq!(subtract(&Value::from_int(1), &vec(&[1.0, 2.0])).unwrap(), vec(&[0.0, -1.0]));
+        assert_eq!(multiply(&Value::from_int(2), &vec(&[1.0, 2.0])).unwrap(), vec(&[2.0, 4.0]));
+        assert_eq!(multiply(&vec(&[1.0, 2.0]), &vec(&[3.0, 4.0])).unwrap(), vec(&[3.0, 8.0]));
+        assert_eq!(divide(&a, &Value::from_int(2)).unwrap(), mat(&[&[0.5, 1.0], &[1.5, 2.0]]));
+        assert_eq!(divide(&a, &a).unwrap(), mat(&[&[1.0, 1.0], &[1.0, 1.0]]));
+
+        assert_eq!(multiply(&a, &a).unwrap(), mat(&[&[7.0, 10.0], &[15.0, 22.0]]));
+        assert_eq!(multiply(&a, &vec(&[1.0, 1.0])).unwrap(), vec(&[3.0, 7.0]));
+        assert_eq!(multiply(&vec(&[1.0, 1.0]), &a).unwrap(), vec(&[4.0, 6.0]));
+
+        assert_eq!(
+            format!("{}", add(&vec(&[1.0, 2.0]), &vec(&[1.0, 2.0, 3.0])).unwrap_err()),
+            "Shape mismatch for + - vector of 2 and vector of 3"
+        );
+        assert_eq!(
+            format!("{}", add(&a, &vec(&[1.0, 2.0])).unwrap_err()),
+            "Shape mismatch for + - 2x2 matrix and vector of 2"
+        );
+        assert_eq!(
+            format!("{}", multiply(&a, &mat(&[&[1.0, 2.0, 3.0]])).unwrap_err()),
+            "Shape mismatch for * - 2x2 matrix and 1x3 matrix"
+        );
+        assert!(add(&vec(&[1.0]), &Value::from_bool(true)).is_err());
+        assert!(power(&a, &Value::from_int(2)).is_err());
+
+        assert_eq!(dot(&vec(&[1.0, 2.0, 3.0]), &list(&[4.0, 5.0, 6.0])).unwrap(), Value::from_num(32.0));
+        assert_eq!(cross(&list(&[1.0, 0.0, 0.0]), &list(&[0.0, 1.0, 0.0])).unwrap(), vec(&[0.0, 0.0, 1.0]));
+        assert!(cross(&list(&[1.0, 0.0]), &list(&[0.0, 1.0])).is_err());
+        assert_eq!(solve(&a, &list(&[5.0, 6.0])).unwrap(), vec(&[-4.0, 4.5]));
+        assert_eq!(
+            format!("{}", solve(&mat(&[&[1.0, 2.0], &[2.0, 4.0]]), &list(&[1.0, 2.0])).unwrap_err()),
+            "Singular matrix for solve"
+        );
+
+        assert_eq!(nth(&vec(&[5.0, 6.0]), &Value::from_int(1)).unwrap(), Value::from_num(6.0));
+        assert_eq!(nth(&a, &Value::from_int(0)).unwrap(), vec(&[1.0, 2.0]));
+        assert_eq!(format!("{}", nth(&a, &Value::from_int(2)).unwrap_err()), "Index 2 out of range for length 2");
+
+        assert_eq!(equal(&a, &a).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&vec(&[1.0]), &vec(&[2.0])).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_binop_bitwise() {
+        let int = Value::from_int;
+        assert_eq!(bit_and(&int(0b1100), &int(0b1010)).unwrap(), int(0b1000));
+        assert_eq!(bit_or(&int(0b1100), &int(0b1010)).unwrap(), int(0b1110));
+        assert_eq!(bit_xor(&int(0b1100), &int(0b1010)).unwrap(), int(0b0110));
+        assert_eq!(shift_left(&int(1), &int(4)).unwrap(), int(16));
+        assert_eq!(shift_left(&int(1), &int(64)).unwrap(), int(0));
+        assert_eq!(shift_right(&int(-16), &int(2)).unwrap(), int(-4));
+        assert_eq!(shift_right(&int(-16), &int(100)).unwrap(), int(-1));
+        assert_eq!(rotate_left(&int(i64::MIN), &int(1)).unwrap(), int(1));
+        assert_eq!(rotate_right(&int(1), &int(1)).unwrap(), int(i64::MIN));
+        assert_eq!(format!("{}", shift_left(&int(1), &int(-1)).unwrap_err()), "Invalid shift count - '-1'");
+        assert!(bit_and(&Value::from_num(1.5), &int(1)).is_err());
+    }
+
+    #[test]
+    fn test_binop_word() {
+        use crate::pcalc_word::{with_context, Overflow, WordContext, WordSize};
+
+        let int = Value::from_int;
+        with_context(WordContext::new(Some(WordSize::U8), Overflow::Check), || {
+            assert_eq!(format!("{}", add(&int(255), &int(1)).unwrap_err()), "Integer overflow - '+ 255 1'");
+            assert!(subtract(&int(0), &int(1)).is_err());
+            assert_eq!(multiply(&int(15), &int(17)).unwrap(), int(255));
+            assert_eq!(shift_left(&int(0x81), &int(1)).unwrap(), int(2));
+            assert_eq!(shift_right(&int(0x80), &int(7)).unwrap(), int(1));
+            assert_eq!(rotate_left(&int(0x81), &int(1)).unwrap(), int(3));
+            assert_eq!(rotate_right(&int(0x81), &int(9)).unwrap(), int(0xC0));
+        });
+
+        with_context(WordContext::new(Some(WordSize::U8), Overflow::Wrap), || {
+            assert_eq!(add(&int(255), &int(1)).unwrap(), int(0));
+            assert_eq!(subtract(&int(0), &int(1)).unwrap(), int(255));
+            assert_eq!(power(&int(3), &int(100)).unwrap(), int(209));
+        });
+
+        with_context(WordContext::new(Some(WordSize::I8), Overflow::Wrap), || {
+            assert_eq!(add(&int(127), &int(1)).unwrap(), int(-128));
+            assert_eq!(shift_right(&int(-128), &int(7)).unwrap(), int(-1));
+            assert_eq!(bit_xor(&int(-1), &int(0x0F)).unwrap(), int(-16));
+        });
+
+        with_context(WordContext::new(Some(WordSize::U64), Overflow::Wrap), || {
+            let top = int(i64::MIN);
+            assert_eq!(top.to_string(), "9223372036854775808");
+            assert_eq!(multiply(&top, &int(2)).unwrap(), int(0));
+            assert_eq!(multiply(&int(-1), &int(-1)).unwrap(), int(1));
+            assert_eq!(maximum(&top, &int(1)).unwrap(), top);
+            assert_eq!(less(&int(1), &top).unwrap(), Value::from_bool(true));
+            assert_eq!(divide(&top, &int(2)).unwrap(), int(1 << 62));
+        });
+    }
+
+    #[test]
+    fn test_binop_default() {
+        let none = Value::from_none();
+        assert_eq!(default(&none, &Value::from_int(5)).unwrap(), Value::from_int(5));
+        assert_eq!(default(&Value::from_int(3), &Value::from_int(5)).unwrap(), Value::from_int(3));
+        assert_eq!(default(&Value::from_bool(false), &Value::from_int(5)).unwrap(), Value::from_bool(false));
+        assert!(default(&none, &none).unwrap().is_none());
+        assert_eq!(equal(&none, &none).unwrap(), Value::from_bool(true));
+        assert_eq!(equal(&none, &Value::from_int(0)).unwrap(), Value::from_bool(false));
+    }
 }
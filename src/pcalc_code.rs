@@ -1,8 +1,14 @@
-use crate::pcalc_binary_ops::BinaryFtn;
+use crate::pcalc_angle::Angle;
+use crate::pcalc_binary_ops::{bop2angle_ftn, logical_short_circuit, AngleBinaryFtn, BinaryFtn};
 use crate::pcalc_environment::Environment;
+use crate::pcalc_error::{ErrorKind, Location};
 use crate::pcalc_function::{Arguments, Expressions, Function, FunctionPtr, Parameters};
+use crate::pcalc_keywords as keywords;
+use crate::pcalc_record::Record;
 use crate::pcalc_recursive_check::*;
-use crate::pcalc_unary_ops::UnaryFtn;
+use crate::pcalc_ternary_ops::TernaryFtn;
+use crate::pcalc_tolerance::Tolerance;
+use crate::pcalc_unary_ops::{uop2angle_ftn, AngleUnaryFtn, UnaryFtn};
 use crate::pcalc_value::{Value, ValueError, ValueResult};
 use std::fmt;
 
@@ -50,7 +56,7 @@ impl NoOp {
 
 impl Code for NoOp {
     fn eval(&self, _env: &mut Environment) -> ValueResult {
-        Err(ValueError::new("Eval called on noop"))
+        Err(ValueError::new(ErrorKind::Syntax, "Eval called on noop"))
     }
 
     #[inline(always)]
@@ -59,6 +65,41 @@ impl Code for NoOp {
     }
 }
 
+// --------------------------------------------------------------------------------
+// Located - Code with the Location of its Token
+
+pub struct Located {
+    code: CodePtr,
+    location: Location
+}
+
+impl Located {
+    pub fn new(code: CodePtr, location: Location) -> Self {
+        Located { code, location }
+    }
+}
+
+impl Code for Located {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        self.code.eval(env).map_err(|err| err.at(&self.location))
+    }
+
+    #[inline(always)]
+    fn is_evaluable(&self) -> bool {
+        self.code.is_evaluable()
+    }
+
+    #[inline(always)]
+    fn is_funcall(&self) -> bool {
+        self.code.is_funcall()
+    }
+
+    #[inline(always)]
+    fn get_name(&self) -> Option<&str> {
+        self.code.get_name()
+    }
+}
+
 // --------------------------------------------------------------------------------
 // Literal
 
@@ -74,7 +115,7 @@ impl Literal {
 
 impl Code for Literal {
     fn eval(&self, _env: &mut Environment) -> ValueResult {
-        Ok(self.value)
+        Ok(self.value.clone())
     }
 }
 
@@ -158,14 +199,22 @@ impl Code for GetVar {
 // BinaryOp
 
 pub struct BinaryOp {
+    op_name: String,
     op_ftn: BinaryFtn,
+    angle_ftn: Option<AngleBinaryFtn>,
     lhs_arg: CodePtr,
     rhs_arg: CodePtr
 }
 
 impl BinaryOp {
-    pub fn new(op_ftn: BinaryFtn, lhs_arg: CodePtr, rhs_arg: CodePtr) -> Self {
-        BinaryOp { op_ftn, lhs_arg, rhs_arg }
+    pub fn new(op_name: &str, op_ftn: BinaryFtn, lhs_arg: CodePtr, rhs_arg: CodePtr) -> Self {
+        BinaryOp {
+            op_name: String::from(op_name),
+            op_ftn,
+            angle_ftn: bop2angle_ftn(op_name),
+            lhs_arg,
+            rhs_arg
+        }
     }
 }
 
@@ -173,7 +222,19 @@ impl Code for BinaryOp {
     fn eval(&self, env: &mut Environment) -> ValueResult {
         let lhs_value = self.lhs_arg.eval(env)?;
         let rhs_value = self.rhs_arg.eval(env)?;
-        (self.op_ftn)(&lhs_value, &rhs_value)
+        env.in_context(|| {
+            let result = match self.angle_ftn {
+                Some(ftn) if env.angle() != Angle::Radians => ftn(&lhs_value, &rhs_value, env.angle())?,
+                _ => (self.op_ftn)(&lhs_value, &rhs_value)?
+            };
+            if env.is_strict() && !result.is_finite() {
+                return Err(ValueError::from_string(
+                    ErrorKind::Domain,
+                    format!("Domain error - '{} {} {}'", self.op_name, lhs_value, rhs_value)
+                ));
+            }
+            Ok(result)
+        })
     }
 }
 
@@ -181,20 +242,262 @@ impl Code for BinaryOp {
 // UnaryOp
 
 pub struct UnaryOp {
+    op_name: String,
     op_ftn: UnaryFtn,
+    angle_ftn: Option<AngleUnaryFtn>,
     arg: CodePtr
 }
 
 impl UnaryOp {
-    pub fn new(op_ftn: UnaryFtn, arg: CodePtr) -> Self {
-        UnaryOp { op_ftn, arg }
+    pub fn new(op_name: &str, op_ftn: UnaryFtn, arg: CodePtr) -> Self {
+        UnaryOp {
+            op_name: String::from(op_name),
+            op_ftn,
+            angle_ftn: uop2angle_ftn(op_name),
+            arg
+        }
     }
 }
 
 impl Code for UnaryOp {
     fn eval(&self, env: &mut Environment) -> ValueResult {
         let value = self.arg.eval(env)?;
-        (self.op_ftn)(&value)
+        env.in_context(|| {
+            let result = match self.angle_ftn {
+                Some(ftn) if env.angle() != Angle::Radians => ftn(&value, env.angle())?,
+                _ => (self.op_ftn)(&value)?
+            };
+            if env.is_strict() && !result.is_finite() {
+                return Err(ValueError::from_string(
+                    ErrorKind::Domain,
+                    format!("Domain error - '{} {}'", self.op_name, value)
+                ));
+            }
+            Ok(result)
+        })
+    }
+}
+
+// --------------------------------------------------------------------------------
+// TernaryOp
+
+pub struct TernaryOp {
+    op_name: String,
+    op_ftn: TernaryFtn,
+    first_arg: CodePtr,
+    second_arg: CodePtr,
+    third_arg: CodePtr
+}
+
+impl TernaryOp {
+    pub fn new(op_name: &str, op_ftn: TernaryFtn, first_arg: CodePtr, second_arg: CodePtr, third_arg: CodePtr) -> Self {
+        TernaryOp {
+            op_name: String::from(op_name),
+            op_ftn,
+            first_arg,
+            second_arg,
+            third_arg
+        }
+    }
+}
+
+impl Code for TernaryOp {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let first_value = self.first_arg.eval(env)?;
+        let second_value = self.second_arg.eval(env)?;
+        let third_value = self.third_arg.eval(env)?;
+        env.in_context(|| {
+            let result = (self.op_ftn)(&first_value, &second_value, &third_value)?;
+            if env.is_strict() && !result.is_finite() {
+                return Err(ValueError::from_string(
+                    ErrorKind::Domain,
+                    format!("Domain error - '{} {} {} {}'", self.op_name, first_value, second_value, third_value)
+                ));
+            }
+            Ok(result)
+        })
+    }
+}
+
+// --------------------------------------------------------------------------------
+// LogicalOp - Logical Binary Op, Evaluating the Right Operand Only When Needed
+
+pub struct LogicalOp {
+    op_name: String,
+    op_ftn: BinaryFtn,
+    lhs_arg: CodePtr,
+    rhs_arg: CodePtr
+}
+
+impl LogicalOp {
+    pub fn new(op_name: &str, op_ftn: BinaryFtn, lhs_arg: CodePtr, rhs_arg: CodePtr) -> Self {
+        LogicalOp {
+            op_name: String::from(op_name),
+            op_ftn,
+            lhs_arg,
+            rhs_arg
+        }
+    }
+}
+
+impl Code for LogicalOp {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let lhs = logical_operand(&self.op_name, &self.lhs_arg, env)?;
+        if let Some(result) = logical_short_circuit(&self.op_name, lhs) {
+            return Ok(Value::from_bool(result));
+        }
+        let rhs = logical_operand(&self.op_name, &self.rhs_arg, env)?;
+        (self.op_ftn)(&Value::from_bool(lhs), &Value::from_bool(rhs))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// LogicalNot
+
+pub struct LogicalNot {
+    arg: CodePtr
+}
+
+impl LogicalNot {
+    pub fn new(arg: CodePtr) -> Self {
+        LogicalNot { arg }
+    }
+}
+
+impl Code for LogicalNot {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        Ok(Value::from_bool(!logical_operand(keywords::NOT, &self.arg, env)?))
+    }
+}
+
+// Operands must be booleans, or in truthy mode any value, taken as a condition would be
+fn logical_operand(op_name: &str, arg: &CodePtr, env: &mut Environment) -> Result<bool, ValueError> {
+    let value = arg.eval(env)?;
+    if !env.is_truthy() {
+        return value.to_bool();
+    }
+    if value.is_unknown() {
+        return Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Ambiguous interval comparison in {} operand", op_name)
+        ));
+    }
+    Ok(value.as_bool())
+}
+
+// --------------------------------------------------------------------------------
+// ListExpr - List Literal
+
+pub struct ListExpr {
+    items: Expressions
+}
+
+impl ListExpr {
+    pub fn new(items: Expressions) -> Self {
+        ListExpr { items }
+    }
+}
+
+impl Code for ListExpr {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let mut values = Vec::with_capacity(self.items.len());
+        for item in self.items.iter() {
+            values.push(item.eval(env)?);
+        }
+        Ok(Value::from_list(values))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// RecordExpr - Record Literal
+
+pub type Fields = Vec<(String, CodePtr)>;
+
+pub struct RecordExpr {
+    fields: Fields
+}
+
+impl RecordExpr {
+    pub fn new(fields: Fields) -> Self {
+        RecordExpr { fields }
+    }
+}
+
+impl Code for RecordExpr {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let mut rec = Record::new();
+        for (name, expr) in self.fields.iter() {
+            rec = rec.with(name, expr.eval(env)?);
+        }
+        Ok(Value::from_record(rec))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// RecordGet - Get Record Field
+
+pub struct RecordGet {
+    rec: CodePtr,
+    field: String
+}
+
+impl RecordGet {
+    pub fn new(rec: CodePtr, field: String) -> Self {
+        RecordGet { rec, field }
+    }
+}
+
+impl Code for RecordGet {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        self.rec.eval(env)?.to_record()?.get(&self.field)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// RecordWith - Copy of Record with Field Set
+
+pub struct RecordWith {
+    rec: CodePtr,
+    field: String,
+    value: CodePtr
+}
+
+impl RecordWith {
+    pub fn new(rec: CodePtr, field: String, value: CodePtr) -> Self {
+        RecordWith { rec, field, value }
+    }
+}
+
+impl Code for RecordWith {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let rec = self.rec.eval(env)?;
+        let value = self.value.eval(env)?;
+        Ok(Value::from_record(rec.to_record()?.with(&self.field, value)))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// ApproxEqual - Approximate Equality, with the Tolerance Setting
+
+pub type ToleranceFtn = fn(&Value, &Value, &Tolerance) -> ValueResult;
+
+pub struct ApproxEqual {
+    op_ftn: ToleranceFtn,
+    lhs_arg: CodePtr,
+    rhs_arg: CodePtr
+}
+
+impl ApproxEqual {
+    pub fn new(op_ftn: ToleranceFtn, lhs_arg: CodePtr, rhs_arg: CodePtr) -> Self {
+        ApproxEqual { op_ftn, lhs_arg, rhs_arg }
+    }
+}
+
+impl Code for ApproxEqual {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let lhs_value = self.lhs_arg.eval(env)?;
+        let rhs_value = self.rhs_arg.eval(env)?;
+        env.in_context(|| (self.op_ftn)(&lhs_value, &rhs_value, env.tolerance()))
     }
 }
 
@@ -214,11 +517,29 @@ impl XPrint {
 impl Code for XPrint {
     fn eval(&self, env: &mut Environment) -> ValueResult {
         let value = self.expr.eval(env)?;
-        println!("{}", value);
+        env.in_context(|| println!("{}", env.format().value(&value)));
         Ok(value)
     }
 }
 
+// --------------------------------------------------------------------------------
+// Now - Current Date and Time
+
+pub struct Now {}
+
+impl Now {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Now {}
+    }
+}
+
+impl Code for Now {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        Ok(Value::from_datetime(env.now()?))
+    }
+}
+
 // --------------------------------------------------------------------------------
 // Defun - Define Function
 
@@ -268,7 +589,130 @@ impl Funcall {
 impl Code for Funcall {
     fn eval(&self, env: &mut Environment) -> ValueResult {
         let func = FunctionPtr::clone(env.get_func(&self.name)?);
-        func.eval(env, &self.args)
+        func.check_arity(self.args.len())?;
+
+        let mut values = Vec::with_capacity(self.args.len());
+        for arg in self.args.iter() {
+            values.push(arg.eval(env)?);
+        }
+        func.apply(env, values).map_err(|err| err.in_function(&self.name))
+    }
+
+    #[inline(always)]
+    fn is_funcall(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn get_name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// ListMap - Apply Function to Each List Item
+
+pub struct ListMap {
+    name: String,
+    list: CodePtr
+}
+
+impl ListMap {
+    pub fn new(name: String, list: CodePtr) -> Self {
+        ListMap { name, list }
+    }
+}
+
+impl Code for ListMap {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let list = self.list.eval(env)?;
+        let func = FunctionPtr::clone(env.get_func(&self.name)?);
+        func.check_arity(1)?;
+
+        let mut values = Vec::new();
+        for item in list.to_list()? {
+            values.push(func.apply(env, vec![item.clone()]).map_err(|err| err.in_function(&self.name))?);
+        }
+        Ok(Value::from_list(values))
+    }
+
+    #[inline(always)]
+    fn is_funcall(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn get_name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// ListFilter - Keep List Items Satisfying Function
+
+pub struct ListFilter {
+    name: String,
+    list: CodePtr
+}
+
+impl ListFilter {
+    pub fn new(name: String, list: CodePtr) -> Self {
+        ListFilter { name, list }
+    }
+}
+
+impl Code for ListFilter {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let list = self.list.eval(env)?;
+        let func = FunctionPtr::clone(env.get_func(&self.name)?);
+        func.check_arity(1)?;
+
+        let mut values = Vec::new();
+        for item in list.to_list()? {
+            if func.apply(env, vec![item.clone()]).map_err(|err| err.in_function(&self.name))?.as_bool() {
+                values.push(item.clone());
+            }
+        }
+        Ok(Value::from_list(values))
+    }
+
+    #[inline(always)]
+    fn is_funcall(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn get_name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// ListFold - Combine List Items Left to Right
+
+pub struct ListFold {
+    name: String,
+    init: CodePtr,
+    list: CodePtr
+}
+
+impl ListFold {
+    pub fn new(name: String, init: CodePtr, list: CodePtr) -> Self {
+        ListFold { name, init, list }
+    }
+}
+
+impl Code for ListFold {
+    fn eval(&self, env: &mut Environment) -> ValueResult {
+        let mut acc = self.init.eval(env)?;
+        let list = self.list.eval(env)?;
+        let func = FunctionPtr::clone(env.get_func(&self.name)?);
+        func.check_arity(2)?;
+
+        for item in list.to_list()? {
+            acc = func.apply(env, vec![acc, item.clone()]).map_err(|err| err.in_function(&self.name))?;
+        }
+        Ok(acc)
     }
 
     #[inline(always)]
@@ -300,14 +744,18 @@ impl Conditional {
         Conditional {
             cond,
             true_code,
-            false_code: Box::new(Literal::new(Value::from_bool(false)))
+            false_code: Box::new(Literal::new(Value::from_none()))
         }
     }
 }
 
 impl Code for Conditional {
     fn eval(&self, env: &mut Environment) -> ValueResult {
-        if self.cond.eval(env)?.as_bool() {
+        let cond = self.cond.eval(env)?;
+        if cond.is_unknown() {
+            return Err(ValueError::new(ErrorKind::Domain, "Ambiguous interval comparison in condition"));
+        }
+        if cond.as_bool() {
             Ok(self.true_code.eval(env)?)
         } else {
             Ok(self.false_code.eval(env)?)
@@ -319,6 +767,7 @@ impl Code for Conditional {
 // Unit Tests
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
     use crate::pcalc_binary_ops::bop2ftn;
@@ -359,6 +808,7 @@ mod tests {
         let mut env = Environment::new();
 
         let bop = BinaryOp::new(
+            "+",
             bop2ftn("+").unwrap(),
             Box::new(Literal::new(Value::from_num(2.0))),
             Box::new(Literal::new(Value::from_num(3.0)))
@@ -366,6 +816,7 @@ mod tests {
         assert_eq!(bop.eval(&mut env).unwrap(), Value::from_num(5.0));
 
         let bop = BinaryOp::new(
+            "or",
             bop2ftn("or").unwrap(),
             Box::new(Literal::new(Value::from_bool(false))),
             Box::new(Literal::new(Value::from_bool(true)))
@@ -377,13 +828,117 @@ mod tests {
     fn test_unaryop() {
         let mut env = Environment::new();
 
-        let uop = UnaryOp::new(uop2ftn("abs").unwrap(), Box::new(Literal::new(Value::from_num(-2.0))));
+        let uop = UnaryOp::new("abs", uop2ftn("abs").unwrap(), Box::new(Literal::new(Value::from_num(-2.0))));
         assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(2.0));
 
-        let uop = UnaryOp::new(uop2ftn("not").unwrap(), Box::new(Literal::new(Value::from_bool(false))));
+        let uop = UnaryOp::new("not", uop2ftn("not").unwrap(), Box::new(Literal::new(Value::from_bool(false))));
         assert_eq!(uop.eval(&mut env).unwrap(), Value::from_bool(true));
     }
 
+    #[test]
+    fn test_strict_mode() {
+        let mut env = Environment::new();
+
+        let bop = BinaryOp::new(
+            "/",
+            bop2ftn("/").unwrap(),
+            Box::new(Literal::new(Value::from_num(1.0))),
+            Box::new(Literal::new(Value::from_num(0.0)))
+        );
+        let uop = UnaryOp::new("ln", uop2ftn("ln").unwrap(), Box::new(Literal::new(Value::from_num(0.0))));
+        assert_eq!(bop.eval(&mut env).unwrap(), Value::from_num(f64::INFINITY));
+        assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(f64::NEG_INFINITY));
+
+        env.set_strict(true);
+        assert_eq!(format!("{}", bop.eval(&mut env).unwrap_err()), "Domain error - '/ 1 0'");
+        assert_eq!(format!("{}", uop.eval(&mut env).unwrap_err()), "Domain error - 'ln 0'");
+
+        let uop = UnaryOp::new("sqrt", uop2ftn("sqrt").unwrap(), Box::new(Literal::new(Value::from_num(4.0))));
+        assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(2.0));
+    }
+
+    #[test]
+    fn test_ternaryop() {
+        use crate::pcalc_ternary_ops::top2ftn;
+
+        let mut env = Environment::new();
+        let num = |x: f64| -> CodePtr { Box::new(Literal::new(Value::from_num(x))) };
+
+        let top = TernaryOp::new("clamp", top2ftn("clamp").unwrap(), num(12.0), num(0.0), num(10.0));
+        assert_eq!(top.eval(&mut env).unwrap(), Value::from_num(10.0));
+
+        let top = TernaryOp::new("lerp", top2ftn("lerp").unwrap(), num(0.0), num(f64::INFINITY), num(0.5));
+        assert!(top.eval(&mut env).unwrap().to_num().unwrap().is_nan());
+        env.set_strict(true);
+        assert_eq!(format!("{}", top.eval(&mut env).unwrap_err()), "Domain error - 'lerp 0 inf 0.5'");
+    }
+
+    #[test]
+    fn test_logical_op() {
+        let mut env = Environment::new();
+        let lit = |val: Value| -> CodePtr { Box::new(Literal::new(val)) };
+        let unknown_var = || -> CodePtr { Box::new(GetVar::new(String::from("undefined"))) };
+
+        // The right operand is not evaluated, so the unknown variable is no error
+        let lop = LogicalOp::new("and", bop2ftn("and").unwrap(), lit(Value::from_bool(false)), unknown_var());
+        assert_eq!(lop.eval(&mut env).unwrap(), Value::from_bool(false));
+        let lop = LogicalOp::new("or", bop2ftn("or").unwrap(), lit(Value::from_bool(true)), unknown_var());
+        assert_eq!(lop.eval(&mut env).unwrap(), Value::from_bool(true));
+        let lop = LogicalOp::new("or", bop2ftn("or").unwrap(), lit(Value::from_bool(false)), unknown_var());
+        assert!(lop.eval(&mut env).is_err());
+
+        let lop = LogicalOp::new("xor", bop2ftn("xor").unwrap(), lit(Value::from_int(1)), lit(Value::from_bool(true)));
+        assert_eq!(format!("{}", lop.eval(&mut env).unwrap_err()), "1 not a boolean");
+        let not = LogicalNot::new(lit(Value::from_string(String::new())));
+        assert!(not.eval(&mut env).is_err());
+
+        env.set_truthy(true);
+        assert_eq!(lop.eval(&mut env).unwrap(), Value::from_bool(false));
+        assert_eq!(not.eval(&mut env).unwrap(), Value::from_bool(true));
+        let lop = LogicalOp::new("and", bop2ftn("and").unwrap(), lit(Value::from_truth(None)), lit(Value::from_bool(true)));
+        assert_eq!(format!("{}", lop.eval(&mut env).unwrap_err()), "Ambiguous interval comparison in and operand");
+    }
+
+    #[test]
+    fn test_approx_equal() {
+        use crate::pcalc_binary_ops::{approx_equal, not_approx_equal};
+
+        let mut env = Environment::new();
+        let num = |x: f64| -> CodePtr { Box::new(Literal::new(Value::from_num(x))) };
+
+        let approx = ApproxEqual::new(approx_equal, num(1.0), num(1.0 + 1e-12));
+        let not_approx = ApproxEqual::new(not_approx_equal, num(1.0), num(1.0 + 1e-12));
+        assert_eq!(approx.eval(&mut env).unwrap(), Value::from_bool(true));
+        assert_eq!(not_approx.eval(&mut env).unwrap(), Value::from_bool(false));
+
+        env.tolerance_mut().apply("ulp 4").unwrap();
+        assert_eq!(approx.eval(&mut env).unwrap(), Value::from_bool(false));
+        assert_eq!(not_approx.eval(&mut env).unwrap(), Value::from_bool(true));
+    }
+
+    #[test]
+    fn test_angle_mode() {
+        let mut env = Environment::new();
+
+        let uop = UnaryOp::new("sin", uop2ftn("sin").unwrap(), Box::new(Literal::new(Value::from_num(90.0))));
+        let bop = BinaryOp::new(
+            "atan2",
+            bop2ftn("atan2").unwrap(),
+            Box::new(Literal::new(Value::from_num(1.0))),
+            Box::new(Literal::new(Value::from_num(0.0)))
+        );
+        assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(90.0f64.sin()));
+        assert_eq!(bop.eval(&mut env).unwrap(), Value::from_num(std::f64::consts::FRAC_PI_2));
+
+        env.set_angle(Angle::Degrees);
+        assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(1.0));
+        assert_eq!(bop.eval(&mut env).unwrap(), Value::from_num(90.0));
+
+        let uop = UnaryOp::new("deg2rad", uop2ftn("deg2rad").unwrap(), Box::new(Literal::new(Value::from_num(90.0))));
+        env.set_angle(Angle::Gradians);
+        assert_eq!(uop.eval(&mut env).unwrap(), Value::from_num(std::f64::consts::FRAC_PI_2));
+    }
+
     #[test]
     fn test_print() {
         let mut env = Environment::new();
@@ -404,6 +959,7 @@ mod tests {
 
         let mut exprs = Expressions::new();
         exprs.push(Box::new(BinaryOp::new(
+            "+",
             bop2ftn("+").unwrap(),
             Box::new(GetVar::new(String::from("x"))),
             Box::new(GetVar::new(String::from("y")))
@@ -432,6 +988,7 @@ mod tests {
 
         let mut exprs = Expressions::new();
         exprs.push(Box::new(BinaryOp::new(
+            "+",
             bop2ftn("+").unwrap(),
             Box::new(GetVar::new(String::from("x"))),
             Box::new(GetVar::new(String::from("y")))
@@ -448,6 +1005,92 @@ mod tests {
         assert_eq!(funcall.eval(&mut call_env).unwrap(), Value::from_num(10.0));
     }
 
+    #[test]
+    fn test_list_expr() {
+        let mut env = Environment::new();
+        env.def_var("x", Value::from_int(2)).unwrap();
+
+        let mut items = Expressions::new();
+        items.push(Box::new(Literal::new(Value::from_int(1))));
+        items.push(Box::new(GetVar::new(String::from("x"))));
+        let list = ListExpr::new(items);
+        assert_eq!(list.eval(&mut env).unwrap(), Value::from_list(vec![Value::from_int(1), Value::from_int(2)]));
+        assert_eq!(ListExpr::new(Expressions::new()).eval(&mut env).unwrap(), Value::from_list(vec![]));
+    }
+
+    #[test]
+    fn test_record_expr() {
+        let mut env = Environment::new();
+        env.def_var("x", Value::from_int(2)).unwrap();
+        let lit = |i: i64| -> CodePtr { Box::new(Literal::new(Value::from_int(i))) };
+        let var = || -> CodePtr { Box::new(GetVar::new(String::from("r"))) };
+
+        let mut fields = Fields::new();
+        fields.push((String::from("a"), lit(1)));
+        fields.push((String::from("b"), Box::new(GetVar::new(String::from("x")))));
+        let rec = RecordExpr::new(fields).eval(&mut env).unwrap();
+        assert_eq!(rec.to_string(), "{a: 1, b: 2}");
+        env.def_var("r", rec).unwrap();
+
+        assert_eq!(RecordGet::new(var(), String::from("b")).eval(&mut env).unwrap(), Value::from_int(2));
+        assert!(RecordGet::new(var(), String::from("c")).eval(&mut env).is_err());
+        assert!(RecordGet::new(lit(1), String::from("a")).eval(&mut env).is_err());
+
+        let updated = RecordWith::new(var(), String::from("a"), lit(5)).eval(&mut env).unwrap();
+        assert_eq!(updated.to_string(), "{a: 5, b: 2}");
+        assert_eq!(env.get_var("r").unwrap().to_string(), "{a: 1, b: 2}");
+        assert!(RecordWith::new(lit(1), String::from("a"), lit(5)).eval(&mut env).is_err());
+    }
+
+    #[test]
+    fn test_list_map_filter_fold() {
+        let mut env = Environment::new();
+        let ints = |v: Vec<i64>| Value::from_list(v.into_iter().map(Value::from_int).collect());
+        let list = || -> CodePtr { Box::new(Literal::new(ints(vec![1, 2, 3, 4]))) };
+        let var = |name: &str| -> CodePtr { Box::new(GetVar::new(String::from(name))) };
+
+        let square = Defun::new(
+            "square".to_string(),
+            vec![String::from("x")],
+            vec![Box::new(BinaryOp::new("*", bop2ftn("*").unwrap(), var("x"), var("x")))]
+        );
+        let even = Defun::new(
+            "even".to_string(),
+            vec![String::from("x")],
+            vec![Box::new(BinaryOp::new(
+                "==",
+                bop2ftn("==").unwrap(),
+                Box::new(BinaryOp::new("%", bop2ftn("%").unwrap(), var("x"), Box::new(Literal::new(Value::from_int(2))))),
+                Box::new(Literal::new(Value::from_int(0)))
+            ))]
+        );
+        let plus = Defun::new(
+            "plus".to_string(),
+            vec![String::from("a"), String::from("b")],
+            vec![Box::new(BinaryOp::new("+", bop2ftn("+").unwrap(), var("a"), var("b")))]
+        );
+        square.eval(&mut env).unwrap();
+        even.eval(&mut env).unwrap();
+        plus.eval(&mut env).unwrap();
+
+        let map = ListMap::new("square".to_string(), list());
+        assert_eq!(map.eval(&mut env).unwrap(), ints(vec![1, 4, 9, 16]));
+        assert!(map.is_funcall());
+        assert_eq!(map.get_name().unwrap(), "square");
+
+        let filter = ListFilter::new("even".to_string(), list());
+        assert_eq!(filter.eval(&mut env).unwrap(), ints(vec![2, 4]));
+
+        let fold = ListFold::new("plus".to_string(), Box::new(Literal::new(Value::from_int(100))), list());
+        assert_eq!(fold.eval(&mut env).unwrap(), Value::from_int(110));
+
+        assert!(ListMap::new("plus".to_string(), list()).eval(&mut env).is_err());
+        assert!(ListMap::new("unknown".to_string(), list()).eval(&mut env).is_err());
+        assert!(ListMap::new("square".to_string(), Box::new(Literal::new(Value::from_int(1))))
+            .eval(&mut env)
+            .is_err());
+    }
+
     #[test]
     fn test_conditional() {
         let mut env = Environment::new();
@@ -485,6 +1128,29 @@ mod tests {
         assert_eq!(cond.eval(&mut env).unwrap(), Value::from_num(4.0));
     }
 
+    #[test]
+    fn test_conditional_unknown() {
+        let mut env = Environment::new();
+        let cond = Conditional::new(
+            Box::new(Literal::new(Value::Unknown)),
+            Box::new(Literal::new(Value::from_num(1.0))),
+            Box::new(Literal::new(Value::from_num(2.0)))
+        );
+        assert_eq!(format!("{}", cond.eval(&mut env).unwrap_err()), "Ambiguous interval comparison in condition");
+    }
+
+    #[test]
+    fn test_now() {
+        use crate::pcalc_datetime::DateTime;
+
+        let mut env = Environment::new();
+        assert!(Now::new().eval(&mut env).unwrap().is_datetime());
+
+        let pinned = DateTime::parse("2024-03-15T10:30").unwrap();
+        env.pin_now(Some(pinned));
+        assert_eq!(Now::new().eval(&mut env).unwrap(), Value::from_datetime(pinned));
+    }
+
     #[test]
     fn test_conditional_when() {
         let mut env = Environment::new();
@@ -496,12 +1162,12 @@ mod tests {
         assert_eq!(cond.eval(&mut env).unwrap(), Value::from_num(1.0));
 
         let cond = Conditional::when(Box::new(Literal::new(Value::from_bool(false))), Box::new(Literal::new(Value::from_num(1.0))));
-        assert_eq!(cond.eval(&mut env).unwrap(), Value::from_bool(false));
+        assert!(cond.eval(&mut env).unwrap().is_none());
 
         let cond = Conditional::when(Box::new(GetVar::new(String::from("check3"))), Box::new(GetVar::new(String::from("true_code"))));
         assert_eq!(cond.eval(&mut env).unwrap(), Value::from_num(3.0));
 
         let cond = Conditional::when(Box::new(GetVar::new(String::from("check4"))), Box::new(GetVar::new(String::from("true_code"))));
-        assert_eq!(cond.eval(&mut env).unwrap(), Value::from_bool(false));
+        assert!(cond.eval(&mut env).unwrap().is_none());
     }
 }
@@ -0,0 +1,556 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use std::cmp;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// --------------------------------------------------------------------------------
+// DateTime Error
+
+pub type DateTimeError = Error;
+
+impl DateTimeError {
+    pub fn invalid_date(text: &str) -> Self {
+        DateTimeError::from_string(ErrorKind::Domain, format!("Invalid date - '{}'", text))
+    }
+
+    pub fn invalid_duration(text: &str) -> Self {
+        DateTimeError::from_string(ErrorKind::Domain, format!("Invalid duration - '{}'", text))
+    }
+
+    pub fn date_out_of_range() -> Self {
+        DateTimeError::new(ErrorKind::Domain, "Date out of range")
+    }
+
+    pub fn duration_out_of_range() -> Self {
+        DateTimeError::new(ErrorKind::Domain, "Duration out of range")
+    }
+
+    pub fn inexact_division(dur: &Duration, divisor: f64) -> Self {
+        DateTimeError::from_string(ErrorKind::Domain, format!("Duration not a whole number of seconds - '/ {} {}'", dur, divisor))
+    }
+}
+
+pub type DateTimeResult = Result<DateTime, DateTimeError>;
+pub type DurationResult = Result<Duration, DateTimeError>;
+
+const SECS_PER_MINUTE: i64 = 60;
+const SECS_PER_HOUR: i64 = 3_600;
+const SECS_PER_DAY: i64 = 86_400;
+const SECS_PER_WEEK: i64 = 604_800;
+
+// Years 1 through 9999, so dates always print as four digit ISO-8601 years
+const MIN_SECS: i64 = -62_135_596_800; // 0001-01-01T00:00:00
+const MAX_SECS: i64 = 253_402_300_799; // 9999-12-31T23:59:59
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+// --------------------------------------------------------------------------------
+// DateTime - UTC date, or date and time, with one second resolution
+//
+// Stored as seconds since 1970-01-01T00:00:00. A date without a time of day stays a
+// date through whole day arithmetic, and prints as YYYY-MM-DD.
+
+#[derive(Debug, Clone, Copy)]
+pub struct DateTime {
+    secs: i64,
+    has_time: bool
+}
+
+impl DateTime {
+    pub fn new(secs: i64, has_time: bool) -> DateTimeResult {
+        if !(MIN_SECS..=MAX_SECS).contains(&secs) {
+            return Err(DateTimeError::date_out_of_range());
+        }
+        Ok(DateTime {
+            secs,
+            has_time: has_time || secs.rem_euclid(SECS_PER_DAY) != 0
+        })
+    }
+
+    // ISO-8601 YYYY-MM-DD, YYYY-MM-DDTHH:MM or YYYY-MM-DDTHH:MM:SS, with optional trailing Z
+    pub fn parse(text: &str) -> DateTimeResult {
+        let invalid = || DateTimeError::invalid_date(text);
+        let body = text.strip_suffix('Z').unwrap_or(text);
+        let (date, time) = match body.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (body, None)
+        };
+
+        let ymd = parse_fields(date, '-', &[4, 2, 2]).ok_or_else(invalid)?;
+        let (year, month, day) = (ymd[0], ymd[1], ymd[2]);
+        if year < 1 || !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return Err(invalid());
+        }
+
+        let mut secs = days_from_civil(year, month, day) * SECS_PER_DAY;
+        if let Some(time) = time {
+            let hms = parse_fields(time, ':', &[2, 2, 2])
+                .or_else(|| parse_fields(time, ':', &[2, 2]))
+                .ok_or_else(invalid)?;
+            let (hour, minute, second) = (hms[0], hms[1], hms.get(2).copied().unwrap_or(0));
+            if hour > 23 || minute > 59 || second > 59 {
+                return Err(invalid());
+            }
+            secs += hour * SECS_PER_HOUR + minute * SECS_PER_MINUTE + second;
+        }
+        DateTime::new(secs, time.is_some())
+    }
+
+    pub fn now() -> DateTimeResult {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => DateTime::new(i64::try_from(elapsed.as_secs()).map_err(|_| DateTimeError::date_out_of_range())?, true),
+            Err(_) => Err(DateTimeError::date_out_of_range())
+        }
+    }
+
+    // Seconds since 1970-01-01T00:00:00
+    #[inline(always)]
+    pub fn secs(&self) -> i64 {
+        self.secs
+    }
+
+    #[inline(always)]
+    pub fn has_time(&self) -> bool {
+        self.has_time
+    }
+
+    pub fn add(&self, dur: &Duration) -> DateTimeResult {
+        DateTime::new(self.secs.checked_add(dur.secs).ok_or_else(DateTimeError::date_out_of_range)?, self.has_time)
+    }
+
+    pub fn sub(&self, dur: &Duration) -> DateTimeResult {
+        DateTime::new(self.secs.checked_sub(dur.secs).ok_or_else(DateTimeError::date_out_of_range)?, self.has_time)
+    }
+
+    #[inline(always)]
+    pub fn since(&self, other: &DateTime) -> Duration {
+        Duration::new(self.secs - other.secs)
+    }
+
+    // Calendar days from self to other, ignoring the time of day
+    #[inline(always)]
+    pub fn days_between(&self, other: &DateTime) -> i64 {
+        other.days() - self.days()
+    }
+
+    #[inline(always)]
+    pub fn weekday(&self) -> &'static str {
+        // 1970-01-01 was a Thursday
+        WEEKDAYS[(self.days() + 3).rem_euclid(7) as usize]
+    }
+
+    // Same day and time in a later or earlier month, clamped to the end of shorter months,
+    // e.g. add_months 2024-01-31 1 is 2024-02-29
+    pub fn add_months(&self, months: i64) -> DateTimeResult {
+        let (year, month, day) = civil_from_days(self.days());
+        let total = months.checked_add(year * 12 + month - 1).ok_or_else(DateTimeError::date_out_of_range)?;
+        let (new_year, new_month) = (total.div_euclid(12), total.rem_euclid(12) + 1);
+        if !(1..=9999).contains(&new_year) {
+            return Err(DateTimeError::date_out_of_range());
+        }
+        let new_day = day.min(days_in_month(new_year, new_month));
+        let time_of_day = self.secs.rem_euclid(SECS_PER_DAY);
+        DateTime::new(days_from_civil(new_year, new_month, new_day) * SECS_PER_DAY + time_of_day, self.has_time)
+    }
+
+    #[inline(always)]
+    fn days(&self) -> i64 {
+        self.secs.div_euclid(SECS_PER_DAY)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.days());
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)?;
+        if self.has_time {
+            let time_of_day = self.secs.rem_euclid(SECS_PER_DAY);
+            write!(
+                f,
+                "T{:02}:{:02}:{:02}",
+                time_of_day / SECS_PER_HOUR,
+                time_of_day % SECS_PER_HOUR / SECS_PER_MINUTE,
+                time_of_day % SECS_PER_MINUTE
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl cmp::PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.secs == other.secs
+    }
+}
+
+impl cmp::PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.secs.partial_cmp(&other.secs)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Duration - Signed length of time in whole seconds
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Duration {
+    secs: i64
+}
+
+impl Duration {
+    #[inline(always)]
+    pub fn new(secs: i64) -> Self {
+        Duration { secs }
+    }
+
+    // ISO-8601 PnW, PnD, PTnHnMnS and combinations, e.g. P1DT12H or PT90M.
+    // Years and months have no fixed length, so they are rejected; use add_months instead.
+    pub fn parse(text: &str) -> DurationResult {
+        let invalid = || DateTimeError::invalid_duration(text);
+        let body = text.strip_prefix('P').ok_or_else(invalid)?;
+        let (date_part, time_part) = match body.split_once('T') {
+            Some((date, time)) if !time.is_empty() => (date, time),
+            Some(_) => return Err(invalid()),
+            None => (body, "")
+        };
+
+        let mut secs: i64 = 0;
+        let mut components = 0;
+        for (part, designators) in [(date_part, "WD"), (time_part, "HMS")] {
+            let mut number = String::new();
+            let mut allowed = designators;
+            for ch in part.chars() {
+                if ch.is_ascii_digit() {
+                    number.push(ch);
+                    continue;
+                }
+                // Designators must appear in order and at most once
+                let pos = allowed.find(ch).ok_or_else(invalid)?;
+                allowed = &allowed[pos + 1..];
+                let count = number.parse::<i64>().map_err(|_| invalid())?;
+                let unit = match ch {
+                    'W' => SECS_PER_WEEK,
+                    'D' => SECS_PER_DAY,
+                    'H' => SECS_PER_HOUR,
+                    'M' => SECS_PER_MINUTE,
+                    _ => 1
+                };
+                secs = count.checked_mul(unit).and_then(|s| s.checked_add(secs)).ok_or_else(invalid)?;
+                components += 1;
+                number.clear();
+            }
+            if !number.is_empty() {
+                return Err(invalid());
+            }
+        }
+        if components == 0 {
+            return Err(invalid());
+        }
+        Ok(Duration::new(secs))
+    }
+
+    #[inline(always)]
+    pub fn secs(&self) -> i64 {
+        self.secs
+    }
+
+    pub fn add(&self, other: &Duration) -> DurationResult {
+        Ok(Duration::new(
+            self.secs.checked_add(other.secs).ok_or_else(DateTimeError::duration_out_of_range)?
+        ))
+    }
+
+    pub fn sub(&self, other: &Duration) -> DurationResult {
+        Ok(Duration::new(
+            self.secs.checked_sub(other.secs).ok_or_else(DateTimeError::duration_out_of_range)?
+        ))
+    }
+
+    pub fn mul(&self, factor: i64) -> DurationResult {
+        Ok(Duration::new(self.secs.checked_mul(factor).ok_or_else(DateTimeError::duration_out_of_range)?))
+    }
+
+    // Rounded to the nearest second. Integral factors stay exact, since durations
+    // beyond 2^53 seconds do not survive the trip through f64.
+    pub fn scale(&self, factor: f64) -> DurationResult {
+        if factor.fract() == 0.0 && factor.abs() < i64::MAX as f64 {
+            return self.mul(factor as i64);
+        }
+        let secs = (self.secs as f64 * factor).round();
+        if !secs.is_finite() || secs.abs() >= i64::MAX as f64 {
+            return Err(DateTimeError::duration_out_of_range());
+        }
+        Ok(Duration::new(secs as i64))
+    }
+
+    // Exact quotient only, since durations are whole seconds, e.g. PT1S / 3 is an error
+    pub fn div(&self, divisor: f64) -> DurationResult {
+        if divisor == 0.0 {
+            return Err(DateTimeError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        let quot = if divisor.fract() == 0.0 && divisor.abs() < i64::MAX as f64 {
+            let d = divisor as i64;
+            match self.secs.checked_rem(d) {
+                Some(0) => self.secs.checked_div(d).ok_or_else(DateTimeError::duration_out_of_range)?,
+                Some(_) => return Err(DateTimeError::inexact_division(self, divisor)),
+                None => return Err(DateTimeError::duration_out_of_range())
+            }
+        } else {
+            let secs = self.secs as f64 / divisor;
+            if secs.is_nan() || secs.fract() != 0.0 {
+                return Err(DateTimeError::inexact_division(self, divisor));
+            }
+            if secs.abs() >= i64::MAX as f64 {
+                return Err(DateTimeError::duration_out_of_range());
+            }
+            secs as i64
+        };
+        Ok(Duration::new(quot))
+    }
+
+    #[inline(always)]
+    pub fn ratio(&self, other: &Duration) -> f64 {
+        self.secs as f64 / other.secs as f64
+    }
+
+    #[inline(always)]
+    pub fn neg(&self) -> Self {
+        Duration::new(self.secs.saturating_neg())
+    }
+
+    #[inline(always)]
+    pub fn abs(&self) -> Self {
+        Duration::new(self.secs.saturating_abs())
+    }
+}
+
+// Non-zero parts only, e.g. 3d 4h 12m, with one sign for them all, e.g. -(1d 12h)
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.secs == 0 {
+            return write!(f, "0s");
+        }
+        let total = self.secs.unsigned_abs();
+        let parts = [
+            (total / SECS_PER_DAY as u64, "d"),
+            (total % SECS_PER_DAY as u64 / SECS_PER_HOUR as u64, "h"),
+            (total % SECS_PER_HOUR as u64 / SECS_PER_MINUTE as u64, "m"),
+            (total % SECS_PER_MINUTE as u64, "s")
+        ];
+        let text: Vec<String> = parts.iter().filter(|(n, _)| *n != 0).map(|(n, unit)| format!("{}{}", n, unit)).collect();
+        match (self.secs < 0, text.len()) {
+            (false, _) => write!(f, "{}", text.join(" ")),
+            (true, 1) => write!(f, "-{}", text[0]),
+            (true, _) => write!(f, "-({})", text.join(" "))
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Calendar Functions
+
+// Split text on sep into fields of exactly the given widths of ascii digits
+fn parse_fields(text: &str, sep: char, widths: &[usize]) -> Option<Vec<i64>> {
+    let fields: Vec<&str> = text.split(sep).collect();
+    if fields.len() != widths.len() {
+        return None;
+    }
+    fields
+        .iter()
+        .zip(widths)
+        .map(|(field, width)| {
+            if field.len() == *width && field.chars().all(|c| c.is_ascii_digit()) {
+                field.parse::<i64>().ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[inline(always)]
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date (Howard Hinnant's days_from_civil)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datetime_parse() {
+        let date = DateTime::parse("2024-03-15").unwrap();
+        assert!(!date.has_time());
+        assert_eq!(format!("{}", date), "2024-03-15");
+        assert_eq!(DateTime::parse("1970-01-01").unwrap().secs(), 0);
+        assert_eq!(DateTime::parse("1969-12-31T23:59:59Z").unwrap().secs(), -1);
+
+        let dt = DateTime::parse("2024-03-15T10:30").unwrap();
+        assert!(dt.has_time());
+        assert_eq!(format!("{}", dt), "2024-03-15T10:30:00");
+        assert_eq!(format!("{}", DateTime::parse("2024-03-15T10:30:05Z").unwrap()), "2024-03-15T10:30:05");
+        assert_eq!(format!("{}", DateTime::parse("0001-01-01").unwrap()), "0001-01-01");
+        assert_eq!(format!("{}", DateTime::parse("9999-12-31T23:59:59").unwrap()), "9999-12-31T23:59:59");
+
+        assert!(DateTime::parse("2024-02-29").is_ok());
+        assert!(DateTime::parse("2023-02-29").is_err());
+        assert!(DateTime::parse("1900-02-29").is_err());
+        assert!(DateTime::parse("2000-02-29").is_ok());
+        assert!(DateTime::parse("2024-13-01").is_err());
+        assert!(DateTime::parse("2024-04-31").is_err());
+        assert!(DateTime::parse("0000-01-01").is_err());
+        assert!(DateTime::parse("2024-1-01").is_err());
+        assert!(DateTime::parse("2024-01-01T24:00").is_err());
+        assert!(DateTime::parse("2024-01-01T").is_err());
+        assert!(DateTime::parse("2024-01-01T10").is_err());
+        assert_eq!(format!("{}", DateTime::parse("2024-01-32").unwrap_err()), "Invalid date - '2024-01-32'");
+    }
+
+    #[test]
+    fn test_datetime_arithmetic() {
+        let date = DateTime::parse("2024-02-28").unwrap();
+        assert_eq!(format!("{}", date.add(&Duration::new(SECS_PER_DAY)).unwrap()), "2024-02-29");
+        assert_eq!(format!("{}", date.add(&Duration::new(2 * SECS_PER_DAY)).unwrap()), "2024-03-01");
+        assert_eq!(format!("{}", date.add(&Duration::new(SECS_PER_HOUR)).unwrap()), "2024-02-28T01:00:00");
+        assert_eq!(format!("{}", date.sub(&Duration::new(SECS_PER_WEEK)).unwrap()), "2024-02-21");
+
+        let later = DateTime::parse("2024-03-02T04:12").unwrap();
+        assert_eq!(format!("{}", later.since(&date)), "3d 4h 12m");
+        assert_eq!(format!("{}", date.since(&later)), "-(3d 4h 12m)");
+
+        assert_eq!(date.days_between(&later), 3);
+        assert_eq!(later.days_between(&date), -3);
+        assert_eq!(
+            DateTime::parse("1969-12-31T23:00")
+                .unwrap()
+                .days_between(&DateTime::parse("1970-01-01T01:00").unwrap()),
+            1
+        );
+
+        assert!(DateTime::parse("9999-12-31").unwrap().add(&Duration::new(SECS_PER_DAY)).is_err());
+        assert!(date.add(&Duration::new(i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_datetime_weekday() {
+        assert_eq!(DateTime::parse("1970-01-01").unwrap().weekday(), "Thursday");
+        assert_eq!(DateTime::parse("2024-03-15T23:59").unwrap().weekday(), "Friday");
+        assert_eq!(DateTime::parse("1969-12-28").unwrap().weekday(), "Sunday");
+        assert_eq!(DateTime::parse("2000-01-03").unwrap().weekday(), "Monday");
+    }
+
+    #[test]
+    fn test_datetime_add_months() {
+        let add = |text: &str, months: i64| format!("{}", DateTime::parse(text).unwrap().add_months(months).unwrap());
+        assert_eq!(add("2024-01-31", 1), "2024-02-29");
+        assert_eq!(add("2023-01-31", 1), "2023-02-28");
+        assert_eq!(add("2024-03-31", -1), "2024-02-29");
+        assert_eq!(add("2024-11-15", 3), "2025-02-15");
+        assert_eq!(add("2024-01-15", -13), "2022-12-15");
+        assert_eq!(add("2024-01-15T08:30", 12), "2025-01-15T08:30:00");
+        assert!(DateTime::parse("9999-12-01").unwrap().add_months(1).is_err());
+        assert!(DateTime::parse("2024-01-01").unwrap().add_months(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_duration_parse() {
+        let parse = |text: &str| Duration::parse(text).unwrap().secs();
+        assert_eq!(parse("P1D"), SECS_PER_DAY);
+        assert_eq!(parse("P2W"), 2 * SECS_PER_WEEK);
+        assert_eq!(parse("PT90M"), 90 * SECS_PER_MINUTE);
+        assert_eq!(parse("PT45S"), 45);
+        assert_eq!(parse("P3DT4H12M"), 3 * SECS_PER_DAY + 4 * SECS_PER_HOUR + 12 * SECS_PER_MINUTE);
+        assert_eq!(parse("P0D"), 0);
+
+        assert!(Duration::parse("P").is_err());
+        assert!(Duration::parse("PT").is_err());
+        assert!(Duration::parse("P1DT").is_err());
+        assert!(Duration::parse("P1Y").is_err());
+        assert!(Duration::parse("P1M").is_err());
+        assert!(Duration::parse("PT1H1H").is_err());
+        assert!(Duration::parse("PT1M1H").is_err());
+        assert!(Duration::parse("P1H").is_err());
+        assert!(Duration::parse("PT5").is_err());
+        assert!(Duration::parse("P99999999999999999999D").is_err());
+        assert_eq!(format!("{}", Duration::parse("P1X").unwrap_err()), "Invalid duration - 'P1X'");
+    }
+
+    #[test]
+    fn test_duration_display() {
+        assert_eq!(format!("{}", Duration::new(0)), "0s");
+        assert_eq!(format!("{}", Duration::new(45)), "45s");
+        assert_eq!(format!("{}", Duration::new(SECS_PER_DAY + 5)), "1d 5s");
+        assert_eq!(format!("{}", Duration::new(-90 * SECS_PER_MINUTE)), "-(1h 30m)");
+        assert_eq!(format!("{}", Duration::new(-SECS_PER_DAY)), "-1d");
+        assert_eq!(format!("{}", Duration::new(i64::MIN)), "-(106751991167300d 15h 30m 8s)");
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let hour = Duration::new(SECS_PER_HOUR);
+        assert_eq!(hour.add(&hour).unwrap(), Duration::new(2 * SECS_PER_HOUR));
+        assert_eq!(hour.sub(&hour).unwrap(), Duration::new(0));
+        assert_eq!(hour.scale(1.5).unwrap(), Duration::new(90 * SECS_PER_MINUTE));
+        assert_eq!(Duration::new(1).scale(0.4).unwrap(), Duration::new(0));
+        assert!(hour.scale(f64::INFINITY).is_err());
+        assert_eq!(hour.mul(-3).unwrap(), Duration::new(-3 * SECS_PER_HOUR));
+        assert!(hour.mul(i64::MAX).is_err());
+        let big = Duration::new((1 << 60) + 1);
+        assert_eq!(big.scale(1.0).unwrap(), big);
+        assert_eq!(big.scale(-1.0).unwrap(), big.neg());
+        assert!(big.scale(16.0).is_err());
+        assert_eq!(hour.div(4.0).unwrap(), Duration::new(15 * SECS_PER_MINUTE));
+        assert_eq!(hour.div(-0.5).unwrap(), Duration::new(-2 * SECS_PER_HOUR));
+        assert_eq!(big.div(1.0).unwrap(), big);
+        assert_eq!(
+            format!("{}", Duration::new(1).div(3.0).unwrap_err()),
+            "Duration not a whole number of seconds - '/ 1s 3'"
+        );
+        assert_eq!(
+            format!("{}", Duration::new(1).div(0.3).unwrap_err()),
+            "Duration not a whole number of seconds - '/ 1s 0.3'"
+        );
+        assert_eq!(format!("{}", hour.div(0.0).unwrap_err()), "Division by zero");
+        assert!(Duration::new(i64::MIN).div(-1.0).is_err());
+        assert_eq!(hour.ratio(&Duration::new(SECS_PER_MINUTE)), 60.0);
+        assert_eq!(hour.neg(), Duration::new(-SECS_PER_HOUR));
+        assert_eq!(hour.neg().abs(), hour);
+        assert!(Duration::new(i64::MAX).add(&hour).is_err());
+        assert!(hour < Duration::new(SECS_PER_DAY));
+    }
+}
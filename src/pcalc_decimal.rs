@@ -0,0 +1,640 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Pow, Signed, ToPrimitive, Zero};
+use std::cell::Cell;
+use std::cmp;
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Decimal Error
+
+pub type DecimalError = Error;
+
+impl DecimalError {
+    pub fn invalid_decimal(text: &str) -> Self {
+        DecimalError::from_string(ErrorKind::Domain, format!("Invalid decimal - '{}'", text))
+    }
+
+    pub fn decimal_overflow() -> Self {
+        DecimalError::new(ErrorKind::Domain, "Decimal overflow")
+    }
+
+    pub fn decimal_underflow() -> Self {
+        DecimalError::new(ErrorKind::Domain, "Decimal underflow - result too small for 28 fractional digits")
+    }
+}
+
+pub type DecimalResult = Result<Decimal, DecimalError>;
+
+// --------------------------------------------------------------------------------
+// Rounding - How digits are dropped by / and round
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    HalfEven, // Ties to even, e.g. 2.345 -> 2.34
+    HalfUp,   // Ties away from zero, e.g. 2.345 -> 2.35
+    Truncate  // Toward zero, e.g. 2.349 -> 2.34
+}
+
+impl Rounding {
+    pub fn parse(name: &str) -> Option<Rounding> {
+        match name {
+            "half-even" => Some(Rounding::HalfEven),
+            "half-up" => Some(Rounding::HalfUp),
+            "truncate" => Some(Rounding::Truncate),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rounding::HalfEven => "half-even",
+            Rounding::HalfUp => "half-up",
+            Rounding::Truncate => "truncate"
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Decimal Context - Scale and rounding used by / and round
+//
+// The context belongs to the Environment, which binds it while its code runs, like a
+// local context of Python's decimal module, so operations keep their plain value
+// signatures. Without a scale, / keeps up to MAX_DIGITS significant digits and round
+// rounds to an integer.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalContext {
+    scale: Option<u32>,
+    rounding: Rounding
+}
+
+impl DecimalContext {
+    pub fn new(scale: Option<u32>, rounding: Rounding) -> Result<Self, DecimalError> {
+        match scale {
+            Some(s) if s > MAX_SCALE => Err(DecimalError::new(ErrorKind::Domain, &format!("Decimal scale must be at most {}", MAX_SCALE))),
+            _ => Ok(DecimalContext { scale, rounding })
+        }
+    }
+
+    #[inline(always)]
+    pub fn scale(&self) -> Option<u32> {
+        self.scale
+    }
+
+    #[inline(always)]
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+}
+
+impl Default for DecimalContext {
+    fn default() -> Self {
+        DecimalContext {
+            scale: None,
+            rounding: Rounding::HalfEven
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: Cell<DecimalContext> = Cell::new(DecimalContext::default());
+}
+
+#[inline(always)]
+pub fn context() -> DecimalContext {
+    CONTEXT.with(|ctx| ctx.get())
+}
+
+// Run f with ctx as the context, then restore the previous one
+pub fn with_context<T>(ctx: DecimalContext, f: impl FnOnce() -> T) -> T {
+    struct Restore(DecimalContext);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CONTEXT.with(|cell| cell.set(self.0));
+        }
+    }
+    let _restore = Restore(CONTEXT.with(|cell| cell.replace(ctx)));
+    f()
+}
+
+// --------------------------------------------------------------------------------
+// Decimal - Exact base 10 number, coeff / 10^scale
+//
+// Holds at most MAX_DIGITS significant digits and MAX_SCALE fractional digits.
+// +, -, * and % are exact within those limits; digits beyond them are rounded with the
+// context rounding, and integer parts that do not fit are an overflow error.
+
+const MAX_DIGITS: u32 = 28;
+const MAX_SCALE: u32 = 28;
+
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    coeff: BigInt,
+    scale: u32
+}
+
+impl Decimal {
+    pub fn new(coeff: BigInt, scale: u32) -> DecimalResult {
+        normalize(coeff, scale, context().rounding)
+    }
+
+    #[inline(always)]
+    pub fn from_int(i: i64) -> Self {
+        Decimal {
+            coeff: BigInt::from(i),
+            scale: 0
+        }
+    }
+
+    // Digits with optional sign, fraction and exponent, e.g. 19.99, -0.5 or 1.5e3
+    pub fn parse(text: &str) -> DecimalResult {
+        let invalid = || DecimalError::invalid_decimal(text);
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(pos) => (&text[..pos], text[pos + 1..].parse::<i32>().map_err(|_| invalid())?),
+            None => (text, 0)
+        };
+        let (sign, digits) = match mantissa.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", mantissa.strip_prefix('+').unwrap_or(mantissa))
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty() && frac_part.is_empty() || !format!("{}{}", int_part, frac_part).chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let coeff = format!("{}{}{}", sign, int_part, frac_part).parse::<BigInt>().map_err(|_| invalid())?;
+        let scale = i64::try_from(frac_part.len()).map_err(|_| invalid())? - i64::from(exponent);
+        if scale > i64::from(MAX_SCALE) + i64::from(digit_count(&coeff)) {
+            // Too small to show at MAX_SCALE digits
+            Decimal::new(BigInt::zero(), MAX_SCALE)
+        } else if scale >= 0 {
+            Decimal::new(coeff, u32::try_from(scale).map_err(|_| invalid())?)
+        } else if -scale > i64::from(MAX_DIGITS) {
+            Err(DecimalError::decimal_overflow())
+        } else {
+            Decimal::new(coeff * pow10(u32::try_from(-scale).map_err(|_| invalid())?), 0)
+        }
+    }
+
+    // Exact value of the float's shortest representation, e.g. 0.1 is 0.1
+    pub fn from_f64(x: f64) -> DecimalResult {
+        if !x.is_finite() {
+            return Err(DecimalError::invalid_decimal(&x.to_string()));
+        }
+        Decimal::parse(&format!("{:e}", x))
+    }
+
+    pub fn from_rational(r: &BigRational) -> DecimalResult {
+        divide(r.numer().clone(), r.denom().clone())
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse::<f64>().unwrap_or(f64::NAN)
+    }
+
+    // Integral value within i64
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.is_integer() {
+            self.trunc().coeff.to_i64()
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.coeff.is_zero()
+    }
+
+    #[inline(always)]
+    pub fn is_negative(&self) -> bool {
+        self.coeff.is_negative()
+    }
+
+    #[inline(always)]
+    pub fn is_integer(&self) -> bool {
+        (&self.coeff % pow10(self.scale)).is_zero()
+    }
+
+    pub fn add(&self, other: &Decimal) -> DecimalResult {
+        let (l, r, scale) = align(self, other);
+        Decimal::new(l + r, scale)
+    }
+
+    pub fn sub(&self, other: &Decimal) -> DecimalResult {
+        let (l, r, scale) = align(self, other);
+        Decimal::new(l - r, scale)
+    }
+
+    pub fn mul(&self, other: &Decimal) -> DecimalResult {
+        Decimal::new(&self.coeff * &other.coeff, self.scale + other.scale)
+    }
+
+    // Rounded to the context scale, or to MAX_DIGITS significant digits without one
+    pub fn div(&self, other: &Decimal) -> DecimalResult {
+        if other.is_zero() {
+            return Err(DecimalError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        let (l, r, _) = align(self, other);
+        divide(l, r)
+    }
+
+    // Remainder with the sign of self, like % on integers
+    pub fn rem(&self, other: &Decimal) -> DecimalResult {
+        if other.is_zero() {
+            return Err(DecimalError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        let (l, r, scale) = align(self, other);
+        Decimal::new(l % r, scale)
+    }
+
+    pub fn powi(&self, exp: i64) -> DecimalResult {
+        let power = self.powu(exp.unsigned_abs());
+        if exp >= 0 {
+            return power;
+        }
+        match power {
+            // The power is too large to hold, so raise the reciprocal instead, which may be too small to show
+            Err(_) => match Decimal::from_int(1).div(self)?.powu(exp.unsigned_abs())? {
+                small if small.is_zero() => Err(DecimalError::decimal_underflow()),
+                small => Ok(small)
+            },
+            // The power rounded to zero, so its reciprocal is too large to hold
+            Ok(power) if power.is_zero() && !self.is_zero() => Err(DecimalError::decimal_overflow()),
+            Ok(power) => Decimal::from_int(1).div(&power)
+        }
+    }
+
+    // Power by repeated squaring
+    fn powu(&self, exp: u64) -> DecimalResult {
+        let mut result = Decimal::from_int(1);
+        let mut base = self.clone();
+        let mut n = exp;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.mul(&base)?;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = base.mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    #[inline(always)]
+    pub fn neg(&self) -> Self {
+        Decimal {
+            coeff: -&self.coeff,
+            scale: self.scale
+        }
+    }
+
+    #[inline(always)]
+    pub fn abs(&self) -> Self {
+        Decimal {
+            coeff: self.coeff.abs(),
+            scale: self.scale
+        }
+    }
+
+    // Rounded to the context scale, or to an integer without one
+    pub fn round(&self) -> Self {
+        let ctx = context();
+        self.with_scale(ctx.scale.unwrap_or(0), ctx.rounding)
+    }
+
+    #[inline(always)]
+    pub fn trunc(&self) -> Self {
+        self.with_scale(0, Rounding::Truncate)
+    }
+
+    pub fn floor(&self) -> Self {
+        let t = self.trunc();
+        if self.is_negative() && !self.is_integer() {
+            Decimal { coeff: t.coeff - 1, scale: 0 }
+        } else {
+            t
+        }
+    }
+
+    pub fn ceil(&self) -> Self {
+        let t = self.trunc();
+        if !self.is_negative() && !self.is_integer() {
+            Decimal { coeff: t.coeff + 1, scale: 0 }
+        } else {
+            t
+        }
+    }
+
+    pub fn fract(&self) -> Self {
+        Decimal {
+            coeff: &self.coeff % pow10(self.scale),
+            scale: self.scale
+        }
+    }
+
+    // Same value with scale fractional digits; only rounds when the scale shrinks
+    fn with_scale(&self, scale: u32, rounding: Rounding) -> Self {
+        if scale >= self.scale {
+            return self.clone();
+        }
+        Decimal {
+            coeff: round_digits(&self.coeff, self.scale - scale, rounding),
+            scale
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.coeff.abs().to_string();
+        let sign = if self.is_negative() { "-" } else { "" };
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let padded = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - self.scale as usize);
+        write!(f, "{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+// Equal values compare equal regardless of scale, e.g. 2.50 == 2.5
+impl cmp::PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl cmp::Eq for Decimal {}
+
+impl cmp::PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::Ord for Decimal {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        let (l, r, _) = align(self, other);
+        l.cmp(&r)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Helpers
+
+#[inline(always)]
+fn pow10(n: u32) -> BigInt {
+    BigInt::from(10).pow(n)
+}
+
+#[inline(always)]
+fn digit_count(n: &BigInt) -> u32 {
+    if n.is_zero() {
+        0
+    } else {
+        n.abs().to_string().len() as u32
+    }
+}
+
+#[inline(always)]
+fn is_odd(n: &BigInt) -> bool {
+    !(n % 2u32).is_zero()
+}
+
+// Coefficients of both operands at their common scale
+fn align(l: &Decimal, r: &Decimal) -> (BigInt, BigInt, u32) {
+    let scale = l.scale.max(r.scale);
+    (&l.coeff * pow10(scale - l.scale), &r.coeff * pow10(scale - r.scale), scale)
+}
+
+// Drop the last digits of n, rounding the result
+fn round_digits(n: &BigInt, digits: u32, rounding: Rounding) -> BigInt {
+    let divisor = pow10(digits);
+    let (quot, rem) = (n / &divisor, n % &divisor);
+    let away = match rounding {
+        Rounding::Truncate => false,
+        Rounding::HalfUp => rem.abs() * 2 >= divisor,
+        Rounding::HalfEven => {
+            let twice = rem.abs() * 2;
+            twice > divisor || (twice == divisor && is_odd(&quot))
+        }
+    };
+    if !away {
+        quot
+    } else if n.is_negative() {
+        quot - 1
+    } else {
+        quot + 1
+    }
+}
+
+// Round coeff / 10^scale to MAX_SCALE fractional and MAX_DIGITS significant digits
+fn normalize(mut coeff: BigInt, mut scale: u32, rounding: Rounding) -> DecimalResult {
+    if scale > MAX_SCALE {
+        coeff = round_digits(&coeff, scale - MAX_SCALE, rounding);
+        scale = MAX_SCALE;
+    }
+    // Rounding can carry into a new digit, e.g. 9.99 -> 10.0, so check again
+    while digit_count(&coeff) > MAX_DIGITS {
+        let excess = digit_count(&coeff) - MAX_DIGITS;
+        if excess > scale {
+            return Err(DecimalError::decimal_overflow());
+        }
+        coeff = round_digits(&coeff, excess, rounding);
+        scale -= excess;
+    }
+    Ok(Decimal { coeff, scale })
+}
+
+// numer / denom at the context scale, or to MAX_DIGITS significant digits with trailing zeros removed
+fn divide(numer: BigInt, denom: BigInt) -> DecimalResult {
+    let ctx = context();
+    let scale = match ctx.scale {
+        Some(s) => s,
+        None => {
+            // Integer digits of the quotient, or at most 0 when it is below 1
+            let mut int_digits = i64::from(digit_count(&numer)) - i64::from(digit_count(&denom));
+            if int_digits >= 0 && numer.abs() >= denom.abs() * pow10(int_digits as u32) {
+                int_digits += 1;
+            }
+            (i64::from(MAX_DIGITS) - int_digits).clamp(0, i64::from(MAX_SCALE)) as u32
+        }
+    };
+
+    let (numer, denom) = if denom.is_negative() { (-numer, -denom) } else { (numer, denom) };
+    let scaled = numer * pow10(scale);
+    let (quot, rem) = (&scaled / &denom, &scaled % &denom);
+    let twice = rem.abs() * 2;
+    let away = match ctx.rounding {
+        Rounding::Truncate => false,
+        Rounding::HalfUp => twice >= denom,
+        Rounding::HalfEven => twice > denom || (twice == denom && is_odd(&quot))
+    };
+    let coeff = if !away {
+        quot
+    } else if scaled.is_negative() {
+        quot - 1
+    } else {
+        quot + 1
+    };
+
+    let mut result = normalize(coeff, scale, ctx.rounding)?;
+    if ctx.scale.is_none() {
+        while result.scale > 0 && (&result.coeff % 10u32).is_zero() {
+            result.coeff /= 10u32;
+            result.scale -= 1;
+        }
+    }
+    Ok(result)
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    fn with_settings<T>(scale: Option<u32>, rounding: Rounding, test: impl FnOnce() -> T) -> T {
+        with_context(DecimalContext::new(scale, rounding).unwrap(), test)
+    }
+
+    #[test]
+    fn test_decimal_parse() {
+        assert_eq!(format!("{}", dec("19.99")), "19.99");
+        assert_eq!(format!("{}", dec("-0.05")), "-0.05");
+        assert_eq!(format!("{}", dec("2.50")), "2.50");
+        assert_eq!(format!("{}", dec("+7")), "7");
+        assert_eq!(format!("{}", dec(".5")), "0.5");
+        assert_eq!(format!("{}", dec("1.5e3")), "1500");
+        assert_eq!(format!("{}", dec("15e-3")), "0.015");
+        assert_eq!(format!("{}", dec("0.1234567890123456789012345678901")), "0.1234567890123456789012345679");
+
+        assert!(Decimal::parse("").is_err());
+        assert!(Decimal::parse(".").is_err());
+        assert!(Decimal::parse("1.2.3").is_err());
+        assert!(Decimal::parse("abc").is_err());
+        assert!(Decimal::parse("1e").is_err());
+        assert_eq!(format!("{}", Decimal::parse("1x").unwrap_err()), "Invalid decimal - '1x'");
+        assert_eq!(format!("{}", Decimal::parse("1e30").unwrap_err()), "Decimal overflow");
+        assert!(Decimal::parse("1e-2000000000").unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_decimal_conversion() {
+        assert_eq!(format!("{}", Decimal::from_f64(0.1).unwrap()), "0.1");
+        assert_eq!(format!("{}", Decimal::from_f64(-2.5e-3).unwrap()), "-0.0025");
+        assert!(Decimal::from_f64(f64::NAN).is_err());
+        assert_eq!(
+            format!("{}", Decimal::from_rational(&BigRational::new(1.into(), 3.into())).unwrap()),
+            "0.3333333333333333333333333333"
+        );
+        assert_eq!(dec("2.25").to_f64(), 2.25);
+        assert_eq!(dec("12.00").to_i64(), Some(12));
+        assert_eq!(dec("12.5").to_i64(), None);
+        assert_eq!(Decimal::from_int(-3), dec("-3.0"));
+    }
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        assert_eq!(format!("{}", dec("0.1").add(&dec("0.2")).unwrap()), "0.3");
+        assert_eq!(format!("{}", dec("19.99").add(&dec("0.01")).unwrap()), "20.00");
+        assert_eq!(format!("{}", dec("1").sub(&dec("0.99")).unwrap()), "0.01");
+        assert_eq!(format!("{}", dec("19.99").mul(&dec("3")).unwrap()), "59.97");
+        assert_eq!(format!("{}", dec("1.5").mul(&dec("1.5")).unwrap()), "2.25");
+        assert_eq!(format!("{}", dec("10").rem(&dec("3")).unwrap()), "1");
+        assert_eq!(format!("{}", dec("-7.5").rem(&dec("2")).unwrap()), "-1.5");
+        assert_eq!(format!("{}", dec("1.1").powi(2).unwrap()), "1.21");
+        assert_eq!(format!("{}", dec("2").powi(-2).unwrap()), "0.25");
+        assert_eq!(format!("{}", dec("0.1").powi(-1000).unwrap_err()), "Decimal overflow");
+        assert_eq!(
+            format!("{}", dec("10").powi(-30).unwrap_err()),
+            "Decimal underflow - result too small for 28 fractional digits"
+        );
+        assert_eq!(format!("{}", dec("10").powi(-28).unwrap()), "0.0000000000000000000000000001");
+        assert_eq!(format!("{}", dec("10").powi(30).unwrap_err()), "Decimal overflow");
+        assert_eq!(format!("{}", dec("0").powi(-2).unwrap_err()), "Division by zero");
+        assert_eq!(
+            format!("{}", dec("9999999999999999999999999999").add(&dec("0.4")).unwrap()),
+            "9999999999999999999999999999"
+        );
+        assert_eq!(
+            format!("{}", dec("9999999999999999999999999999").add(&dec("1")).unwrap_err()),
+            "Decimal overflow"
+        );
+        assert!(dec("1").rem(&dec("0")).is_err());
+    }
+
+    #[test]
+    fn test_decimal_divide() {
+        assert_eq!(format!("{}", dec("1").div(&dec("3")).unwrap()), "0.3333333333333333333333333333");
+        assert_eq!(format!("{}", dec("2").div(&dec("3")).unwrap()), "0.6666666666666666666666666667");
+        assert_eq!(format!("{}", dec("10.00").div(&dec("4")).unwrap()), "2.5");
+        assert_eq!(format!("{}", dec("-1").div(&dec("8")).unwrap()), "-0.125");
+        assert_eq!(format!("{}", dec("100").div(&dec("0.5")).unwrap()), "200");
+        assert_eq!(format!("{}", dec("1").div(&dec("0")).unwrap_err()), "Division by zero");
+
+        with_settings(Some(2), Rounding::HalfEven, || {
+            assert_eq!(format!("{}", dec("10").div(&dec("4")).unwrap()), "2.50");
+            assert_eq!(format!("{}", dec("0.125").div(&dec("1")).unwrap()), "0.12");
+            assert_eq!(format!("{}", dec("0.135").div(&dec("1")).unwrap()), "0.14");
+            assert_eq!(format!("{}", dec("-0.125").div(&dec("1")).unwrap()), "-0.12");
+        });
+        with_settings(Some(2), Rounding::HalfUp, || {
+            assert_eq!(format!("{}", dec("0.125").div(&dec("1")).unwrap()), "0.13");
+            assert_eq!(format!("{}", dec("-0.125").div(&dec("1")).unwrap()), "-0.13");
+        });
+        with_settings(Some(2), Rounding::Truncate, || {
+            assert_eq!(format!("{}", dec("2").div(&dec("3")).unwrap()), "0.66");
+            assert_eq!(format!("{}", dec("-2").div(&dec("3")).unwrap()), "-0.66");
+        });
+    }
+
+    #[test]
+    fn test_decimal_round() {
+        assert_eq!(format!("{}", dec("2.5").round()), "2");
+        assert_eq!(format!("{}", dec("3.5").round()), "4");
+        assert_eq!(format!("{}", dec("-2.5").round()), "-2");
+        assert_eq!(format!("{}", dec("7").round()), "7");
+        with_settings(Some(2), Rounding::HalfEven, || assert_eq!(format!("{}", dec("2.345").round()), "2.34"));
+        with_settings(Some(2), Rounding::HalfUp, || assert_eq!(format!("{}", dec("2.345").round()), "2.35"));
+        with_settings(Some(2), Rounding::Truncate, || assert_eq!(format!("{}", dec("2.349").round()), "2.34"));
+        with_settings(Some(2), Rounding::HalfUp, || assert_eq!(format!("{}", dec("2.3").round()), "2.3"));
+
+        assert_eq!(format!("{}", dec("-2.7").trunc()), "-2");
+        assert_eq!(format!("{}", dec("-2.7").floor()), "-3");
+        assert_eq!(format!("{}", dec("2.1").ceil()), "3");
+        assert_eq!(format!("{}", dec("-2.1").ceil()), "-2");
+        assert_eq!(format!("{}", dec("-2.75").fract()), "-0.75");
+        assert_eq!(format!("{}", dec("-2.75").abs()), "2.75");
+        assert_eq!(format!("{}", dec("2.75").neg()), "-2.75");
+    }
+
+    #[test]
+    fn test_decimal_context() {
+        assert_eq!(Rounding::parse("half-up"), Some(Rounding::HalfUp));
+        assert_eq!(Rounding::parse("up"), None);
+        assert_eq!(Rounding::Truncate.name(), "truncate");
+        assert_eq!(DecimalContext::default().scale(), None);
+        assert_eq!(DecimalContext::default().rounding(), Rounding::HalfEven);
+        assert_eq!(
+            format!("{}", DecimalContext::new(Some(29), Rounding::HalfUp).unwrap_err()),
+            "Decimal scale must be at most 28"
+        );
+    }
+
+    #[test]
+    fn test_decimal_compare() {
+        assert_eq!(dec("2.50"), dec("2.5"));
+        assert!(dec("0.1") < dec("0.11"));
+        assert!(dec("-1") < dec("0.001"));
+        assert!(dec("1.0") > dec("0.99"));
+    }
+}
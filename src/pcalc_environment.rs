@@ -1,25 +1,55 @@
+use crate::pcalc_angle::Angle;
+use crate::pcalc_datetime::{DateTime, DateTimeResult};
+use crate::pcalc_decimal::{self, DecimalContext};
+use crate::pcalc_format::Format;
 use crate::pcalc_function::{FunctionPtr, FunctionResult};
 use crate::pcalc_function_table::{FunctionTable, FunctionTablePtr};
+use crate::pcalc_tolerance::Tolerance;
 use crate::pcalc_value::{Value, ValueResult};
 use crate::pcalc_variable_table::VariableTable;
+use crate::pcalc_word::{self, WordContext};
 
 pub struct Environment {
     vars: VariableTable,
-    funcs: FunctionTablePtr
+    funcs: FunctionTablePtr,
+    format: Format,
+    tolerance: Tolerance,
+    strict: bool,
+    truthy: bool,
+    angle: Angle,
+    decimal: DecimalContext,
+    word: WordContext,
+    now: Option<DateTime>
 }
 
 impl Environment {
     pub fn new() -> Self {
         Environment {
             vars: VariableTable::new(),
-            funcs: FunctionTablePtr::new(FunctionTable::new())
+            funcs: FunctionTablePtr::new(FunctionTable::new()),
+            format: Format::new(),
+            tolerance: Tolerance::new(),
+            strict: false,
+            truthy: false,
+            angle: Angle::Radians,
+            decimal: DecimalContext::default(),
+            word: WordContext::default(),
+            now: None
         }
     }
 
     pub fn with_parent_funcs(parent: &Environment) -> Self {
         Environment {
             vars: VariableTable::new(),
-            funcs: FunctionTablePtr::clone(&parent.funcs)
+            funcs: FunctionTablePtr::clone(&parent.funcs),
+            format: parent.format.clone(),
+            tolerance: parent.tolerance.clone(),
+            strict: parent.strict,
+            truthy: parent.truthy,
+            angle: parent.angle,
+            decimal: parent.decimal,
+            word: parent.word,
+            now: parent.now
         }
     }
 
@@ -39,7 +69,7 @@ impl Environment {
     }
 
     #[inline(always)]
-    pub fn get_func(&self, name: &str) -> FunctionResult {
+    pub fn get_func(&self, name: &str) -> FunctionResult<'_> {
         self.funcs.get(name)
     }
 
@@ -48,6 +78,102 @@ impl Environment {
         FunctionTablePtr::get_mut(&mut self.funcs).expect("Missing funcs table").def(name, func);
     }
 
+    #[inline(always)]
+    pub fn format(&self) -> &Format {
+        &self.format
+    }
+
+    #[inline(always)]
+    pub fn format_mut(&mut self) -> &mut Format {
+        &mut self.format
+    }
+
+    // Approximate equality settings of ~= and !~=
+    #[inline(always)]
+    pub fn tolerance(&self) -> &Tolerance {
+        &self.tolerance
+    }
+
+    #[inline(always)]
+    pub fn tolerance_mut(&mut self) -> &mut Tolerance {
+        &mut self.tolerance
+    }
+
+    // Strict numeric mode, where operations producing NaN or infinity are domain errors
+    #[inline(always)]
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    #[inline(always)]
+    pub fn set_strict(&mut self, flag: bool) {
+        self.strict = flag;
+    }
+
+    // Truthy mode, where logical ops take any value as a condition does, instead of only booleans
+    #[inline(always)]
+    pub fn is_truthy(&self) -> bool {
+        self.truthy
+    }
+
+    #[inline(always)]
+    pub fn set_truthy(&mut self, flag: bool) {
+        self.truthy = flag;
+    }
+
+    // Unit of trig op inputs and inverse trig op outputs
+    #[inline(always)]
+    pub fn angle(&self) -> Angle {
+        self.angle
+    }
+
+    #[inline(always)]
+    pub fn set_angle(&mut self, angle: Angle) {
+        self.angle = angle;
+    }
+
+    // Scale and rounding of decimal operations
+    #[inline(always)]
+    pub fn decimal(&self) -> DecimalContext {
+        self.decimal
+    }
+
+    #[inline(always)]
+    pub fn set_decimal(&mut self, ctx: DecimalContext) {
+        self.decimal = ctx;
+    }
+
+    // Word size and overflow mode of integer operations
+    #[inline(always)]
+    pub fn word(&self) -> WordContext {
+        self.word
+    }
+
+    #[inline(always)]
+    pub fn set_word(&mut self, ctx: WordContext) {
+        self.word = ctx;
+    }
+
+    // Run f with this environment's decimal and word contexts bound
+    #[inline(always)]
+    pub fn in_context<T>(&self, f: impl FnOnce() -> T) -> T {
+        bind_context(self.decimal, self.word, f)
+    }
+
+    // Pinned time when set, e.g. for repeatable tests, otherwise the system clock
+    #[inline(always)]
+    pub fn now(&self) -> DateTimeResult {
+        match self.now {
+            Some(dt) => Ok(dt),
+            None => DateTime::now()
+        }
+    }
+
+    #[inline(always)]
+    pub fn pin_now(&mut self, now: Option<DateTime>) {
+        self.now = now;
+    }
+
     #[inline(always)]
     pub fn reset(&mut self) {
         self.vars.reset();
@@ -69,7 +195,7 @@ impl Environment {
         let pfuns: bool = !self.funcs.is_empty();
         let newln: bool = pvars && pfuns;
         if pvars {
-            self.vars.show();
+            self.vars.show(&self.format);
         }
         if newln {
             println!();
@@ -80,6 +206,12 @@ impl Environment {
     }
 }
 
+// Run f with the given decimal and word contexts bound, e.g. while an environment is borrowed mutably
+#[inline(always)]
+pub fn bind_context<T>(decimal: DecimalContext, word: WordContext, f: impl FnOnce() -> T) -> T {
+    pcalc_decimal::with_context(decimal, || pcalc_word::with_context(word, f))
+}
+
 impl Default for Environment {
     fn default() -> Self {
         Self::new()
@@ -89,8 +221,10 @@ impl Default for Environment {
 // --------------------------------------------------------------------------------
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
+    use crate::pcalc_binary_ops::bop2ftn;
     use crate::pcalc_code::Literal;
     use crate::pcalc_function::*;
 
@@ -142,6 +276,103 @@ mod tests {
         assert_eq!(env.len(), 0);
     }
 
+    #[test]
+    fn test_environment_format() {
+        use crate::pcalc_format::RationalOutput;
+
+        let mut env = Environment::new();
+        env.format_mut().set_rational(RationalOutput::Decimal);
+        env.reset();
+        assert_eq!(env.format().rational(), RationalOutput::Decimal);
+
+        let func_env = Environment::with_parent_funcs(&env);
+        assert_eq!(func_env.format().rational(), RationalOutput::Decimal);
+    }
+
+    #[test]
+    fn test_environment_tolerance() {
+        let mut env = Environment::new();
+        env.tolerance_mut().apply("ulp 2").unwrap();
+        env.reset();
+        assert_eq!(env.tolerance().settings(), "ulp 2");
+        assert_eq!(Environment::with_parent_funcs(&env).tolerance().settings(), "ulp 2");
+    }
+
+    #[test]
+    fn test_environment_truthy() {
+        let mut env = Environment::new();
+        assert!(!env.is_truthy());
+        env.set_truthy(true);
+        assert!(Environment::with_parent_funcs(&env).is_truthy());
+    }
+
+    #[test]
+    fn test_environment_angle() {
+        let mut env = Environment::new();
+        assert_eq!(env.angle(), Angle::Radians);
+        env.set_angle(Angle::Degrees);
+        env.reset();
+        assert_eq!(env.angle(), Angle::Degrees);
+        assert_eq!(Environment::with_parent_funcs(&env).angle(), Angle::Degrees);
+    }
+
+    #[test]
+    fn test_environment_decimal() {
+        use crate::pcalc_code::{BinaryOp, Code};
+        use crate::pcalc_decimal::{Decimal, Rounding};
+
+        let mut env = Environment::new();
+        assert_eq!(env.decimal(), DecimalContext::default());
+        let ctx = DecimalContext::new(Some(2), Rounding::HalfUp).unwrap();
+        env.set_decimal(ctx);
+        env.reset();
+        assert_eq!(env.decimal(), ctx);
+        assert_eq!(Environment::with_parent_funcs(&env).decimal(), ctx);
+
+        // Each environment divides with its own context, which is unbound afterwards
+        let dec = |text: &str| Box::new(Literal::new(Value::from_decimal(Decimal::parse(text).unwrap())));
+        let third = BinaryOp::new("/", bop2ftn("/").unwrap(), dec("1"), dec("3"));
+        assert_eq!(third.eval(&mut env).unwrap().to_string(), "0.33");
+        assert_eq!(third.eval(&mut Environment::new()).unwrap().to_string(), "0.3333333333333333333333333333");
+        assert_eq!(pcalc_decimal::context(), DecimalContext::default());
+    }
+
+    #[test]
+    fn test_environment_word() {
+        use crate::pcalc_code::{BinaryOp, Code};
+        use crate::pcalc_word::{Overflow, WordSize};
+
+        let mut env = Environment::new();
+        assert_eq!(env.word(), WordContext::default());
+        let ctx = WordContext::new(Some(WordSize::U8), Overflow::Wrap);
+        env.set_word(ctx);
+        env.reset();
+        assert_eq!(env.word(), ctx);
+        assert_eq!(Environment::with_parent_funcs(&env).word(), ctx);
+
+        // Each environment wraps with its own word, which is unbound afterwards
+        let int = |i: i64| Box::new(Literal::new(Value::from_int(i)));
+        let sum = BinaryOp::new("+", bop2ftn("+").unwrap(), int(255), int(1));
+        assert_eq!(sum.eval(&mut env).unwrap(), Value::from_int(0));
+        assert_eq!(sum.eval(&mut Environment::new()).unwrap(), Value::from_int(256));
+        assert_eq!(pcalc_word::context(), WordContext::default());
+    }
+
+    #[test]
+    fn test_environment_now() {
+        let mut env = Environment::new();
+        assert!(env.now().unwrap().has_time());
+
+        let pinned = DateTime::parse("2024-03-15").unwrap();
+        env.pin_now(Some(pinned));
+        assert_eq!(env.now().unwrap(), pinned);
+        env.reset();
+        assert_eq!(Environment::with_parent_funcs(&env).now().unwrap(), pinned);
+
+        env.pin_now(None);
+        assert!(env.now().unwrap() > pinned);
+    }
+
     #[test]
     fn test_environment_eval_function() {
         let mut env = Environment::new();
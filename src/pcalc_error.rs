@@ -0,0 +1,271 @@
+use std::error;
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Error Kind
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Syntax,              // Malformed expression or literal
+    TypeMismatch,        // Value of the wrong type, e.g. + true 1
+    UnknownVariable,     // Use of an undefined variable
+    UnknownFunction,     // Call of an undefined function
+    DuplicateDefinition, // Variable defined twice
+    ArityMismatch,       // Function called with the wrong number of arguments
+    Domain,              // Operand outside the operation's domain, e.g. / 1 0
+    Recursion            // Recursive function definition
+}
+
+impl ErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Syntax => "syntax",
+            ErrorKind::TypeMismatch => "type mismatch",
+            ErrorKind::UnknownVariable => "unknown variable",
+            ErrorKind::UnknownFunction => "unknown function",
+            ErrorKind::DuplicateDefinition => "duplicate definition",
+            ErrorKind::ArityMismatch => "arity mismatch",
+            ErrorKind::Domain => "domain",
+            ErrorKind::Recursion => "recursion"
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Span - Line and column of a token, counting from 1
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub width: usize
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, width: usize) -> Self {
+        Span { line, column, width }
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Span::new(1, 1, 0)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Location - Token and its span
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub token: String,
+    pub span: Span
+}
+
+impl Location {
+    pub fn new(token: &str, span: Span) -> Self {
+        Location {
+            token: String::from(token),
+            span
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}', line {}, column {}", self.token, self.span.line, self.span.column)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Frame - User function call the error passed through
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub function: String,
+    pub location: Option<Location>
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.location {
+            Some(loc) => write!(f, "in function '{}' at {}", self.function, loc),
+            None => write!(f, "in function '{}'", self.function)
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Error - Shared by the lexer, parser and evaluator
+//
+// The location is the innermost token the error is known at. Errors leaving a user
+// function move their location into the trace, and take the call's location instead,
+// so the location always refers to the expression being evaluated.
+
+#[derive(Debug, Clone)]
+pub struct Error {
+    kind: ErrorKind,
+    error_msg: String,
+    location: Option<Box<Location>>,
+    trace: Vec<Frame>,
+    suggestions: Vec<String>
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, err_msg: &str) -> Self {
+        Error::from_string(kind, String::from(err_msg))
+    }
+
+    pub fn from_string(kind: ErrorKind, err_msg: String) -> Self {
+        Error {
+            kind,
+            error_msg: err_msg,
+            location: None,
+            trace: Vec::new(),
+            suggestions: Vec::new()
+        }
+    }
+
+    #[inline(always)]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    #[inline(always)]
+    pub fn message(&self) -> &str {
+        self.error_msg.as_str()
+    }
+
+    #[inline(always)]
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_deref()
+    }
+
+    // User function calls, innermost first
+    #[inline(always)]
+    pub fn trace(&self) -> &[Frame] {
+        &self.trace
+    }
+
+    // Names close to a misspelled one, closest first
+    #[inline(always)]
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    // Set the location, unless an inner token already set it
+    pub fn at(mut self, location: &Location) -> Self {
+        if self.location.is_none() {
+            self.location = Some(Box::new(location.clone()));
+        }
+        self
+    }
+
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    // Suggestions as a question, e.g. did you mean 'sin' or 'sinh'?
+    pub fn did_you_mean(&self) -> Option<String> {
+        let quoted: Vec<String> = self.suggestions.iter().map(|name| format!("'{}'", name)).collect();
+        match quoted.split_last() {
+            None => None,
+            Some((last, [])) => Some(format!("did you mean {}?", last)),
+            Some((last, rest)) => Some(format!("did you mean {} or {}?", rest.join(", "), last))
+        }
+    }
+
+    pub fn in_function(mut self, name: &str) -> Self {
+        self.trace.push(Frame {
+            function: String::from(name),
+            location: self.location.take().map(|loc| *loc)
+        });
+        self
+    }
+
+    // Source line of the location, with a caret under the token
+    pub fn caret(&self, source: &str) -> Option<String> {
+        let span = self.location.as_ref()?.span;
+        let line = source.lines().nth(span.line.checked_sub(1)?)?;
+        Some(format!("{}\n{}{}", line, " ".repeat(span.column - 1), "^".repeat(span.width.max(1))))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error_msg)
+    }
+}
+
+impl error::Error for Error {}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        let err = Error::new(ErrorKind::Domain, "Division by zero");
+        assert_eq!(err.kind(), ErrorKind::Domain);
+        assert_eq!(err.kind().name(), "domain");
+        assert_eq!(err.message(), "Division by zero");
+        assert_eq!(format!("{}", err), "Division by zero");
+        assert!(err.location().is_none());
+        assert!(err.trace().is_empty());
+        assert!(err.caret("/ 1 0").is_none());
+        assert!(err.did_you_mean().is_none());
+        assert_eq!(err.with_kind(ErrorKind::Syntax).kind(), ErrorKind::Syntax);
+    }
+
+    #[test]
+    fn test_error_location() {
+        let inner = Location::new("y", Span::new(1, 5, 1));
+        let outer = Location::new("+", Span::new(1, 1, 1));
+        let err = Error::new(ErrorKind::UnknownVariable, "Unknown variable 'y'").at(&inner).at(&outer);
+        assert_eq!(err.location(), Some(&inner));
+        assert_eq!(err.caret("+ x y").unwrap(), "+ x y\n    ^");
+        assert_eq!(err.caret("def f x begin\n  * x yy\nend").unwrap(), "def f x begin\n    ^");
+
+        let wide = Location::new("yy", Span::new(2, 7, 2));
+        let err = Error::new(ErrorKind::UnknownVariable, "Unknown variable 'yy'").at(&wide);
+        assert_eq!(err.caret("def f x begin\n  * x yy\nend").unwrap(), "  * x yy\n      ^^");
+        assert!(err.caret("* x yy").is_none());
+    }
+
+    #[test]
+    fn test_error_suggestions() {
+        let err = Error::new(ErrorKind::UnknownFunction, "Unknown function 'dsit'").with_suggestions(vec![String::from("dist")]);
+        assert_eq!(err.suggestions(), ["dist"]);
+        assert_eq!(err.did_you_mean().unwrap(), "did you mean 'dist'?");
+
+        let names = vec![String::from("sin"), String::from("sinh"), String::from("sign")];
+        let err = Error::new(ErrorKind::UnknownVariable, "Unknown variable 'sinn'").with_suggestions(names);
+        assert_eq!(err.did_you_mean().unwrap(), "did you mean 'sin', 'sinh' or 'sign'?");
+    }
+
+    #[test]
+    fn test_error_trace() {
+        let body = Location::new("y", Span::new(1, 5, 1));
+        let call = Location::new("f", Span::new(1, 6, 1));
+        let err = Error::new(ErrorKind::UnknownVariable, "Unknown variable 'y'")
+            .at(&body)
+            .in_function("f")
+            .at(&call);
+        assert_eq!(err.location(), Some(&call));
+        assert_eq!(err.trace().len(), 1);
+        assert_eq!(err.trace()[0].location, Some(body));
+        assert_eq!(format!("{}", err.trace()[0]), "in function 'f' at 'y', line 1, column 5");
+
+        let err = err.in_function("g");
+        assert!(err.location().is_none());
+        assert_eq!(format!("{}", err.trace()[1]), "in function 'g' at 'f', line 1, column 6");
+    }
+}
@@ -0,0 +1,440 @@
+use crate::pcalc_value::{complex_to_string, list_to_string, record_to_string, Value};
+use crate::pcalc_word as word;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Pow, Signed};
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Format Error
+
+#[derive(Debug, Clone)]
+pub struct FormatError {
+    error_msg: String
+}
+
+impl FormatError {
+    pub fn new(err_msg: &str) -> Self {
+        FormatError {
+            error_msg: String::from(err_msg)
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.error_msg.as_str()
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error_msg)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Rational Output
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RationalOutput {
+    Fraction, // e.g. 1/3
+    Decimal   // e.g. 0.33333333333333333333
+}
+
+// --------------------------------------------------------------------------------
+// Notation - How floating point numbers print
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    Default,          // Shortest exact representation, e.g. 12.222222222222221
+    Fixed(u32),       // Fractional digits, e.g. fix 2 gives 12.22
+    Significant(u32), // Significant figures, e.g. sig 3 gives 12.2
+    Scientific(u32),  // Fractional digits of the mantissa, e.g. sci 2 gives 1.22e1
+    Engineering(u32)  // Like scientific, with exponents a multiple of 3, e.g. eng 2 gives 12.22e0
+}
+
+// --------------------------------------------------------------------------------
+// Radix - How integral values print
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Dec, // e.g. 255
+    Hex, // e.g. 0xFF
+    Bin, // e.g. 0b11111111
+    Oct  // e.g. 0o377
+}
+
+impl Radix {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Radix::Dec => "dec",
+            Radix::Hex => "hex",
+            Radix::Bin => "bin",
+            Radix::Oct => "oct"
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Format - Value output settings
+
+#[derive(Debug, Clone)]
+pub struct Format {
+    rational: RationalOutput,
+    notation: Notation,
+    radix: Radix,
+    separators: bool
+}
+
+impl Format {
+    pub fn new() -> Self {
+        Format {
+            rational: RationalOutput::Fraction,
+            notation: Notation::Default,
+            radix: Radix::Dec,
+            separators: false
+        }
+    }
+
+    #[inline(always)]
+    pub fn notation(&self) -> Notation {
+        self.notation
+    }
+
+    #[inline(always)]
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    #[inline(always)]
+    pub fn separators(&self) -> bool {
+        self.separators
+    }
+
+    // Apply space separated settings, e.g. "fix 2 sep hex"; nothing changes on error
+    pub fn apply(&mut self, settings: &str) -> Result<(), FormatError> {
+        let mut format = self.clone();
+        let mut tokens = settings.split_whitespace();
+        while let Some(name) = tokens.next() {
+            match name {
+                "default" => format.notation = Notation::Default,
+                "fix" => format.notation = Notation::Fixed(Self::parse_digits(name, tokens.next(), 0)?),
+                "sig" => format.notation = Notation::Significant(Self::parse_digits(name, tokens.next(), 1)?),
+                "sci" => format.notation = Notation::Scientific(Self::parse_digits(name, tokens.next(), 0)?),
+                "eng" => format.notation = Notation::Engineering(Self::parse_digits(name, tokens.next(), 0)?),
+                "dec" => format.radix = Radix::Dec,
+                "hex" => format.radix = Radix::Hex,
+                "bin" => format.radix = Radix::Bin,
+                "oct" => format.radix = Radix::Oct,
+                "sep" => format.separators = true,
+                "nosep" => format.separators = false,
+                _ => return Err(FormatError::new(&format!("Unknown format setting - '{}'", name)))
+            }
+        }
+        *self = format;
+        Ok(())
+    }
+
+    // Current settings, in the form accepted by apply
+    pub fn settings(&self) -> String {
+        let notation = match self.notation {
+            Notation::Default => String::from("default"),
+            Notation::Fixed(digits) => format!("fix {}", digits),
+            Notation::Significant(digits) => format!("sig {}", digits),
+            Notation::Scientific(digits) => format!("sci {}", digits),
+            Notation::Engineering(digits) => format!("eng {}", digits)
+        };
+        format!("{} {} {}", notation, self.radix.name(), if self.separators { "sep" } else { "nosep" })
+    }
+
+    #[inline(always)]
+    pub fn rational(&self) -> RationalOutput {
+        self.rational
+    }
+
+    #[inline(always)]
+    pub fn set_rational(&mut self, output: RationalOutput) {
+        self.rational = output;
+    }
+
+    pub fn value(&self, value: &Value) -> String {
+        match value {
+            Value::Int(i) => self.int(*i),
+            Value::Num(n) => self.num(*n),
+            Value::Rational(r) if self.rational == RationalOutput::Decimal => self.group(rational_to_decimal(r, DECIMAL_DIGITS)),
+            Value::Decimal(_) => self.group(value.to_string()),
+            Value::Complex(z) => complex_to_string(z, |x| self.num(x)),
+            Value::List(items) => list_to_string(items, |v| self.value(v)),
+            Value::Record(rec) => record_to_string(rec, |v| self.value(v)),
+            Value::Vector(vec) => vec.to_string_with(|x| self.num(x)),
+            Value::Matrix(mat) => mat.to_string_with(|x| self.num(x)),
+            Value::Quantity(q) => q.to_string_with(|x| self.num(x)),
+            Value::Interval(ival) => ival.to_string_with(|x| self.num(x)),
+            Value::Uncertain(u) => u.to_string_with(|x| self.num(x)),
+            _ => value.to_string()
+        }
+    }
+
+    // --------------------------------------------------------------------------------
+    // Private Functions
+
+    fn parse_digits(name: &str, digits: Option<&str>, min: u32) -> Result<u32, FormatError> {
+        match digits.and_then(|d| d.parse::<u32>().ok()) {
+            Some(d) if (min..=MAX_DIGITS).contains(&d) => Ok(d),
+            _ => Err(FormatError::new(&format!("Invalid digits for {} - expecting {} to {}", name, min, MAX_DIGITS)))
+        }
+    }
+
+    // Other radixes print the bit pattern of the current word
+    fn int(&self, i: i64) -> String {
+        match self.radix {
+            Radix::Dec => self.group(word::widen(i).to_string()),
+            Radix::Hex => format!("0x{:X}", word::pattern(i)),
+            Radix::Bin => format!("0b{:b}", word::pattern(i)),
+            Radix::Oct => format!("0o{:o}", word::pattern(i))
+        }
+    }
+
+    fn num(&self, n: f64) -> String {
+        if self.radix != Radix::Dec {
+            if let Ok(i) = Value::from_num(n).to_int() {
+                return self.int(i);
+            }
+        }
+        if !n.is_finite() {
+            return n.to_string();
+        }
+        match self.notation {
+            Notation::Default => self.group(n.to_string()),
+            Notation::Fixed(digits) => self.group(format!("{:.*}", digits as usize, n)),
+            Notation::Significant(digits) => self.group(significant(n, digits)),
+            Notation::Scientific(digits) => format!("{:.*e}", digits as usize, n),
+            Notation::Engineering(digits) => engineering(n, digits)
+        }
+    }
+
+    fn group(&self, text: String) -> String {
+        if self.separators {
+            group_thousands(&text)
+        } else {
+            text
+        }
+    }
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Helpers
+
+const DECIMAL_DIGITS: u32 = 20;
+
+// Most digits for fix, sig, sci and eng
+const MAX_DIGITS: u32 = 20;
+
+// Round to figures significant digits, without an exponent
+fn significant(n: f64, figures: u32) -> String {
+    let sci = format!("{:.*e}", (figures - 1) as usize, n);
+    let exp = sci.split_once('e').map_or(0, |(_, e)| e.parse::<i32>().unwrap_or(0));
+    let rounded = sci.parse::<f64>().unwrap_or(n);
+    format!("{:.*}", (figures as i32 - 1 - exp).max(0) as usize, rounded)
+}
+
+// Mantissa in [1, 1000) with digits fractional digits, and an exponent that is a multiple of 3
+fn engineering(n: f64, digits: u32) -> String {
+    let mantissa = |exp: i32| format!("{:.*}", digits as usize, n / 10f64.powi(exp));
+    if n == 0.0 {
+        return format!("{}e0", mantissa(0));
+    }
+    let mut exp = (n.abs().log10().floor() as i32).div_euclid(3) * 3;
+    if mantissa(exp).trim_start_matches('-').starts_with("1000") {
+        exp += 3;
+    }
+    format!("{}e{}", mantissa(exp), exp)
+}
+
+// Insert , between groups of 3 digits of the integer part, e.g. -1234567.5 is -1,234,567.5
+fn group_thousands(text: &str) -> String {
+    let (sign, rest) = text.strip_prefix('-').map_or(("", text), |rest| ("-", rest));
+    let (int_part, tail) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()));
+    let mut grouped = String::new();
+    for (idx, ch) in int_part.chars().enumerate() {
+        if idx > 0 && (int_part.len() - idx) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    format!("{}{}{}", sign, grouped, tail)
+}
+
+// Exact decimal expansion of a rational, rounded to at most digits fractional digits.
+pub fn rational_to_decimal(r: &BigRational, digits: u32) -> String {
+    let scale = BigRational::from_integer(BigInt::from(10).pow(digits));
+    let scaled = (r.abs() * scale).round().to_integer().to_string();
+
+    let width = digits as usize + 1;
+    let padded = format!("{:0>width$}", scaled, width = width);
+    let (int_part, frac_part) = padded.split_at(padded.len() - digits as usize);
+    let frac_part = frac_part.trim_end_matches('0');
+
+    let sign = if r.is_negative() && (int_part != "0" || !frac_part.is_empty()) {
+        "-"
+    } else {
+        ""
+    };
+    if frac_part.is_empty() {
+        format!("{}{}", sign, int_part)
+    } else {
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcalc_record::Record;
+
+    fn rat(n: i64, d: i64) -> BigRational {
+        BigRational::new(n.into(), d.into())
+    }
+
+    #[test]
+    fn test_rational_to_decimal() {
+        assert_eq!(rational_to_decimal(&rat(1, 3), 20), "0.33333333333333333333");
+        assert_eq!(rational_to_decimal(&rat(2, 3), 5), "0.66667");
+        assert_eq!(rational_to_decimal(&rat(-1, 8), 20), "-0.125");
+        assert_eq!(rational_to_decimal(&rat(21, 1), 20), "21");
+        assert_eq!(rational_to_decimal(&rat(-1, 3), 2), "-0.33");
+        assert_eq!(rational_to_decimal(&rat(-1, 1000), 2), "0");
+    }
+
+    #[test]
+    fn test_format_rational() {
+        let mut format = Format::new();
+        let third = Value::from_rational(rat(1, 3));
+        assert_eq!(format.rational(), RationalOutput::Fraction);
+        assert_eq!(format.value(&third), "1/3");
+        assert_eq!(format.value(&Value::from_num(0.5)), "0.5");
+
+        format.set_rational(RationalOutput::Decimal);
+        assert_eq!(format.rational(), RationalOutput::Decimal);
+        assert_eq!(format.value(&third), "0.33333333333333333333");
+        assert_eq!(format.value(&Value::from_int(5)), "5");
+        assert_eq!(
+            format.value(&Value::from_list(vec![third.clone(), Value::from_int(1)])),
+            "[0.33333333333333333333, 1]"
+        );
+
+        let inner = Record::new().with("third", third);
+        let rec = Record::new()
+            .with("name", Value::from_string(String::from("x")))
+            .with("inner", Value::from_record(inner));
+        assert_eq!(format.value(&Value::from_record(rec)), "{name: \"x\", inner: {third: 0.33333333333333333333}}");
+    }
+
+    #[test]
+    fn test_format_notation() {
+        let mut format = Format::new();
+        let num = Value::from_num(12.222222222222221);
+        assert_eq!(format.value(&num), "12.222222222222221");
+
+        format.apply("fix 2").unwrap();
+        assert_eq!(format.notation(), Notation::Fixed(2));
+        assert_eq!(format.value(&num), "12.22");
+        assert_eq!(format.value(&Value::from_num(-0.005)), "-0.01");
+        assert_eq!(format.value(&Value::from_int(5)), "5");
+
+        format.apply("sig 3").unwrap();
+        assert_eq!(format.value(&num), "12.2");
+        assert_eq!(format.value(&Value::from_num(9.996)), "10.0");
+        assert_eq!(format.value(&Value::from_num(123456.0)), "123000");
+        assert_eq!(format.value(&Value::from_num(0.00012345)), "0.000123");
+
+        format.apply("sci 2").unwrap();
+        assert_eq!(format.value(&num), "1.22e1");
+        assert_eq!(format.value(&Value::from_num(-0.00012345)), "-1.23e-4");
+
+        format.apply("eng 2").unwrap();
+        assert_eq!(format.value(&num), "12.22e0");
+        assert_eq!(format.value(&Value::from_num(0.00012345)), "123.45e-6");
+        assert_eq!(format.value(&Value::from_num(999999.0)), "1.00e6");
+        assert_eq!(format.value(&Value::from_num(0.0)), "0.00e0");
+        assert_eq!(format.value(&Value::from_num(f64::INFINITY)), "inf");
+
+        format.apply("default").unwrap();
+        assert_eq!(format.value(&num), "12.222222222222221");
+    }
+
+    #[test]
+    fn test_format_radix_separators() {
+        let mut format = Format::new();
+        format.apply("hex").unwrap();
+        assert_eq!(format.radix(), Radix::Hex);
+        assert_eq!(format.value(&Value::from_int(255)), "0xFF");
+        assert_eq!(format.value(&Value::from_int(-1)), "0xFFFFFFFFFFFFFFFF");
+        assert_eq!(format.value(&Value::from_num(16.0)), "0x10");
+        assert_eq!(format.value(&Value::from_num(0.5)), "0.5");
+        assert_eq!(format.value(&Value::from_list(vec![Value::from_int(10), Value::from_int(11)])), "[0xA, 0xB]");
+
+        format.apply("bin").unwrap();
+        assert_eq!(format.value(&Value::from_int(5)), "0b101");
+        format.apply("oct").unwrap();
+        assert_eq!(format.value(&Value::from_int(8)), "0o10");
+
+        format.apply("dec sep").unwrap();
+        assert!(format.separators());
+        assert_eq!(format.value(&Value::from_int(-1234567)), "-1,234,567");
+        assert_eq!(format.value(&Value::from_num(1234.5)), "1,234.5");
+        assert_eq!(format.value(&Value::from_int(123)), "123");
+        format.apply("fix 2").unwrap();
+        assert_eq!(format.value(&Value::from_num(1234567.891)), "1,234,567.89");
+        assert_eq!(format.settings(), "fix 2 dec sep");
+
+        format.apply("nosep").unwrap();
+        assert_eq!(format.value(&Value::from_int(1234)), "1234");
+    }
+
+    #[test]
+    fn test_format_compound() {
+        use crate::pcalc_interval::Interval;
+        use crate::pcalc_matrix::{Matrix, Vector};
+        use crate::pcalc_uncertain::Uncertain;
+        use crate::pcalc_units::{Quantity, Unit};
+        use num_complex::Complex64;
+
+        let mut format = Format::new();
+        format.apply("fix 2 sep").unwrap();
+        let qty = Quantity::new(37.0 / 3.0, Unit::parse("m").unwrap());
+        assert_eq!(format.value(&Value::from_quantity(qty)), "12.33 m");
+        assert_eq!(format.value(&Value::from_complex(Complex64::new(1000.5, -2.25))), "1,000.50-2.25i");
+        assert_eq!(
+            format.value(&Value::from_vector(Vector::new(vec![1.0 / 3.0, 2000.0]).unwrap())),
+            "(0.33, 2,000.00)"
+        );
+        let mat = Matrix::new(vec![vec![1.0, 2.5], vec![1000.0, 4.0]]).unwrap();
+        assert_eq!(format.value(&Value::from_matrix(mat)), "[    1.00  2.50]\n[1,000.00  4.00]");
+        let ival = Interval::new(1.0 / 3.0, 2.0 / 3.0).unwrap();
+        assert_eq!(format.value(&Value::from_interval(ival)), "[0.33 .. 0.67]");
+        let unc = Uncertain::new(1234.5678, 0.01234).unwrap();
+        assert_eq!(format.value(&Value::from_uncertain(unc)), "1,234.57 ± 0.01");
+
+        format.apply("default hex nosep").unwrap();
+        assert_eq!(format.value(&Value::from_vector(Vector::new(vec![16.0, 0.5]).unwrap())), "(0x10, 0.5)");
+    }
+
+    #[test]
+    fn test_format_apply_error() {
+        let mut format = Format::new();
+        assert_eq!(format.apply("hex fix").unwrap_err().message(), "Invalid digits for fix - expecting 0 to 20");
+        assert_eq!(format.apply("sig 0").unwrap_err().message(), "Invalid digits for sig - expecting 1 to 20");
+        assert_eq!(format.apply("sci 21").unwrap_err().message(), "Invalid digits for sci - expecting 0 to 20");
+        assert_eq!(format.apply("round").unwrap_err().message(), "Unknown format setting - 'round'");
+        assert_eq!(format.settings(), "default dec nosep");
+    }
+}
@@ -1,37 +1,14 @@
 use crate::pcalc_code::CodePtr;
 use crate::pcalc_environment::Environment;
+use crate::pcalc_error::{Error, ErrorKind};
 use crate::pcalc_value::{Value, ValueError, ValueResult};
-use std::fmt;
 use std::iter::zip;
 use std::rc::Rc;
 
 // --------------------------------------------------------------------------------
 // Function Error
 
-#[derive(Debug, Clone)]
-pub struct FunctionError {
-    error_msg: String
-}
-
-impl FunctionError {
-    pub fn new(err_msg: &str) -> Self {
-        FunctionError {
-            error_msg: String::from(err_msg)
-        }
-    }
-}
-
-impl From<FunctionError> for ValueError {
-    fn from(item: FunctionError) -> Self {
-        ValueError::new(&item.error_msg)
-    }
-}
-
-impl fmt::Display for FunctionError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.error_msg)
-    }
-}
+pub type FunctionError = Error;
 
 // --------------------------------------------------------------------------------
 // Function
@@ -60,17 +37,33 @@ impl Function {
         &self.body
     }
 
+    pub fn check_arity(&self, count: usize) -> Result<(), ValueError> {
+        if count != self.params.len() {
+            return Err(ValueError::new(ErrorKind::ArityMismatch, "Invalid arguments length"));
+        }
+        Ok(())
+    }
+
     pub fn eval(&self, call_env: &mut Environment, args: &Arguments) -> ValueResult {
-        if args.len() != self.params.len() {
-            return Err(ValueError::new("Invalid arguments length"));
+        self.check_arity(args.len())?;
+
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(arg.eval(call_env)?);
         }
+        self.apply(call_env, values)
+    }
+
+    // Call with already evaluated arguments, e.g. from map, filter and fold.
+    pub fn apply(&self, call_env: &Environment, values: Vec<Value>) -> ValueResult {
+        self.check_arity(values.len())?;
 
         let mut func_env = Environment::with_parent_funcs(call_env);
-        for (param, arg) in zip(&self.params, args) {
-            func_env.def_var(param, arg.eval(call_env)?)?;
+        for (param, value) in zip(&self.params, values) {
+            func_env.def_var(param, value)?;
         }
 
-        let mut result = Value::from_num(0.0);
+        let mut result = Value::from_none();
         for expr in self.body.iter() {
             result = expr.eval(&mut func_env)?;
         }
@@ -90,6 +83,7 @@ pub type FunctionResult<'a> = Result<&'a FunctionPtr, FunctionError>;
 // Unit Tests
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
     use crate::pcalc_binary_ops::*;
@@ -99,7 +93,7 @@ mod tests {
     fn test_function_empty() {
         let mut call_env = Environment::new();
         let func = Function::new(Parameters::new(), Expressions::new());
-        assert_eq!(func.eval(&mut call_env, &Arguments::new()).unwrap(), Value::from_num(0.0));
+        assert!(func.eval(&mut call_env, &Arguments::new()).unwrap().is_none());
     }
 
     #[test]
@@ -121,6 +115,7 @@ mod tests {
         let params = Parameters::new();
         let mut exprs = Expressions::new();
         exprs.push(Box::new(BinaryOp::new(
+            "+",
             bop2ftn("+").unwrap(),
             Box::new(Literal::new(Value::from_num(2.0))),
             Box::new(Literal::new(Value::from_num(3.0)))
@@ -141,6 +136,7 @@ mod tests {
 
         let mut exprs = Expressions::new();
         exprs.push(Box::new(BinaryOp::new(
+            "+",
             bop2ftn("+").unwrap(),
             Box::new(GetVar::new(String::from("x"))),
             Box::new(GetVar::new(String::from("y")))
@@ -167,12 +163,14 @@ mod tests {
         exprs.push(Box::new(DefVar::new(
             String::from("temp"),
             Box::new(BinaryOp::new(
+                "+",
                 bop2ftn("+").unwrap(),
                 Box::new(GetVar::new(String::from("x"))),
                 Box::new(GetVar::new(String::from("y")))
             ))
         )));
         exprs.push(Box::new(BinaryOp::new(
+            ">",
             bop2ftn(">").unwrap(),
             Box::new(GetVar::new(String::from("temp"))),
             Box::new(Literal::new(Value::from_num(8.0)))
@@ -198,6 +196,7 @@ mod tests {
         exprs.push(Box::new(DefVar::new(
             String::from("celsius"),
             Box::new(BinaryOp::new(
+                "-",
                 bop2ftn("-").unwrap(),
                 Box::new(GetVar::new(String::from("fahrenheit"))),
                 Box::new(Literal::new(Value::from_num(32.0)))
@@ -206,12 +205,14 @@ mod tests {
         exprs.push(Box::new(SetVar::new(
             String::from("celsius"),
             Box::new(BinaryOp::new(
+                "*",
                 bop2ftn("*").unwrap(),
                 Box::new(GetVar::new(String::from("celsius"))),
                 Box::new(Literal::new(Value::from_num(5.0)))
             ))
         )));
         exprs.push(Box::new(BinaryOp::new(
+            "/",
             bop2ftn("/").unwrap(),
             Box::new(GetVar::new(String::from("celsius"))),
             Box::new(Literal::new(Value::from_num(9.0)))
@@ -224,6 +225,26 @@ mod tests {
         assert_eq!(func.eval(&mut call_env, &args).unwrap(), Value::from_num(12.222222222222221));
     }
 
+    #[test]
+    fn test_function_apply() {
+        let mut params = Parameters::new();
+        params.push(String::from("x"));
+        params.push(String::from("y"));
+
+        let mut body = Expressions::new();
+        body.push(Box::new(BinaryOp::new(
+            "-",
+            bop2ftn("-").unwrap(),
+            Box::new(GetVar::new(String::from("x"))),
+            Box::new(GetVar::new(String::from("y")))
+        )));
+
+        let env = Environment::new();
+        let func = Function::new(params, body);
+        assert_eq!(func.apply(&env, vec![Value::from_int(5), Value::from_int(3)]).unwrap(), Value::from_int(2));
+        assert!(func.apply(&env, vec![Value::from_int(5)]).is_err());
+    }
+
     #[test]
     fn test_function_invalid_arguments_length() {
         let mut call_env = Environment::new();
@@ -233,8 +254,38 @@ mod tests {
 
         let func = Function::new(Parameters::new(), Expressions::new());
         match func.eval(&mut call_env, &args) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(err) => assert_eq!(format!("{}", err), "Invalid arguments length")
         };
     }
+
+    #[test]
+    fn test_function_record_result() {
+        let mut call_env = Environment::new();
+
+        let mut params = Parameters::new();
+        params.push(String::from("x"));
+
+        let mut fields = Fields::new();
+        fields.push((String::from("x"), Box::new(GetVar::new(String::from("x")))));
+        fields.push((
+            String::from("square"),
+            Box::new(BinaryOp::new(
+                "*",
+                bop2ftn("*").unwrap(),
+                Box::new(GetVar::new(String::from("x"))),
+                Box::new(GetVar::new(String::from("x")))
+            ))
+        ));
+        let mut body = Expressions::new();
+        body.push(Box::new(RecordExpr::new(fields)));
+
+        let mut args = Arguments::new();
+        args.push(Box::new(Literal::new(Value::from_int(3))));
+
+        let func = Function::new(params, body);
+        let result = func.eval(&mut call_env, &args).unwrap();
+        assert_eq!(result.to_record().unwrap().get("square").unwrap(), Value::from_int(9));
+        assert_eq!(result.to_string(), "{x: 3, square: 9}");
+    }
 }
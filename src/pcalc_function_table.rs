@@ -1,4 +1,6 @@
+use crate::pcalc_error::ErrorKind;
 use crate::pcalc_function::*;
+use crate::pcalc_suggest as suggest;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -11,11 +13,12 @@ impl FunctionTable {
         FunctionTable { funcs: HashMap::new() }
     }
 
-    pub fn get(&self, name: &str) -> FunctionResult {
+    pub fn get(&self, name: &str) -> FunctionResult<'_> {
         if let Some(func) = self.funcs.get(name) {
             Ok(func)
         } else {
-            Err(FunctionError::new(&format!("Unknown function '{}'", name)))
+            Err(FunctionError::from_string(ErrorKind::UnknownFunction, format!("Unknown function '{}'", name))
+                .with_suggestions(suggest::closest(name, self.funcs.keys().map(String::as_str))))
         }
     }
 
@@ -70,6 +73,7 @@ pub type FunctionTablePtr = Rc<FunctionTable>;
 // --------------------------------------------------------------------------------
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
     use crate::pcalc_code::Literal;
@@ -95,6 +99,7 @@ mod tests {
         assert_eq!(ftab.len(), 1);
 
         assert!(ftab.get("bar").is_err());
+        assert_eq!(ftab.get("fo").err().unwrap().suggestions(), ["foo"]);
 
         ftab.reset();
         assert!(ftab.is_empty());
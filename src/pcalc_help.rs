@@ -1,12 +1,15 @@
 use crate::pcalc_keywords as keywords;
+use crate::pcalc_units as units;
 
 pub fn print_help(special_vars: &Vec<&str>, repl_commands: &Vec<&str>) {
     print_list("   Binary Ops", &keywords::binary_ops());
     print_list("    Unary Ops", &keywords::unary_ops());
+    print_list("  Ternary Ops", &keywords::ternary_ops());
     print_list("    Vars Mgmt", &vec![keywords::DEFVAR, keywords::SETVAR]);
     print_list("    Ftns Mgmt", &vec![keywords::DEFUN, keywords::FUNCALL]);
     print_list("    Ctrl Flow", &vec![keywords::IF]);
     print_list("    Constants", &keywords::constants());
+    print_list("        Units", &units::unit_names());
     print_list(" Special Ftns", &keywords::special_ftns());
     print_list(" Special Vars", special_vars);
     print_list("    REPL Cmds", repl_commands);
@@ -34,10 +37,10 @@ pub fn print_examples() {
     print_example(
         2,
         "Functions",
-        "> def dist x1 y1 x2 y2\n\
+        "> def dist xa ya xb yb\n\
          >>> begin\n\
-         >>> var dx2 ^ - x2 x1 2\n\
-         >>> var dy2 ^ - y2 y1 2\n\
+         >>> var dx2 ^ - xb xa 2\n\
+         >>> var dy2 ^ - yb ya 2\n\
          >>> sqrt + dx2 dy2\n\
          >>> end\n\
          true\n\
@@ -45,7 +48,7 @@ pub fn print_examples() {
          > call dist 3 4 6 8 cend\n\
          5\n\
          >\n\
-         > def near x1 y1 x2 y2 begin < call dist x1 y1 x2 y2 cend 1.0 end\n\
+         > def near xa ya xb yb begin < call dist xa ya xb yb cend 1.0 end\n\
          true\n\
          >\n\
          > call near 3 4 3.5 4.5 cend\n\
@@ -65,7 +68,42 @@ pub fn print_examples() {
          > if < x 10 ? x fi\n\
          6\n\
          > if > x 10 ? x fi\n\
-         false"
+         > default if > x 10 ? x fi 0\n\
+         0"
+    );
+    print_example(
+        4,
+        "Strings",
+        "> var name \"World\"\n\
+         World\n\
+         > concat \"Hello, \" upper name\n\
+         Hello, WORLD\n\
+         > len name\n\
+         5\n\
+         > + 1 parse \"41\"\n\
+         42\n\
+         > concat \"x = \" str * 2 21\n\
+         x = 42"
+    );
+    print_example(
+        5,
+        "Lists",
+        "> var xs [1 2 3 4]\n\
+         [1, 2, 3, 4]\n\
+         > def sq x begin * x x end\n\
+         true\n\
+         > map sq xs\n\
+         [1, 4, 9, 16]\n\
+         > def odd x begin == % x 2 1 end\n\
+         true\n\
+         > filter odd range 0 10\n\
+         [1, 3, 5, 7, 9]\n\
+         > def add a b begin + a b end\n\
+         true\n\
+         > fold add 0 xs\n\
+         10\n\
+         > sum push xs 5\n\
+         15"
     );
 }
 
@@ -0,0 +1,582 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use std::cmp;
+use std::f64::consts;
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Interval Error
+
+pub type IntervalError = Error;
+
+impl IntervalError {
+    pub fn invalid_bounds(lo: f64, hi: f64) -> Self {
+        IntervalError::from_string(ErrorKind::Domain, format!("Invalid interval bounds - '{} {}'", lo, hi))
+    }
+
+    pub fn interval_domain(op: &str, ival: &Interval) -> Self {
+        IntervalError::from_string(ErrorKind::Domain, format!("Interval outside {} domain - '{}'", op, ival))
+    }
+}
+
+pub type IntervalResult = Result<Interval, IntervalError>;
+
+// --------------------------------------------------------------------------------
+// Interval - Closed range [lo, hi] guaranteed to contain the exact result
+//
+// Results are rounded outward. +, -, *, /, sqrt and integral ^ compute the exact
+// rounding error of each endpoint and only widen when the endpoint is inexact.
+// exp, ln, sin, cos and fractional ^ rely on the platform math library, which is
+// not correctly rounded, so their endpoints are always widened by one ulp.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64
+}
+
+impl Interval {
+    pub fn new(lo: f64, hi: f64) -> IntervalResult {
+        if lo.is_nan() || hi.is_nan() || lo > hi {
+            return Err(IntervalError::invalid_bounds(lo, hi));
+        }
+        Ok(Interval { lo, hi })
+    }
+
+    #[inline(always)]
+    pub fn point(x: f64) -> Self {
+        Interval { lo: x, hi: x }
+    }
+
+    #[inline(always)]
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    #[inline(always)]
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    #[inline(always)]
+    pub fn mid(&self) -> f64 {
+        self.lo / 2.0 + self.hi / 2.0
+    }
+
+    // Rounded up, so the width is never understated
+    #[inline(always)]
+    pub fn width(&self) -> f64 {
+        sub_up(self.hi, self.lo)
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn add(&self, other: &Interval) -> Interval {
+        Interval {
+            lo: add_down(self.lo, other.lo),
+            hi: add_up(self.hi, other.hi)
+        }
+    }
+
+    pub fn sub(&self, other: &Interval) -> Interval {
+        Interval {
+            lo: sub_down(self.lo, other.hi),
+            hi: sub_up(self.hi, other.lo)
+        }
+    }
+
+    pub fn mul(&self, other: &Interval) -> Interval {
+        let pairs = [(self.lo, other.lo), (self.lo, other.hi), (self.hi, other.lo), (self.hi, other.hi)];
+        Interval {
+            lo: pairs.iter().map(|(a, b)| mul_down(*a, *b)).fold(f64::INFINITY, f64::min),
+            hi: pairs.iter().map(|(a, b)| mul_up(*a, *b)).fold(f64::NEG_INFINITY, f64::max)
+        }
+    }
+
+    pub fn div(&self, other: &Interval) -> IntervalResult {
+        if other.contains(0.0) {
+            return Err(IntervalError::new(ErrorKind::Domain, "Interval division by an interval containing zero"));
+        }
+        let pairs = [(self.lo, other.lo), (self.lo, other.hi), (self.hi, other.lo), (self.hi, other.hi)];
+        Ok(Interval {
+            lo: pairs.iter().map(|(a, b)| div_down(*a, *b)).fold(f64::INFINITY, f64::min),
+            hi: pairs.iter().map(|(a, b)| div_up(*a, *b)).fold(f64::NEG_INFINITY, f64::max)
+        })
+    }
+
+    pub fn neg(&self) -> Interval {
+        Interval { lo: -self.hi, hi: -self.lo }
+    }
+
+    pub fn abs(&self) -> Interval {
+        if self.lo >= 0.0 {
+            *self
+        } else if self.hi <= 0.0 {
+            self.neg()
+        } else {
+            Interval {
+                lo: 0.0,
+                hi: self.hi.max(-self.lo)
+            }
+        }
+    }
+
+    pub fn powi(&self, exp: i32) -> IntervalResult {
+        let pos = self.powu(exp.unsigned_abs());
+        if exp < 0 {
+            Interval::point(1.0).div(&pos)
+        } else {
+            Ok(pos)
+        }
+    }
+
+    // x^y = exp(y ln x), for positive x
+    pub fn pow(&self, exp: &Interval) -> IntervalResult {
+        if self.lo <= 0.0 {
+            return Err(IntervalError::interval_domain("^", self));
+        }
+        Ok(exp.mul(&self.ln()?).exp())
+    }
+
+    pub fn sqrt(&self) -> IntervalResult {
+        if self.lo < 0.0 {
+            return Err(IntervalError::interval_domain("sqrt", self));
+        }
+        Ok(Interval {
+            lo: sqrt_down(self.lo),
+            hi: sqrt_up(self.hi)
+        })
+    }
+
+    pub fn exp(&self) -> Interval {
+        Interval {
+            lo: self.lo.exp().next_down().max(0.0),
+            hi: self.hi.exp().next_up()
+        }
+    }
+
+    pub fn ln(&self) -> IntervalResult {
+        if self.lo <= 0.0 {
+            return Err(IntervalError::interval_domain("ln", self));
+        }
+        Ok(Interval {
+            lo: widen_down(self.lo.ln()),
+            hi: widen_up(self.hi.ln())
+        })
+    }
+
+    pub fn sin(&self) -> Interval {
+        self.periodic(f64::sin, consts::FRAC_PI_2, -consts::FRAC_PI_2)
+    }
+
+    pub fn cos(&self) -> Interval {
+        self.periodic(f64::cos, 0.0, consts::PI)
+    }
+
+    // Three-valued comparisons: Some(true) when true for every pair of points,
+    // Some(false) when false for every pair, and None (unknown) when the intervals
+    // overlap such that the answer depends on the points chosen.
+
+    pub fn less(&self, other: &Interval) -> Option<bool> {
+        certain(self.hi < other.lo, self.lo >= other.hi)
+    }
+
+    pub fn less_equal(&self, other: &Interval) -> Option<bool> {
+        certain(self.hi <= other.lo, self.lo > other.hi)
+    }
+
+    #[inline(always)]
+    pub fn greater(&self, other: &Interval) -> Option<bool> {
+        other.less(self)
+    }
+
+    #[inline(always)]
+    pub fn greater_equal(&self, other: &Interval) -> Option<bool> {
+        other.less_equal(self)
+    }
+
+    pub fn equal(&self, other: &Interval) -> Option<bool> {
+        let same_point = self.lo == self.hi && self == other;
+        certain(same_point, self.hi < other.lo || other.hi < self.lo)
+    }
+
+    #[inline(always)]
+    pub fn not_equal(&self, other: &Interval) -> Option<bool> {
+        self.equal(other).map(|eq| !eq)
+    }
+
+    // Bounds printed with fmt_num, e.g. [1 .. 2]
+    pub fn to_string_with(&self, fmt_num: impl Fn(f64) -> String) -> String {
+        format!("[{} .. {}]", fmt_num(self.lo), fmt_num(self.hi))
+    }
+
+    // --------------------------------------------------------------------------------
+    // Private Functions
+
+    // x^n for the magnitude n of an integral exponent
+    fn powu(&self, n: u32) -> Interval {
+        if n == 0 {
+            Interval::point(1.0)
+        } else if n % 2 == 1 {
+            // Odd powers are increasing
+            Interval {
+                lo: signed_pow_down(self.lo, n),
+                hi: signed_pow_up(self.hi, n)
+            }
+        } else if self.lo >= 0.0 {
+            Interval {
+                lo: pow_down(self.lo, n),
+                hi: pow_up(self.hi, n)
+            }
+        } else if self.hi <= 0.0 {
+            Interval {
+                lo: pow_down(-self.hi, n),
+                hi: pow_up(-self.lo, n)
+            }
+        } else {
+            Interval {
+                lo: 0.0,
+                hi: pow_up(self.hi.max(-self.lo), n)
+            }
+        }
+    }
+
+    // Range of a 2*pi periodic function with a maximum of 1 at max_at and a minimum of -1 at min_at.
+    fn periodic(&self, ftn: fn(f64) -> f64, max_at: f64, min_at: f64) -> Interval {
+        if self.width() >= consts::TAU {
+            return Interval { lo: -1.0, hi: 1.0 };
+        }
+
+        let hits = |at: f64| {
+            let k = ((self.lo - at) / consts::TAU).ceil();
+            at + k * consts::TAU <= self.hi
+        };
+        let (flo, fhi) = (ftn(self.lo), ftn(self.hi));
+        let lo = if hits(min_at) { -1.0 } else { widen_down(flo.min(fhi)).max(-1.0) };
+        let hi = if hits(max_at) { 1.0 } else { widen_up(flo.max(fhi)).min(1.0) };
+        Interval { lo, hi }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(|x| x.to_string()))
+    }
+}
+
+impl cmp::PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self == other {
+            Some(cmp::Ordering::Equal)
+        } else if self.less(other) == Some(true) {
+            Some(cmp::Ordering::Less)
+        } else if self.greater(other) == Some(true) {
+            Some(cmp::Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Directed Rounding - Exact error terms decide whether to step to the adjacent float
+
+// Math library results are widened by one ulp, except zero, which sin and ln
+// only return at their exact zeros, e.g. sin 0 and ln 1.
+#[inline(always)]
+fn widen_down(y: f64) -> f64 {
+    if y == 0.0 {
+        y
+    } else {
+        y.next_down()
+    }
+}
+
+#[inline(always)]
+fn widen_up(y: f64) -> f64 {
+    if y == 0.0 {
+        y
+    } else {
+        y.next_up()
+    }
+}
+
+#[inline(always)]
+fn certain(is_true: bool, is_false: bool) -> Option<bool> {
+    if is_true {
+        Some(true)
+    } else if is_false {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// Rounding error of s = a + b, i.e. (a + b) - s, computed exactly
+#[inline(always)]
+fn two_sum_err(a: f64, b: f64, s: f64) -> f64 {
+    let bb = s - a;
+    (a - (s - bb)) + (b - bb)
+}
+
+#[inline(always)]
+fn round_down(result: f64, err: f64) -> f64 {
+    if err < 0.0 {
+        result.next_down()
+    } else {
+        result
+    }
+}
+
+#[inline(always)]
+fn round_up(result: f64, err: f64) -> f64 {
+    if err > 0.0 {
+        result.next_up()
+    } else {
+        result
+    }
+}
+
+fn add_down(a: f64, b: f64) -> f64 {
+    let s = a + b;
+    round_down(s, two_sum_err(a, b, s))
+}
+
+fn add_up(a: f64, b: f64) -> f64 {
+    let s = a + b;
+    round_up(s, two_sum_err(a, b, s))
+}
+
+fn sub_down(a: f64, b: f64) -> f64 {
+    add_down(a, -b)
+}
+
+fn sub_up(a: f64, b: f64) -> f64 {
+    add_up(a, -b)
+}
+
+// Rounding error of p = a * b, whose sign is only trusted above the subnormal range,
+// where the error itself may round to zero
+#[inline(always)]
+fn mul_err(a: f64, b: f64, p: f64) -> f64 {
+    if p.abs() < f64::MIN_POSITIVE && a != 0.0 && b != 0.0 {
+        a.signum() * b.signum()
+    } else {
+        a.mul_add(b, -p)
+    }
+}
+
+fn mul_down(a: f64, b: f64) -> f64 {
+    let p = a * b;
+    round_down(p, mul_err(a, b, p))
+}
+
+fn mul_up(a: f64, b: f64) -> f64 {
+    let p = a * b;
+    round_up(p, mul_err(a, b, p))
+}
+
+// Sign of the correction r / b, where r = a - q * b is exact
+#[inline(always)]
+fn div_err(a: f64, b: f64, q: f64) -> f64 {
+    let r = (-q).mul_add(b, a);
+    if r == 0.0 || r.is_nan() {
+        0.0
+    } else if (r < 0.0) == (b < 0.0) {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn div_down(a: f64, b: f64) -> f64 {
+    let q = a / b;
+    round_down(q, div_err(a, b, q))
+}
+
+fn div_up(a: f64, b: f64) -> f64 {
+    let q = a / b;
+    round_up(q, div_err(a, b, q))
+}
+
+fn sqrt_down(x: f64) -> f64 {
+    let s = x.sqrt();
+    round_down(s, (-s).mul_add(s, x))
+}
+
+fn sqrt_up(x: f64) -> f64 {
+    let s = x.sqrt();
+    round_up(s, (-s).mul_add(s, x))
+}
+
+// x^n for x >= 0 by squaring, where every product rounds the same way as the result
+fn directed_pow(x: f64, n: u32, mul: fn(f64, f64) -> f64) -> f64 {
+    let (mut result, mut base, mut n) = (1.0, x, n);
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mul(result, base);
+        }
+        n >>= 1;
+        if n > 0 {
+            base = mul(base, base);
+        }
+    }
+    result
+}
+
+fn pow_down(x: f64, n: u32) -> f64 {
+    directed_pow(x, n, mul_down)
+}
+
+fn pow_up(x: f64, n: u32) -> f64 {
+    directed_pow(x, n, mul_up)
+}
+
+// Odd n, any sign of x
+fn signed_pow_down(x: f64, n: u32) -> f64 {
+    if x < 0.0 {
+        -pow_up(-x, n)
+    } else {
+        pow_down(x, n)
+    }
+}
+
+fn signed_pow_up(x: f64, n: u32) -> f64 {
+    if x < 0.0 {
+        -pow_down(-x, n)
+    } else {
+        pow_up(x, n)
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ival(lo: f64, hi: f64) -> Interval {
+        Interval::new(lo, hi).unwrap()
+    }
+
+    fn encloses(ival: &Interval, x: f64) -> bool {
+        ival.contains(x)
+    }
+
+    #[test]
+    fn test_interval_new() {
+        let i = ival(1.0, 2.0);
+        assert_eq!(i.lo(), 1.0);
+        assert_eq!(i.hi(), 2.0);
+        assert_eq!(i.mid(), 1.5);
+        assert_eq!(i.width(), 1.0);
+        assert_eq!(i.to_string(), "[1 .. 2]");
+        assert_eq!(Interval::point(3.0), ival(3.0, 3.0));
+
+        assert_eq!(Interval::new(2.0, 1.0).unwrap_err().message(), "Invalid interval bounds - '2 1'");
+        assert!(Interval::new(f64::NAN, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_interval_directed_rounding() {
+        // Exact results are not widened
+        assert_eq!(ival(1.0, 2.0).add(&ival(3.0, 4.0)), ival(4.0, 6.0));
+        assert_eq!(ival(1.0, 2.0).mul(&ival(3.0, 4.0)), ival(3.0, 8.0));
+        assert_eq!(ival(1.0, 2.0).div(&ival(4.0, 8.0)).unwrap(), ival(0.125, 0.5));
+        assert_eq!(ival(4.0, 9.0).sqrt().unwrap(), ival(2.0, 3.0));
+
+        // Inexact results are widened by one ulp
+        let sum = Interval::point(0.1).add(&Interval::point(0.2));
+        assert!(sum.lo() < sum.hi());
+        assert_eq!(sum.hi(), sum.lo().next_up());
+
+        let third = Interval::point(1.0).div(&Interval::point(3.0)).unwrap();
+        assert_eq!(third.hi(), third.lo().next_up());
+        assert!(third.mul(&Interval::point(3.0)).contains(1.0));
+
+        let root2 = Interval::point(2.0).sqrt().unwrap();
+        assert_eq!(root2.hi(), root2.lo().next_up());
+        assert!(encloses(&root2, consts::SQRT_2));
+    }
+
+    #[test]
+    fn test_interval_arithmetic() {
+        assert_eq!(ival(1.0, 2.0).sub(&ival(3.0, 5.0)), ival(-4.0, -1.0));
+        assert_eq!(ival(-1.0, 2.0).mul(&ival(-3.0, 4.0)), ival(-6.0, 8.0));
+        assert_eq!(ival(-2.0, 1.0).neg(), ival(-1.0, 2.0));
+        assert_eq!(ival(-3.0, 1.0).abs(), ival(0.0, 3.0));
+        assert_eq!(ival(-3.0, -1.0).abs(), ival(1.0, 3.0));
+        assert_eq!(
+            ival(1.0, 2.0).div(&ival(-1.0, 1.0)).unwrap_err().message(),
+            "Interval division by an interval containing zero"
+        );
+
+        assert_eq!(ival(-1.0, 2.0).powi(2).unwrap(), ival(0.0, 4.0));
+        assert_eq!(ival(-3.0, -2.0).powi(2).unwrap(), ival(4.0, 9.0));
+        assert_eq!(ival(-2.0, 3.0).powi(3).unwrap(), ival(-8.0, 27.0));
+        assert_eq!(ival(-2.0, 3.0).powi(0).unwrap(), ival(1.0, 1.0));
+        assert_eq!(ival(2.0, 4.0).powi(-1).unwrap(), ival(0.25, 0.5));
+        assert!(ival(-1.0, 1.0).powi(-2).is_err());
+        assert_eq!(ival(1.0, 2.0).powi(2_000_000_000).unwrap(), ival(1.0, f64::INFINITY));
+        assert_eq!(ival(1.0, 2.0).powi(i32::MIN).unwrap(), ival(0.0, 1.0));
+        assert_eq!(ival(0.5, 0.5).powi(2_000_000_000).unwrap(), ival(0.0, 0.0_f64.next_up()));
+        assert_eq!(ival(3.0, 3.0).powi(13).unwrap(), ival(1594323.0, 1594323.0));
+        let p = ival(1.1, 1.1).powi(21).unwrap();
+        assert!(p.lo() < p.hi() && p.width() < 1e-13);
+
+        let p = ival(4.0, 9.0).pow(&Interval::point(0.5)).unwrap();
+        assert!(encloses(&p, 2.0) && encloses(&p, 3.0));
+        assert!(ival(-1.0, 2.0).pow(&Interval::point(0.5)).is_err());
+    }
+
+    #[test]
+    fn test_interval_functions() {
+        let e = ival(0.0, 1.0).exp();
+        assert!(encloses(&e, 1.0) && encloses(&e, consts::E));
+
+        let l = ival(1.0, consts::E).ln().unwrap();
+        assert!(encloses(&l, 0.0) && encloses(&l, 1.0));
+        assert_eq!(ival(0.0, 1.0).ln().unwrap_err().message(), "Interval outside ln domain - '[0 .. 1]'");
+        assert!(ival(-1.0, 1.0).sqrt().is_err());
+
+        let s = ival(0.0, consts::PI).sin();
+        assert_eq!(s, ival(0.0, 1.0));
+        assert_eq!(ival(1.0, 2.0).ln().unwrap().lo(), 0.0);
+
+        let s = ival(-1.0, 1.0).sin();
+        assert!(encloses(&s, 1.0_f64.sin()) && encloses(&s, (-1.0_f64).sin()));
+        assert!(s.hi() < 1.0 && s.lo() > -1.0);
+
+        assert_eq!(ival(0.0, 10.0).sin(), ival(-1.0, 1.0));
+        assert_eq!(ival(3.0, 3.5).cos().lo(), -1.0);
+        assert_eq!(ival(-0.5, 0.5).cos().hi(), 1.0);
+    }
+
+    #[test]
+    fn test_interval_compare() {
+        let a = ival(1.0, 2.0);
+        let b = ival(3.0, 4.0);
+        let c = ival(1.5, 3.5);
+
+        assert_eq!(a.less(&b), Some(true));
+        assert_eq!(b.less(&a), Some(false));
+        assert_eq!(a.less(&c), None);
+        assert_eq!(ival(1.0, 2.0).less(&ival(2.0, 3.0)), None);
+        assert_eq!(ival(1.0, 2.0).less_equal(&ival(2.0, 3.0)), Some(true));
+        assert_eq!(b.greater(&a), Some(true));
+        assert_eq!(a.greater_equal(&b), Some(false));
+
+        assert_eq!(Interval::point(2.0).equal(&Interval::point(2.0)), Some(true));
+        assert_eq!(a.equal(&b), Some(false));
+        assert_eq!(a.equal(&a), None);
+        assert_eq!(a.not_equal(&b), Some(true));
+        assert_eq!(a.not_equal(&c), None);
+
+        assert_eq!(a.partial_cmp(&b), Some(cmp::Ordering::Less));
+        assert_eq!(a.partial_cmp(&a), Some(cmp::Ordering::Equal));
+        assert_eq!(a.partial_cmp(&c), None);
+    }
+}
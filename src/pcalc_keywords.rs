@@ -18,14 +18,20 @@ pub const IF: &str = "if";
 pub const THEN: &str = "?";
 pub const ELSE: &str = ":";
 pub const FI: &str = "fi";
+pub const LIST_BEGIN: &str = "[";
+pub const LIST_END: &str = "]";
+pub const RECORD_BEGIN: &str = "{";
+pub const RECORD_END: &str = "}";
 
 // Literals and Consts
 pub const TRUE: &str = "true";
 pub const FALSE: &str = "false";
+pub const NONE: &str = "none";
 pub const PI: &str = "pi";
 pub const TAU: &str = "tau";
 pub const E: &str = "e";
 pub const PHI: &str = "phi";
+pub const I: &str = "i";
 
 // Binary Ops
 pub const ADD: &str = "+";
@@ -36,14 +42,58 @@ pub const REMAINDER: &str = "%";
 pub const POWER: &str = "^";
 pub const MAX: &str = "max";
 pub const MIN: &str = "min";
+pub const ATAN2: &str = "atan2";
+pub const HYPOT: &str = "hypot";
+pub const LOGB: &str = "logb";
+pub const ROOT: &str = "root";
+pub const IDIV: &str = "idiv";
+pub const MOD: &str = "mod";
+pub const COPYSIGN: &str = "copysign";
+pub const FDIM: &str = "fdim";
+pub const GCD: &str = "gcd";
+pub const LCM: &str = "lcm";
+pub const CHOOSE: &str = "choose";
+pub const PERM: &str = "perm";
+pub const MODINV: &str = "modinv";
+pub const BETA: &str = "beta";
+pub const GAMMAINC: &str = "gammainc";
+pub const GAMMAINCC: &str = "gammaincc";
 pub const EQUAL: &str = "==";
 pub const NOT_EQUAL: &str = "!=";
 pub const LESS: &str = "<";
 pub const LESS_EQUAL: &str = "<=";
 pub const GREATER: &str = ">";
 pub const GREATER_EQUAL: &str = ">=";
+pub const APPROX_EQUAL: &str = "~=";
+pub const NOT_APPROX_EQUAL: &str = "!~=";
 pub const AND: &str = "and";
 pub const OR: &str = "or";
+pub const XOR: &str = "xor";
+pub const NAND: &str = "nand";
+pub const NOR: &str = "nor";
+pub const IMPLIES: &str = "implies";
+pub const RECT: &str = "rect";
+pub const POLAR: &str = "polar";
+pub const TO: &str = "to";
+pub const INTERVAL: &str = "interval";
+pub const PM: &str = "pm";
+pub const CONCAT: &str = "concat";
+pub const NTH: &str = "nth";
+pub const PUSH: &str = "push";
+pub const RANGE: &str = "range";
+pub const DAYS_BETWEEN: &str = "days_between";
+pub const ADD_MONTHS: &str = "add_months";
+pub const DOT: &str = "dot";
+pub const CROSS: &str = "cross";
+pub const SOLVE: &str = "solve";
+pub const DEFAULT: &str = "default";
+pub const BAND: &str = "band";
+pub const BOR: &str = "bor";
+pub const BXOR: &str = "bxor";
+pub const SHL: &str = "shl";
+pub const SHR: &str = "shr";
+pub const ROTL: &str = "rotl";
+pub const ROTR: &str = "rotr";
 
 // Unary Ops
 pub const SQRT: &str = "sqrt";
@@ -64,6 +114,8 @@ pub const ATAN: &str = "atan";
 pub const ASINH: &str = "asinh";
 pub const ACOSH: &str = "acosh";
 pub const ATANH: &str = "atanh";
+pub const DEG2RAD: &str = "deg2rad";
+pub const RAD2DEG: &str = "rad2deg";
 pub const SIGN: &str = "sign";
 pub const ABS: &str = "abs";
 pub const RECIP: &str = "recip";
@@ -76,9 +128,71 @@ pub const NEG: &str = "neg";
 pub const NOT: &str = "not";
 pub const ASNUM: &str = "asnum";
 pub const ASBOOL: &str = "asbool";
+pub const RE: &str = "re";
+pub const IM: &str = "im";
+pub const CONJ: &str = "conj";
+pub const ARG: &str = "arg";
+pub const CABS: &str = "cabs";
+pub const LEN: &str = "len";
+pub const STR: &str = "str";
+pub const PARSE: &str = "parse";
+pub const UPPER: &str = "upper";
+pub const LOWER: &str = "lower";
+pub const SUM: &str = "sum";
+pub const PROD: &str = "prod";
+pub const LO: &str = "lo";
+pub const HI: &str = "hi";
+pub const MID: &str = "mid";
+pub const WIDTH: &str = "width";
+pub const SIGMA: &str = "sigma";
+pub const WEEKDAY: &str = "weekday";
+pub const DEC: &str = "dec";
+pub const VEC: &str = "vec";
+pub const MAT: &str = "mat";
+pub const NORM: &str = "norm";
+pub const TRANSPOSE: &str = "transpose";
+pub const DET: &str = "det";
+pub const INV: &str = "inv";
+pub const ISNONE: &str = "isnone";
+pub const ISNAN: &str = "isnan";
+pub const ISINF: &str = "isinf";
+pub const ISFINITE: &str = "isfinite";
+pub const BNOT: &str = "bnot";
+pub const POPCNT: &str = "popcnt";
+pub const CLZ: &str = "clz";
+pub const CTZ: &str = "ctz";
+pub const FACT: &str = "fact";
+pub const ISPRIME: &str = "isprime";
+pub const NEXTPRIME: &str = "nextprime";
+pub const FACTOR: &str = "factor";
+pub const GAMMA: &str = "gamma";
+pub const LGAMMA: &str = "lgamma";
+pub const DIGAMMA: &str = "digamma";
+pub const ERF: &str = "erf";
+pub const ERFC: &str = "erfc";
+pub const ERFINV: &str = "erfinv";
+pub const BESSEL_J0: &str = "j0";
+pub const BESSEL_J1: &str = "j1";
+pub const BESSEL_Y0: &str = "y0";
+pub const BESSEL_Y1: &str = "y1";
+
+// Ternary Operators
+pub const CLAMP: &str = "clamp";
+pub const LERP: &str = "lerp";
+pub const FMA: &str = "fma";
+pub const BETWEEN: &str = "between";
+pub const SELECT: &str = "select";
+pub const MODPOW: &str = "modpow";
+pub const APPROX: &str = "approx";
 
 // Special Functions
 pub const XPRINT: &str = "xprint";
+pub const MAP: &str = "map";
+pub const FILTER: &str = "filter";
+pub const FOLD: &str = "fold";
+pub const NOW: &str = "now";
+pub const GET: &str = "get";
+pub const WITH: &str = "with";
 
 // --------------------------------------------------------------------------------
 // Keyword Functions
@@ -88,8 +202,20 @@ pub const XPRINT: &str = "xprint";
 pub fn binary_ops() -> NameList<'static> {
     vec![ADD, SUBTRACT, MULTIPLY, DIVIDE, REMAINDER, POWER,
          MAX, MIN,
+         ATAN2, HYPOT, LOGB, ROOT, IDIV, MOD, COPYSIGN, FDIM,
+         GCD, LCM, CHOOSE, PERM, MODINV,
+         BETA, GAMMAINC, GAMMAINCC,
          EQUAL, NOT_EQUAL, LESS, LESS_EQUAL, GREATER, GREATER_EQUAL,
-         AND, OR]
+         APPROX_EQUAL, NOT_APPROX_EQUAL,
+         AND, OR, XOR, NAND, NOR, IMPLIES,
+         RECT, POLAR,
+         TO, INTERVAL, PM,
+         CONCAT,
+         NTH, PUSH, RANGE,
+         DAYS_BETWEEN, ADD_MONTHS,
+         DOT, CROSS, SOLVE,
+         DEFAULT,
+         BAND, BOR, BXOR, SHL, SHR, ROTL, ROTR]
 }
 
 #[inline(always)]
@@ -98,19 +224,41 @@ pub fn unary_ops() -> NameList<'static> {
     vec![SQRT, EXP, EXP2, LN, LOG2, LOG10,
          SIN, COS, TAN, SINH, COSH, TANH,
          ASIN, ACOS, ATAN, ASINH, ACOSH, ATANH,
+         DEG2RAD, RAD2DEG,
          SIGN, ABS, RECIP, FRACT, TRUNC,
          CEIL, FLOOR, ROUND,
          NEG, NOT,
-         ASNUM, ASBOOL]
+         ASNUM, ASBOOL,
+         RE, IM, CONJ, ARG, CABS,
+         LEN, STR, PARSE, UPPER, LOWER,
+         SUM, PROD,
+         LO, HI, MID, WIDTH,
+         SIGMA,
+         WEEKDAY,
+         DEC,
+         VEC, MAT, NORM, TRANSPOSE, DET, INV,
+         ISNONE,
+         ISNAN, ISINF, ISFINITE,
+         BNOT, POPCNT, CLZ, CTZ,
+         FACT, ISPRIME, NEXTPRIME, FACTOR,
+         GAMMA, LGAMMA, DIGAMMA, ERF, ERFC, ERFINV,
+         BESSEL_J0, BESSEL_J1, BESSEL_Y0, BESSEL_Y1]
+}
+
+#[inline(always)]
+#[rustfmt::skip]
+pub fn ternary_ops() -> NameList<'static> {
+    vec![CLAMP, LERP, FMA, BETWEEN, SELECT,
+         MODPOW, APPROX]
 }
 
 #[inline(always)]
 #[rustfmt::skip]
 pub fn special_ftns() -> NameList<'static> {
-    vec![XPRINT]
+    vec![XPRINT, MAP, FILTER, FOLD, NOW, GET, WITH]
 }
 
 #[inline(always)]
 pub fn constants() -> NameList<'static> {
-    vec![PI, TAU, E, PHI]
+    vec![PI, TAU, E, PHI, I]
 }
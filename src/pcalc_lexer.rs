@@ -1,30 +1,25 @@
+use crate::pcalc_error::{Error, ErrorKind, Location, Span};
 use crate::pcalc_keywords as keywords;
+use crate::pcalc_suggest as suggest;
 use std::cmp;
 use std::collections::HashMap;
 
 // --------------------------------------------------------------------------------
-// Parser Error
+// Lexer Error
 
-#[derive(Debug, Clone)]
-pub struct LexerError {
-    error_msg: String
-}
+pub type LexerError = Error;
 
 impl LexerError {
     pub fn invalid_identifier(name: &str) -> Self {
-        LexerError {
-            error_msg: format!("Invalid identifier - '{}'", name)
-        }
+        LexerError::from_string(ErrorKind::Syntax, format!("Invalid identifier - '{}'", name))
     }
 
-    pub fn reserved_name(what: &str, name: &str) -> Self {
-        LexerError {
-            error_msg: format!("Invalid reserved {} - '{}'", what, name)
-        }
+    pub fn unterminated_string(text: &str) -> Self {
+        LexerError::from_string(ErrorKind::Syntax, format!("Unterminated string - '{}'", text))
     }
 
-    pub fn message(&self) -> &str {
-        self.error_msg.as_str()
+    pub fn reserved_name(what: &str, name: &str) -> Self {
+        LexerError::from_string(ErrorKind::Syntax, format!("Invalid reserved {} - '{}'", what, name))
     }
 }
 
@@ -35,6 +30,7 @@ impl LexerError {
 pub enum TokenType {
     BinaryOp,
     UnaryOp,
+    TernaryOp,
     SpecialFtn,
     Literal,
     Const,
@@ -49,7 +45,11 @@ pub enum TokenType {
     If,
     Then,
     Else,
-    Fi
+    Fi,
+    ListBegin,
+    ListEnd,
+    RecordBegin,
+    RecordEnd
 }
 
 impl TokenType {
@@ -57,6 +57,7 @@ impl TokenType {
         match self {
             TokenType::BinaryOp => "BinaryOp",
             TokenType::UnaryOp => "UnaryOp",
+            TokenType::TernaryOp => "TernaryOp",
             TokenType::SpecialFtn => "SpecialFtn",
             TokenType::Literal => "Literal",
             TokenType::Const => "Const",
@@ -71,7 +72,11 @@ impl TokenType {
             TokenType::If => "If",
             TokenType::Then => "Then",
             TokenType::Else => "Else",
-            TokenType::Fi => "Fi"
+            TokenType::Fi => "Fi",
+            TokenType::ListBegin => "ListBegin",
+            TokenType::ListEnd => "ListEnd",
+            TokenType::RecordBegin => "RecordBegin",
+            TokenType::RecordEnd => "RecordEnd"
         }
     }
 }
@@ -82,16 +87,27 @@ impl TokenType {
 #[derive(Debug, Clone)]
 pub struct Token {
     pub ttype: TokenType,
-    pub tname: String
+    pub tname: String,
+    pub span: Span
 }
 
 impl Token {
     pub fn new(ttype: TokenType, tname: &str) -> Self {
+        Token::at(ttype, tname, Span::default())
+    }
+
+    pub fn at(ttype: TokenType, tname: &str, span: Span) -> Self {
         Token {
             ttype,
-            tname: String::from(tname)
+            tname: String::from(tname),
+            span
         }
     }
+
+    #[inline(always)]
+    pub fn location(&self) -> Location {
+        Location::new(&self.tname, self.span)
+    }
 }
 
 impl cmp::PartialEq for Token {
@@ -105,36 +121,170 @@ impl cmp::PartialEq for Token {
 
 pub struct Lexer {
     table: HashMap<String, TokenType>,
-    tokens: Vec<Token>
+    tokens: Vec<Token>,
+    line: usize
 }
 
 impl Lexer {
     pub fn new() -> Self {
         Lexer {
             table: Lexer::make_token_types(),
-            tokens: Vec::new()
+            tokens: Vec::new(),
+            line: 1
         }
     }
 
     pub fn token_type(&self, token: &str) -> Result<TokenType, LexerError> {
         if let Some(toktyp) = self.table.get(token) {
             Ok(*toktyp)
-        } else if token.parse::<f64>().is_ok() {
+        } else if Self::is_string_literal(token)
+            || Self::is_quantity_literal(token)
+            || Self::is_datetime_literal(token)
+            || Self::is_duration_literal(token)
+            || Self::is_decimal_literal(token)
+            || Self::is_radix_literal(token)
+            || token.parse::<f64>().is_ok()
+        {
             Ok(TokenType::Literal)
         } else if Self::is_valid_identifier(token) {
             Ok(TokenType::Identifier)
         } else {
-            Err(LexerError::invalid_identifier(token))
+            Err(LexerError::invalid_identifier(token).with_suggestions(self.suggest(token)))
         }
     }
 
+    // Token lines count from the first line of the pending expression, e.g. of a multi-line function
     pub fn tokenize(&mut self, expr: &str) -> Result<(), LexerError> {
-        for tok in expr.split_whitespace() {
-            self.tokens.push(Token::new(self.token_type(tok)?, tok));
+        if self.tokens.is_empty() {
+            self.line = 1;
+        }
+        for (tok, span) in Self::split_spans(expr, self.line)? {
+            let ttype = self.token_type(tok).map_err(|err| err.at(&Location::new(tok, span)))?;
+            self.tokens.push(Token::at(ttype, tok, span));
         }
+        self.line += cmp::max(expr.lines().count(), 1);
         Ok(())
     }
 
+    // Split on whitespace, keeping double-quoted strings (quotes included) as single tokens,
+    // and list brackets as tokens of their own.
+    pub fn split_tokens(expr: &str) -> Result<Vec<&str>, LexerError> {
+        Ok(Self::split_spans(expr, 1)?.into_iter().map(|(tok, _)| tok).collect())
+    }
+
+    // Tokens with their spans, where the expression starts on first_line
+    pub fn split_spans(expr: &str, first_line: usize) -> Result<Vec<(&str, Span)>, LexerError> {
+        let span_of = |start: usize, end: usize| {
+            let before = &expr[..start];
+            let line = first_line + before.matches('\n').count();
+            let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+            Span::new(line, column, expr[start..end].chars().count())
+        };
+
+        let mut tokens = Vec::new();
+        let mut chars = expr.char_indices().peekable();
+        while let Some(&(start, ch)) = chars.peek() {
+            if ch.is_whitespace() {
+                chars.next();
+            } else if Self::is_bracket(ch) {
+                chars.next();
+                tokens.push((&expr[start..start + 1], span_of(start, start + 1)));
+            } else if ch == '"' {
+                chars.next();
+                let mut escaped = false;
+                let mut end = None;
+                for (idx, c) in chars.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        end = Some(idx + 1);
+                        break;
+                    }
+                }
+                match end {
+                    Some(end) => tokens.push((&expr[start..end], span_of(start, end))),
+                    None => {
+                        let text = &expr[start..];
+                        return Err(LexerError::unterminated_string(text).at(&Location::new(text, span_of(start, expr.len()))));
+                    }
+                }
+            } else {
+                let mut end = expr.len();
+                while let Some(&(idx, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '"' || Self::is_bracket(c) {
+                        end = idx;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push((&expr[start..end], span_of(start, end)));
+            }
+        }
+        Ok(tokens)
+    }
+
+    // Number with unit, e.g. 3_m or 9.81_m/s^2, or bare unit, e.g. _km
+    pub fn is_quantity_literal(token: &str) -> bool {
+        match token.split_once('_') {
+            Some((num, unit)) => {
+                (num.is_empty() || (num.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c)) && num.parse::<f64>().is_ok()))
+                    && unit.starts_with(char::is_alphabetic)
+                    && unit.chars().all(|c| c.is_alphanumeric() || "*/^-".contains(c))
+            }
+            None => false
+        }
+    }
+
+    // ISO-8601 date or datetime shape, e.g. 2024-03-15 or 2024-03-15T10:30; the parser validates fields
+    pub fn is_datetime_literal(token: &str) -> bool {
+        let bytes = token.as_bytes();
+        bytes.len() >= 10 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-' && token.chars().all(|c| c.is_ascii_digit() || "-:TZ".contains(c))
+    }
+
+    // ISO-8601 duration shape, e.g. P3D or PT4H12M; the parser validates designators
+    pub fn is_duration_literal(token: &str) -> bool {
+        let bytes = token.as_bytes();
+        bytes.len() >= 3
+            && bytes[0] == b'P'
+            && (bytes[1].is_ascii_digit() || bytes[1] == b'T')
+            && !bytes[bytes.len() - 1].is_ascii_digit()
+            && token[1..].chars().all(|c| c.is_ascii_digit() || "YMWDTHS".contains(c))
+    }
+
+    // Number with a d suffix, e.g. 19.99d
+    pub fn is_decimal_literal(token: &str) -> bool {
+        match token.strip_suffix('d') {
+            Some(num) => num.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c)) && num.parse::<f64>().is_ok(),
+            None => false
+        }
+    }
+
+    // Hexadecimal, binary or octal integer, e.g. 0xFF, 0b1010 or 0o17
+    pub fn is_radix_literal(token: &str) -> bool {
+        match Self::radix_digits(token) {
+            Some((digits, radix)) => !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)),
+            None => false
+        }
+    }
+
+    // Digits and radix of a radix literal
+    pub fn radix_digits(token: &str) -> Option<(&str, u32)> {
+        if let Some(digits) = token.strip_prefix("0x") {
+            Some((digits, 16))
+        } else if let Some(digits) = token.strip_prefix("0b") {
+            Some((digits, 2))
+        } else {
+            token.strip_prefix("0o").map(|digits| (digits, 8))
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_string_literal(token: &str) -> bool {
+        token.len() >= 2 && token.starts_with('"') && token.ends_with('"')
+    }
+
     pub fn next_token(&mut self) -> Option<Token> {
         if !self.tokens.is_empty() {
             Some(self.tokens.remove(0))
@@ -151,15 +301,20 @@ impl Lexer {
         }
     }
 
+    // Keywords closest to a misspelled name
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        suggest::closest(name, self.table.keys().map(String::as_str))
+    }
+
     #[inline(always)]
     pub fn is_reserved(&self, name: &str) -> bool {
-        self.table.get(name).is_some()
+        self.table.contains_key(name)
     }
 
     #[inline(always)]
     pub fn check_reserved(&self, tok: &Token, what: &str) -> Result<(), LexerError> {
         if self.is_reserved(&tok.tname) {
-            return Err(LexerError::reserved_name(what, &tok.tname));
+            return Err(LexerError::reserved_name(what, &tok.tname).at(&tok.location()));
         }
         Ok(())
     }
@@ -182,6 +337,7 @@ impl Lexer {
     #[inline(always)]
     pub fn clear(&mut self) {
         self.tokens.clear();
+        self.line = 1;
     }
 
     #[inline(always)]
@@ -192,7 +348,12 @@ impl Lexer {
     // --------------------------------------------------------------------------------
     // Private Functions
 
-    fn is_valid_identifier(token: &str) -> bool {
+    #[inline(always)]
+    fn is_bracket(ch: char) -> bool {
+        ch == '[' || ch == ']' || ch == '{' || ch == '}'
+    }
+
+    pub fn is_valid_identifier(token: &str) -> bool {
         token.starts_with(char::is_alphabetic) && token.find(|c: char| !c.is_alphanumeric() && c != '_').is_none()
     }
 
@@ -207,6 +368,10 @@ impl Lexer {
             table.insert(String::from(sym), TokenType::UnaryOp);
         }
 
+        for sym in keywords::ternary_ops() {
+            table.insert(String::from(sym), TokenType::TernaryOp);
+        }
+
         for sym in keywords::special_ftns() {
             table.insert(String::from(sym), TokenType::SpecialFtn);
         }
@@ -217,6 +382,7 @@ impl Lexer {
 
         table.insert(String::from(keywords::TRUE), TokenType::Literal);
         table.insert(String::from(keywords::FALSE), TokenType::Literal);
+        table.insert(String::from(keywords::NONE), TokenType::Literal);
         table.insert(String::from(keywords::DEFVAR), TokenType::Define);
         table.insert(String::from(keywords::SETVAR), TokenType::Assign);
         table.insert(String::from(keywords::DEFUN), TokenType::Defun);
@@ -228,6 +394,10 @@ impl Lexer {
         table.insert(String::from(keywords::THEN), TokenType::Then);
         table.insert(String::from(keywords::ELSE), TokenType::Else);
         table.insert(String::from(keywords::FI), TokenType::Fi);
+        table.insert(String::from(keywords::LIST_BEGIN), TokenType::ListBegin);
+        table.insert(String::from(keywords::LIST_END), TokenType::ListEnd);
+        table.insert(String::from(keywords::RECORD_BEGIN), TokenType::RecordBegin);
+        table.insert(String::from(keywords::RECORD_END), TokenType::RecordEnd);
 
         table
     }
@@ -273,6 +443,10 @@ mod tests {
             assert_eq!(lexer.token_type(sym).unwrap(), TokenType::UnaryOp);
         }
 
+        for sym in keywords::ternary_ops() {
+            assert_eq!(lexer.token_type(sym).unwrap(), TokenType::TernaryOp);
+        }
+
         for sym in keywords::special_ftns() {
             assert_eq!(lexer.token_type(sym).unwrap(), TokenType::SpecialFtn);
         }
@@ -292,10 +466,110 @@ mod tests {
         assert_eq!(lexer.token_type(keywords::THEN).unwrap(), TokenType::Then);
         assert_eq!(lexer.token_type(keywords::ELSE).unwrap(), TokenType::Else);
         assert_eq!(lexer.token_type(keywords::FI).unwrap(), TokenType::Fi);
+        assert_eq!(lexer.token_type(keywords::LIST_BEGIN).unwrap(), TokenType::ListBegin);
+        assert_eq!(lexer.token_type(keywords::LIST_END).unwrap(), TokenType::ListEnd);
+        assert_eq!(lexer.token_type(keywords::RECORD_BEGIN).unwrap(), TokenType::RecordBegin);
+        assert_eq!(lexer.token_type(keywords::RECORD_END).unwrap(), TokenType::RecordEnd);
         assert_eq!(lexer.token_type(keywords::TRUE).unwrap(), TokenType::Literal);
         assert_eq!(lexer.token_type(keywords::FALSE).unwrap(), TokenType::Literal);
+        assert_eq!(lexer.token_type(keywords::NONE).unwrap(), TokenType::Literal);
         assert_eq!(lexer.token_type("5.0").unwrap(), TokenType::Literal);
         assert_eq!(lexer.token_type("foobar").unwrap(), TokenType::Identifier);
+        assert_eq!(lexer.token_type("\"foo bar\"").unwrap(), TokenType::Literal);
+        assert_eq!(lexer.token_type("\"\"").unwrap(), TokenType::Literal);
+        assert_eq!(lexer.token_type("3_m").unwrap(), TokenType::Literal);
+        assert_eq!(lexer.token_type("_km").unwrap(), TokenType::Literal);
+        assert_eq!(lexer.token_type("foo_bar").unwrap(), TokenType::Identifier);
+    }
+
+    #[test]
+    fn test_is_quantity_literal() {
+        assert!(Lexer::is_quantity_literal("3_m"));
+        assert!(Lexer::is_quantity_literal("-2.5_km"));
+        assert!(Lexer::is_quantity_literal("9.81_m/s^2"));
+        assert!(Lexer::is_quantity_literal("1e3_kg*m/s^-2"));
+        assert!(Lexer::is_quantity_literal("_degC"));
+
+        assert!(!Lexer::is_quantity_literal("3"));
+        assert!(!Lexer::is_quantity_literal("3_"));
+        assert!(!Lexer::is_quantity_literal("_"));
+        assert!(!Lexer::is_quantity_literal("a_b"));
+        assert!(!Lexer::is_quantity_literal("inf_m"));
+        assert!(!Lexer::is_quantity_literal("3_2"));
+        assert!(!Lexer::is_quantity_literal("3_m!"));
+    }
+
+    #[test]
+    fn test_is_datetime_literal() {
+        assert!(Lexer::is_datetime_literal("2024-03-15"));
+        assert!(Lexer::is_datetime_literal("2024-03-15T10:30"));
+        assert!(Lexer::is_datetime_literal("2024-03-15T10:30:05Z"));
+        assert!(Lexer::is_datetime_literal("2024-13-45"));
+
+        assert!(!Lexer::is_datetime_literal("2024"));
+        assert!(!Lexer::is_datetime_literal("2024-03"));
+        assert!(!Lexer::is_datetime_literal("20240315xx"));
+        assert!(!Lexer::is_datetime_literal("2024-03-15 "));
+        assert!(!Lexer::is_datetime_literal("éé24-03-15"));
+    }
+
+    #[test]
+    fn test_is_radix_literal() {
+        assert!(Lexer::is_radix_literal("0xFF"));
+        assert!(Lexer::is_radix_literal("0xdeadBEEF"));
+        assert!(Lexer::is_radix_literal("0b1010"));
+        assert!(Lexer::is_radix_literal("0o17"));
+        assert_eq!(Lexer::radix_digits("0b1010"), Some(("1010", 2)));
+
+        assert!(!Lexer::is_radix_literal("0x"));
+        assert!(!Lexer::is_radix_literal("0xFG"));
+        assert!(!Lexer::is_radix_literal("0b102"));
+        assert!(!Lexer::is_radix_literal("0o8"));
+        assert!(!Lexer::is_radix_literal("0XFF"));
+        assert!(!Lexer::is_radix_literal("255"));
+    }
+
+    #[test]
+    fn test_is_decimal_literal() {
+        assert!(Lexer::is_decimal_literal("19.99d"));
+        assert!(Lexer::is_decimal_literal("-0.5d"));
+        assert!(Lexer::is_decimal_literal("5d"));
+        assert!(Lexer::is_decimal_literal(".25d"));
+        assert!(Lexer::is_decimal_literal("1e3d"));
+
+        assert!(!Lexer::is_decimal_literal("d"));
+        assert!(!Lexer::is_decimal_literal("19.99"));
+        assert!(!Lexer::is_decimal_literal("infd"));
+        assert!(!Lexer::is_decimal_literal("1.2.3d"));
+        assert!(!Lexer::is_decimal_literal("19.99D"));
+    }
+
+    #[test]
+    fn test_is_duration_literal() {
+        assert!(Lexer::is_duration_literal("P3D"));
+        assert!(Lexer::is_duration_literal("PT90M"));
+        assert!(Lexer::is_duration_literal("P3DT4H12M"));
+        assert!(Lexer::is_duration_literal("P1Y"));
+
+        assert!(!Lexer::is_duration_literal("P"));
+        assert!(!Lexer::is_duration_literal("P1"));
+        assert!(!Lexer::is_duration_literal("P12"));
+        assert!(!Lexer::is_duration_literal("PI"));
+        assert!(!Lexer::is_duration_literal("Pa3"));
+        assert!(!Lexer::is_duration_literal("p3d"));
+    }
+
+    #[test]
+    fn test_lexer_split_tokens() {
+        assert_eq!(Lexer::split_tokens("+ a 5").unwrap(), vec!["+", "a", "5"]);
+        assert_eq!(Lexer::split_tokens("concat \"a b\" \"\"").unwrap(), vec!["concat", "\"a b\"", "\"\""]);
+        assert_eq!(Lexer::split_tokens("len \"say \\\"hi\\\"\"").unwrap(), vec!["len", "\"say \\\"hi\\\"\""]);
+        assert_eq!(Lexer::split_tokens("upper \"x\"\"y\"").unwrap(), vec!["upper", "\"x\"", "\"y\""]);
+        assert_eq!(Lexer::split_tokens("  \n").unwrap().len(), 0);
+        assert!(Lexer::split_tokens("len \"abc").is_err());
+        assert_eq!(Lexer::split_tokens("[1 2 3]").unwrap(), vec!["[", "1", "2", "3", "]"]);
+        assert_eq!(Lexer::split_tokens("[ [] \"[a]\" ]").unwrap(), vec!["[", "[", "]", "\"[a]\"", "]"]);
+        assert_eq!(Lexer::split_tokens("{x 1 y [2]}").unwrap(), vec!["{", "x", "1", "y", "[", "2", "]", "}"]);
     }
 
     #[test]
@@ -323,11 +597,44 @@ mod tests {
         assert!(lexer.is_empty());
     }
 
+    #[test]
+    fn test_lexer_spans() {
+        let spans = Lexer::split_spans("+ ab\n  [\"x y\"]", 1).unwrap();
+        assert_eq!(spans[1], ("ab", Span::new(1, 3, 2)));
+        assert_eq!(spans[2], ("[", Span::new(2, 3, 1)));
+        assert_eq!(spans[3], ("\"x y\"", Span::new(2, 4, 5)));
+
+        let mut lexer = Lexer::new();
+        lexer.tokenize("def f x").unwrap();
+        lexer.tokenize("begin 1 end").unwrap();
+        assert_eq!(lexer.tokens[3].span, Span::new(2, 1, 5));
+
+        let err = lexer.tokenize("+ 1 a$b").unwrap_err();
+        assert!(err.suggestions().is_empty());
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+        assert_eq!(err.location(), Some(&Location::new("a$b", Span::new(3, 5, 3))));
+
+        lexer.clear();
+        assert_eq!(lexer.tokenize("sqrt$ 4").unwrap_err().suggestions(), ["sqrt"]);
+
+        lexer.clear();
+        let err = lexer.tokenize("len \"abc").unwrap_err();
+        assert_eq!(err.location(), Some(&Location::new("\"abc", Span::new(1, 5, 4))));
+
+        let tok = Token::at(TokenType::Identifier, "x", Span::new(1, 7, 1));
+        assert!(lexer.check_reserved(&tok, "function parameter definition").is_ok());
+        let tok = Token::at(TokenType::BinaryOp, "max", Span::new(1, 5, 3));
+        assert_eq!(
+            lexer.check_reserved(&tok, "function name definition").unwrap_err().location(),
+            Some(&tok.location())
+        );
+    }
+
     #[test]
     fn test_lexer_search() {
         let tokstr = "def add x y begin + x y end";
         let mut lexer = Lexer::new();
-        lexer.tokenize(&tokstr).unwrap();
+        lexer.tokenize(tokstr).unwrap();
 
         assert!(lexer.starts_with(TokenType::Defun));
         assert!(lexer.ends_with(TokenType::End));
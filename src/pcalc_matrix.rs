@@ -0,0 +1,547 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Matrix Error
+
+pub type MatrixError = Error;
+
+impl MatrixError {
+    pub fn shape_mismatch(op: &str, lhs: &str, rhs: &str) -> Self {
+        MatrixError::from_string(ErrorKind::Domain, format!("Shape mismatch for {} - {} and {}", op, lhs, rhs))
+    }
+
+    pub fn not_square(op: &str, mat: &Matrix) -> Self {
+        MatrixError::from_string(ErrorKind::Domain, format!("{} requires a square matrix - {}", op, mat.shape()))
+    }
+
+    pub fn singular(op: &str) -> Self {
+        MatrixError::from_string(ErrorKind::Domain, format!("Singular matrix for {}", op))
+    }
+}
+
+pub type VectorResult = Result<Vector, MatrixError>;
+pub type MatrixResult = Result<Matrix, MatrixError>;
+
+// --------------------------------------------------------------------------------
+// Vector - Non-empty list of real components
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vector {
+    items: Vec<f64>
+}
+
+impl Vector {
+    pub fn new(items: Vec<f64>) -> VectorResult {
+        if items.is_empty() {
+            return Err(MatrixError::new(ErrorKind::Domain, "Empty vector"));
+        }
+        Ok(Vector { items })
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    // Vectors are never empty
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    pub fn items(&self) -> &[f64] {
+        &self.items
+    }
+
+    #[inline(always)]
+    pub fn shape(&self) -> String {
+        format!("vector of {}", self.items.len())
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.items.iter().all(|x| *x == 0.0)
+    }
+
+    pub fn dot(&self, other: &Vector) -> Result<f64, MatrixError> {
+        self.check_len("dot", other)?;
+        Ok(self.items.iter().zip(&other.items).map(|(x, y)| x * y).sum())
+    }
+
+    pub fn cross(&self, other: &Vector) -> VectorResult {
+        if self.len() != 3 || other.len() != 3 {
+            return Err(MatrixError::shape_mismatch("cross", &self.shape(), &other.shape()));
+        }
+        let (a, b) = (&self.items, &other.items);
+        Vector::new(vec![a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]])
+    }
+
+    // Euclidean length
+    pub fn norm(&self) -> f64 {
+        self.items.iter().fold(0.0, |acc: f64, x| acc.hypot(*x))
+    }
+
+    pub fn map(&self, ftn: impl Fn(f64) -> f64) -> Self {
+        Vector {
+            items: self.items.iter().map(|x| ftn(*x)).collect()
+        }
+    }
+
+    // Element-wise ftn of equal length vectors
+    pub fn zip(&self, op: &str, other: &Vector, ftn: fn(f64, f64) -> f64) -> VectorResult {
+        self.check_len(op, other)?;
+        Ok(Vector {
+            items: self.items.iter().zip(&other.items).map(|(x, y)| ftn(*x, *y)).collect()
+        })
+    }
+
+    // Components printed with fmt_num, e.g. (1, 2, 3)
+    pub fn to_string_with(&self, fmt_num: impl Fn(f64) -> String) -> String {
+        let items: Vec<String> = self.items.iter().map(|x| fmt_num(positive_zero(*x))).collect();
+        format!("({})", items.join(", "))
+    }
+
+    fn check_len(&self, op: &str, other: &Vector) -> Result<(), MatrixError> {
+        if self.len() != other.len() {
+            return Err(MatrixError::shape_mismatch(op, &self.shape(), &other.shape()));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(|x| x.to_string()))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Matrix - Non-empty grid of reals, stored row by row
+//
+// Square matrices up to SMALL_SIZE use cofactor expansion for det and inv, and
+// Cramer's rule for solve, which are exact for small integer entries. Larger
+// matrices use Gaussian elimination with partial pivoting.
+
+const SMALL_SIZE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>
+}
+
+impl Matrix {
+    pub fn new(rows: Vec<Vec<f64>>) -> MatrixResult {
+        let cols = rows.first().map_or(0, |row| row.len());
+        if cols == 0 {
+            return Err(MatrixError::new(ErrorKind::Domain, "Empty matrix"));
+        }
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(MatrixError::new(ErrorKind::Domain, "Matrix rows must have equal length"));
+        }
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.concat()
+        })
+    }
+
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline(always)]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline(always)]
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.data[row * self.cols + col]
+    }
+
+    #[inline(always)]
+    pub fn row(&self, row: usize) -> Vector {
+        Vector {
+            items: self.data[row * self.cols..(row + 1) * self.cols].to_vec()
+        }
+    }
+
+    #[inline(always)]
+    pub fn shape(&self) -> String {
+        format!("{}x{} matrix", self.rows, self.cols)
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.data.iter().all(|x| *x == 0.0)
+    }
+
+    pub fn transpose(&self) -> Self {
+        self.generate(self.cols, self.rows, |r, c| self.get(c, r))
+    }
+
+    pub fn mul(&self, other: &Matrix) -> MatrixResult {
+        if self.cols != other.rows {
+            return Err(MatrixError::shape_mismatch("*", &self.shape(), &other.shape()));
+        }
+        Ok(self.generate(self.rows, other.cols, |r, c| (0..self.cols).map(|k| self.get(r, k) * other.get(k, c)).sum()))
+    }
+
+    // Matrix times column vector
+    pub fn mul_vec(&self, vec: &Vector) -> VectorResult {
+        if self.cols != vec.len() {
+            return Err(MatrixError::shape_mismatch("*", &self.shape(), &vec.shape()));
+        }
+        Ok(Vector {
+            items: (0..self.rows)
+                .map(|r| self.row(r).items.iter().zip(&vec.items).map(|(x, y)| x * y).sum())
+                .collect()
+        })
+    }
+
+    // Row vector times matrix
+    pub fn vec_mul(&self, vec: &Vector) -> VectorResult {
+        if self.rows != vec.len() {
+            return Err(MatrixError::shape_mismatch("*", &vec.shape(), &self.shape()));
+        }
+        self.transpose().mul_vec(vec)
+    }
+
+    pub fn det(&self) -> Result<f64, MatrixError> {
+        self.check_square("det")?;
+        if self.rows <= SMALL_SIZE {
+            Ok(self.cofactor_det())
+        } else {
+            let mut a = self.data.clone();
+            Ok(eliminate(&mut a, &mut [], self.rows, 0))
+        }
+    }
+
+    pub fn inv(&self) -> MatrixResult {
+        self.check_square("inv")?;
+        let n = self.rows;
+        if n <= SMALL_SIZE {
+            let det = self.cofactor_det();
+            if det == 0.0 {
+                return Err(MatrixError::singular("inv"));
+            }
+            // Adjugate over determinant
+            return Ok(self.generate(n, n, |r, c| self.cofactor(c, r) / det));
+        }
+        let mut identity = vec![0.0; n * n];
+        for i in 0..n {
+            identity[i * n + i] = 1.0;
+        }
+        Ok(Matrix {
+            rows: n,
+            cols: n,
+            data: self.eliminate_solve("inv", identity, n)?
+        })
+    }
+
+    // x such that self * x is rhs
+    pub fn solve(&self, rhs: &Vector) -> VectorResult {
+        self.check_square("solve")?;
+        if self.rows != rhs.len() {
+            return Err(MatrixError::shape_mismatch("solve", &self.shape(), &rhs.shape()));
+        }
+        let n = self.rows;
+        if n <= SMALL_SIZE {
+            let det = self.cofactor_det();
+            if det == 0.0 {
+                return Err(MatrixError::singular("solve"));
+            }
+            // Cramer's rule
+            let items = (0..n)
+                .map(|i| self.generate(n, n, |r, c| if c == i { rhs.items[r] } else { self.get(r, c) }).cofactor_det() / det)
+                .collect();
+            return Ok(Vector { items });
+        }
+        Ok(Vector {
+            items: self.eliminate_solve("solve", rhs.items.clone(), 1)?
+        })
+    }
+
+    pub fn map(&self, ftn: impl Fn(f64) -> f64) -> Self {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|x| ftn(*x)).collect()
+        }
+    }
+
+    // Element-wise ftn of equal shape matrices
+    pub fn zip(&self, op: &str, other: &Matrix, ftn: fn(f64, f64) -> f64) -> MatrixResult {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err(MatrixError::shape_mismatch(op, &self.shape(), &other.shape()));
+        }
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().zip(&other.data).map(|(x, y)| ftn(*x, *y)).collect()
+        })
+    }
+
+    // Rows on separate lines, with entries printed with fmt_num and columns right aligned
+    pub fn to_string_with(&self, fmt_num: impl Fn(f64) -> String) -> String {
+        let cells: Vec<String> = self.data.iter().map(|x| fmt_num(positive_zero(*x))).collect();
+        let widths: Vec<usize> = (0..self.cols)
+            .map(|c| (0..self.rows).map(|r| cells[r * self.cols + c].len()).max().unwrap_or(0))
+            .collect();
+        let lines: Vec<String> = (0..self.rows)
+            .map(|r| {
+                let row: Vec<String> = (0..self.cols)
+                    .map(|c| format!("{:>width$}", cells[r * self.cols + c], width = widths[c]))
+                    .collect();
+                format!("[{}]", row.join("  "))
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    fn generate(&self, rows: usize, cols: usize, ftn: impl Fn(usize, usize) -> f64) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: (0..rows * cols).map(|i| ftn(i / cols, i % cols)).collect()
+        }
+    }
+
+    fn check_square(&self, op: &str) -> Result<(), MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::not_square(op, self));
+        }
+        Ok(())
+    }
+
+    // Laplace expansion along the first row, for small square matrices
+    fn cofactor_det(&self) -> f64 {
+        if self.rows == 0 {
+            return 1.0;
+        }
+        (0..self.cols).map(|c| self.get(0, c) * self.cofactor(0, c)).sum()
+    }
+
+    fn cofactor(&self, row: usize, col: usize) -> f64 {
+        let minor = Matrix {
+            rows: self.rows - 1,
+            cols: self.cols - 1,
+            data: (0..self.rows)
+                .filter(|r| *r != row)
+                .flat_map(|r| (0..self.cols).filter(|c| *c != col).map(move |c| (r, c)))
+                .map(|(r, c)| self.get(r, c))
+                .collect()
+        };
+        let sign = if (row + col).is_multiple_of(2) { 1.0 } else { -1.0 };
+        sign * minor.cofactor_det()
+    }
+
+    // Solve self * x = b for the n x m right hand side b, by elimination and back substitution
+    fn eliminate_solve(&self, op: &str, mut b: Vec<f64>, m: usize) -> Result<Vec<f64>, MatrixError> {
+        let n = self.rows;
+        let mut a = self.data.clone();
+        eliminate(&mut a, &mut b, n, m);
+
+        // Pivots that are rounding noise relative to the largest entry mean a singular matrix
+        let tolerance = self.data.iter().fold(0.0, |acc: f64, x| acc.max(x.abs())) * f64::EPSILON * n as f64;
+        if (0..n).any(|i| a[i * n + i].abs() <= tolerance) {
+            return Err(MatrixError::singular(op));
+        }
+
+        for i in (0..n).rev() {
+            for j in 0..m {
+                let sum: f64 = (i + 1..n).map(|k| a[i * n + k] * b[k * m + j]).sum();
+                b[i * m + j] = (b[i * m + j] - sum) / a[i * n + i];
+            }
+        }
+        Ok(b)
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(|x| x.to_string()))
+    }
+}
+
+// Zero without its sign, since elimination leaves -0 entries that would print as -0
+#[inline(always)]
+fn positive_zero(x: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+// Gaussian elimination with partial pivoting of the n x n matrix a, applying the same row
+// operations to the n x m matrix b. Leaves a upper triangular and returns its determinant.
+fn eliminate(a: &mut [f64], b: &mut [f64], n: usize, m: usize) -> f64 {
+    let mut det = 1.0;
+    for k in 0..n {
+        let pivot_row = (k..n).fold(k, |best, i| if a[i * n + k].abs() > a[best * n + k].abs() { i } else { best });
+        if pivot_row != k {
+            for j in 0..n {
+                a.swap(k * n + j, pivot_row * n + j);
+            }
+            for j in 0..m {
+                b.swap(k * m + j, pivot_row * m + j);
+            }
+            det = -det;
+        }
+
+        let pivot = a[k * n + k];
+        det *= pivot;
+        if pivot == 0.0 {
+            continue;
+        }
+        for i in k + 1..n {
+            let factor = a[i * n + k] / pivot;
+            for j in k..n {
+                a[i * n + j] -= factor * a[k * n + j];
+            }
+            for j in 0..m {
+                b[i * m + j] -= factor * b[k * m + j];
+            }
+        }
+    }
+    det
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(items: &[f64]) -> Vector {
+        Vector::new(items.to_vec()).unwrap()
+    }
+
+    fn matrix(rows: &[&[f64]]) -> Matrix {
+        Matrix::new(rows.iter().map(|row| row.to_vec()).collect()).unwrap()
+    }
+
+    fn approx(x: f64, y: f64) -> bool {
+        (x - y).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_vector() {
+        let u = vector(&[1.0, 2.0, 3.0]);
+        let v = vector(&[4.0, 5.0, 6.0]);
+        assert_eq!(u.len(), 3);
+        assert_eq!(format!("{}", u), "(1, 2, 3)");
+        assert_eq!(format!("{}", Vector::new(vec![-0.0, 1.0]).unwrap()), "(0, 1)");
+        assert_eq!(u.dot(&v).unwrap(), 32.0);
+        assert_eq!(u.cross(&v).unwrap(), vector(&[-3.0, 6.0, -3.0]));
+        assert_eq!(vector(&[3.0, 4.0]).norm(), 5.0);
+        assert_eq!(u.map(|x| x * 2.0), vector(&[2.0, 4.0, 6.0]));
+        assert_eq!(u.zip("+", &v, |x, y| x + y).unwrap(), vector(&[5.0, 7.0, 9.0]));
+
+        let w = vector(&[1.0, 2.0]);
+        assert_eq!(format!("{}", u.dot(&w).unwrap_err()), "Shape mismatch for dot - vector of 3 and vector of 2");
+        assert!(u.cross(&w).is_err());
+        assert!(u.zip("+", &w, |x, y| x + y).is_err());
+        assert_eq!(format!("{}", Vector::new(vec![]).unwrap_err()), "Empty vector");
+    }
+
+    #[test]
+    fn test_matrix_new() {
+        let m = matrix(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+        assert_eq!((m.rows(), m.cols()), (2, 3));
+        assert_eq!(m.get(1, 2), 6.0);
+        assert_eq!(m.row(1), vector(&[4.0, 5.0, 6.0]));
+        assert_eq!(m.shape(), "2x3 matrix");
+        assert_eq!(m.transpose(), matrix(&[&[1.0, 4.0], &[2.0, 5.0], &[3.0, 6.0]]));
+
+        assert_eq!(format!("{}", Matrix::new(vec![]).unwrap_err()), "Empty matrix");
+        assert_eq!(format!("{}", Matrix::new(vec![vec![]]).unwrap_err()), "Empty matrix");
+        assert_eq!(
+            format!("{}", Matrix::new(vec![vec![1.0], vec![1.0, 2.0]]).unwrap_err()),
+            "Matrix rows must have equal length"
+        );
+    }
+
+    #[test]
+    fn test_matrix_display() {
+        assert_eq!(format!("{}", matrix(&[&[1.0, 2.0], &[3.0, 4.0]])), "[1  2]\n[3  4]");
+        assert_eq!(format!("{}", matrix(&[&[1.0, -20.5], &[300.0, 4.0]])), "[  1  -20.5]\n[300      4]");
+        assert_eq!(format!("{}", matrix(&[&[1.0, -0.0], &[-0.0, 1.0]])), "[1  0]\n[0  1]");
+        assert_eq!(format!("{}", matrix(&[&[1.0, 0.0], &[0.0, 1.0]]).inv().unwrap()), "[1  0]\n[0  1]");
+    }
+
+    #[test]
+    fn test_matrix_mul() {
+        let a = matrix(&[&[1.0, 2.0], &[3.0, 4.0]]);
+        let b = matrix(&[&[5.0, 6.0], &[7.0, 8.0]]);
+        assert_eq!(a.mul(&b).unwrap(), matrix(&[&[19.0, 22.0], &[43.0, 50.0]]));
+        assert_eq!(a.mul_vec(&vector(&[1.0, 1.0])).unwrap(), vector(&[3.0, 7.0]));
+        assert_eq!(a.vec_mul(&vector(&[1.0, 1.0])).unwrap(), vector(&[4.0, 6.0]));
+
+        let c = matrix(&[&[1.0, 2.0, 3.0]]);
+        assert_eq!(c.mul(&c.transpose()).unwrap(), matrix(&[&[14.0]]));
+        assert_eq!(
+            format!("{}", a.mul(&c.transpose()).unwrap_err()),
+            "Shape mismatch for * - 2x2 matrix and 3x1 matrix"
+        );
+        assert!(a.mul_vec(&vector(&[1.0, 2.0, 3.0])).is_err());
+        assert!(a.vec_mul(&vector(&[1.0, 2.0, 3.0])).is_err());
+        assert!(a.zip("+", &c, |x, y| x + y).is_err());
+    }
+
+    #[test]
+    fn test_matrix_det_inv() {
+        let a = matrix(&[&[1.0, 2.0], &[3.0, 4.0]]);
+        assert_eq!(a.det().unwrap(), -2.0);
+        assert_eq!(a.inv().unwrap(), matrix(&[&[-2.0, 1.0], &[1.5, -0.5]]));
+        assert_eq!(matrix(&[&[2.0, 0.0, 1.0], &[1.0, 3.0, 2.0], &[1.0, 1.0, 2.0]]).det().unwrap(), 6.0);
+        assert_eq!(matrix(&[&[4.0]]).inv().unwrap(), matrix(&[&[0.25]]));
+
+        let singular = matrix(&[&[1.0, 2.0], &[2.0, 4.0]]);
+        assert_eq!(singular.det().unwrap(), 0.0);
+        assert_eq!(format!("{}", singular.inv().unwrap_err()), "Singular matrix for inv");
+
+        let rect = matrix(&[&[1.0, 2.0, 3.0]]);
+        assert_eq!(format!("{}", rect.det().unwrap_err()), "det requires a square matrix - 1x3 matrix");
+        assert!(rect.inv().is_err());
+
+        // Larger matrices use elimination
+        let big = matrix(&[&[2.0, 1.0, 0.0, 0.0], &[1.0, 2.0, 1.0, 0.0], &[0.0, 1.0, 2.0, 1.0], &[0.0, 0.0, 1.0, 2.0]]);
+        assert!(approx(big.det().unwrap(), 5.0));
+        let prod = big.mul(&big.inv().unwrap()).unwrap();
+        for r in 0..4 {
+            for c in 0..4 {
+                assert!(approx(prod.get(r, c), if r == c { 1.0 } else { 0.0 }));
+            }
+        }
+        let big_singular = matrix(&[&[1.0, 2.0, 3.0, 4.0], &[2.0, 4.0, 6.0, 8.0], &[0.0, 1.0, 0.0, 1.0], &[1.0, 0.0, 1.0, 0.0]]);
+        assert_eq!(big_singular.det().unwrap(), 0.0);
+        assert!(big_singular.inv().is_err());
+    }
+
+    #[test]
+    fn test_matrix_solve() {
+        let a = matrix(&[&[2.0, 1.0], &[1.0, 3.0]]);
+        assert_eq!(a.solve(&vector(&[3.0, 5.0])).unwrap(), vector(&[0.8, 1.4]));
+        assert_eq!(
+            format!("{}", a.solve(&vector(&[1.0, 2.0, 3.0])).unwrap_err()),
+            "Shape mismatch for solve - 2x2 matrix and vector of 3"
+        );
+        assert_eq!(
+            format!("{}", matrix(&[&[1.0, 2.0], &[2.0, 4.0]]).solve(&vector(&[1.0, 2.0])).unwrap_err()),
+            "Singular matrix for solve"
+        );
+
+        let big = matrix(&[&[4.0, 1.0, 0.0, 0.0], &[1.0, 4.0, 1.0, 0.0], &[0.0, 1.0, 4.0, 1.0], &[0.0, 0.0, 1.0, 4.0]]);
+        let x = vector(&[1.0, -2.0, 3.0, 0.5]);
+        let b = big.mul_vec(&x).unwrap();
+        let solved = big.solve(&b).unwrap();
+        assert!(solved.items().iter().zip(x.items()).all(|(s, e)| approx(*s, *e)));
+    }
+}
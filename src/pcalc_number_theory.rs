@@ -0,0 +1,211 @@
+use std::cmp;
+
+// --------------------------------------------------------------------------------
+// Number Theory - gcd, counting, primes and modular arithmetic
+//
+// Operands are magnitudes of 64 bit words, so products of two of them fit in u128.
+
+// Miller-Rabin witnesses that decide primality for every n < 2^64
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// None on overflow, and 0 when either operand is 0
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        Some(0)
+    } else {
+        (a / gcd(a, b)).checked_mul(b)
+    }
+}
+
+pub fn factorial(n: u128) -> Option<u128> {
+    (2..=n).try_fold(1u128, |acc, i| acc.checked_mul(i))
+}
+
+// Ways to choose k of n items, in any order
+pub fn choose(n: u128, k: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    // Each partial product is itself a binomial, so the division is exact
+    (0..cmp::min(k, n - k)).try_fold(1u128, |acc, i| acc.checked_mul(n - i).map(|p| p / (i + 1)))
+}
+
+// Ways to arrange k of n items, in order
+pub fn permutations(n: u128, k: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    (0..k).try_fold(1u128, |acc, i| acc.checked_mul(n - i))
+}
+
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if let Some(p) = WITNESSES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    WITNESSES.iter().all(|a| {
+        let mut x = mod_pow(*a, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..shift {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+// Smallest prime greater than n, or None past u64::MAX
+pub fn next_prime(n: u64) -> Option<u64> {
+    (n.checked_add(1)?..=u64::MAX).find(|c| is_prime(*c))
+}
+
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let (mut result, mut base) = (1 % m, base % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// Inverse of a modulo m, when a and m are coprime
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (i128::from(a), i128::from(m));
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quot = old_r / r;
+        (old_r, r) = (r, old_r - quot * r);
+        (old_s, s) = (s, old_s - quot * s);
+    }
+    (old_r == 1).then(|| old_s.rem_euclid(i128::from(m)) as u64)
+}
+
+// Prime factors in ascending order, repeated by multiplicity, e.g. 12 is [2, 2, 3]
+pub fn factor(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    for p in WITNESSES {
+        while n > 1 && n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+    split(n, &mut factors);
+    factors.sort_unstable();
+    factors
+}
+
+// Factors of n, which has no prime factors among the witnesses
+fn split(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let d = pollard_rho(n);
+    split(d, factors);
+    split(n / d, factors);
+}
+
+// Nontrivial divisor of the odd composite n, trying successive polynomials x^2 + c
+fn pollard_rho(n: u64) -> u64 {
+    let mut c = 1u64;
+    loop {
+        let next = |x: u64| ((u128::from(x) * u128::from(x) + u128::from(c)) % u128::from(n)) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = next(x);
+            y = next(next(y));
+            d = gcd(u128::from(x.abs_diff(y)), u128::from(n)) as u64;
+        }
+        if d != n {
+            return d;
+        }
+        c += 1;
+    }
+}
+
+#[inline(always)]
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u128::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_counting() {
+        assert_eq!(factorial(0), Some(1));
+        assert_eq!(factorial(20), Some(2432902008176640000));
+        assert_eq!(factorial(35), None);
+        assert_eq!(choose(5, 2), Some(10));
+        assert_eq!(choose(52, 5), Some(2598960));
+        assert_eq!(choose(67, 33), Some(14226520737620288370));
+        assert_eq!(choose(3, 5), Some(0));
+        assert_eq!(permutations(5, 2), Some(20));
+        assert_eq!(permutations(5, 0), Some(1));
+        assert_eq!(permutations(3, 5), Some(0));
+    }
+
+    #[test]
+    fn test_primes() {
+        let small: Vec<u64> = (0..30).filter(|n| is_prime(*n)).collect();
+        assert_eq!(small, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(is_prime(1_000_000_007));
+        assert!(is_prime(18446744073709551557));
+        assert!(!is_prime(3215031751)); // Strong pseudoprime to bases 2, 3, 5 and 7
+        assert_eq!(next_prime(13), Some(17));
+        assert_eq!(next_prime(18446744073709551557), None);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(2, 4), None);
+    }
+
+    #[test]
+    fn test_factor() {
+        assert_eq!(factor(1), Vec::<u64>::new());
+        assert_eq!(factor(12), vec![2, 2, 3]);
+        assert_eq!(factor(97), vec![97]);
+        assert_eq!(factor(600851475143), vec![71, 839, 1471, 6857]);
+        assert_eq!(factor(4294967291 * 4294967279), vec![4294967279, 4294967291]);
+        assert_eq!(factor(u64::MAX), vec![3, 5, 17, 257, 641, 65537, 6700417]);
+    }
+}
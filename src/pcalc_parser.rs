@@ -1,48 +1,39 @@
-use crate::pcalc_binary_ops::bop2ftn;
-use crate::pcalc_code::{BinaryOp, CodePtr, Conditional, DefVar, Defun, Funcall, GetVar, Literal, NoOp, SetVar, UnaryOp, XPrint};
+use crate::pcalc_binary_ops::{approx_equal, bop2ftn, is_logical, not_approx_equal};
+use crate::pcalc_code::{
+    ApproxEqual, BinaryOp, CodePtr, Conditional, DefVar, Defun, Fields, Funcall, GetVar, ListExpr, ListFilter, ListFold, ListMap, Literal, Located, LogicalNot,
+    LogicalOp, NoOp, Now, RecordExpr, RecordGet, RecordWith, SetVar, TernaryOp, UnaryOp, XPrint
+};
+use crate::pcalc_datetime::{DateTime, Duration};
+use crate::pcalc_decimal::Decimal;
+use crate::pcalc_error::{Error, ErrorKind, Location};
 use crate::pcalc_function::{Arguments, Expressions, Parameters};
 use crate::pcalc_keywords as keywords;
-use crate::pcalc_lexer::{Lexer, LexerError, TokenType};
+use crate::pcalc_lexer::{Lexer, TokenType};
+use crate::pcalc_ternary_ops::top2ftn;
 use crate::pcalc_unary_ops::uop2ftn;
-use crate::pcalc_value::Value;
+use crate::pcalc_units::{Quantity, Unit};
+use crate::pcalc_value::{Value, MAX_RATIONAL_BITS};
+use crate::pcalc_word as word;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::Pow;
 use std::f64::consts;
-use std::fmt;
 
 // --------------------------------------------------------------------------------
 // Parser Error
 
-#[derive(Debug, Clone)]
-pub struct ParserError {
-    error_msg: String
-}
+pub type ParserError = Error;
 
 impl ParserError {
-    pub fn new(err_msg: &str) -> ParserError {
-        ParserError {
-            error_msg: String::from(err_msg)
-        }
-    }
-}
-
-impl fmt::Display for ParserError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.error_msg)
+    pub fn syntax(err_msg: &str) -> Self {
+        ParserError::new(ErrorKind::Syntax, err_msg)
     }
 }
 
 impl From<std::num::ParseFloatError> for ParserError {
     fn from(item: std::num::ParseFloatError) -> Self {
-        ParserError {
-            error_msg: format!("{}", item)
-        }
-    }
-}
-
-impl From<LexerError> for ParserError {
-    fn from(item: LexerError) -> Self {
-        ParserError {
-            error_msg: String::from(item.message())
-        }
+        ParserError::from_string(ErrorKind::Syntax, format!("{}", item))
     }
 }
 
@@ -55,18 +46,24 @@ pub type ParserResult = Result<CodePtr, ParserError>;
 // Parser
 
 pub struct Parser {
-    lexer: Lexer
+    lexer: Lexer,
+    rational: bool,
+    decimal: bool
 }
 
 impl Parser {
     pub fn new() -> Self {
-        Parser { lexer: Default::default() }
+        Parser {
+            lexer: Default::default(),
+            rational: false,
+            decimal: false
+        }
     }
 
     pub fn parse(&mut self, expr: &str) -> ParserResult {
         if let Err(err) = self.lexer.tokenize(expr) {
             self.lexer.clear();
-            return Err(err.into());
+            return Err(err);
         }
 
         if self.lexer.starts_with(TokenType::Defun) && !self.lexer.ends_with(TokenType::End) {
@@ -74,12 +71,21 @@ impl Parser {
             return Ok(Box::new(NoOp::new()));
         }
 
+        let first = self.lexer.peek_token().cloned();
         match self.make_code() {
             Ok(code) => {
                 // Expect a full/complete expression.
-                if !self.lexer.is_empty() {
+                if let Some(extra) = self.lexer.peek_token().map(|tok| tok.location()) {
                     self.lexer.clear();
-                    Err(ParserError::new(&format!("Invalid expression - '{}'", expr)))
+                    let err = ParserError::syntax(&format!("Invalid expression - '{}'", expr));
+                    // A leading name followed by operands is likely a misspelled operator, e.g. sqr 4
+                    match first
+                        .filter(|tok| tok.ttype == TokenType::Identifier)
+                        .map(|tok| (self.lexer.suggest(&tok.tname), tok.location()))
+                    {
+                        Some((suggestions, name)) if !suggestions.is_empty() => Err(err.with_suggestions(suggestions).at(&name)),
+                        _ => Err(err.at(&extra))
+                    }
                 } else {
                     Ok(code)
                 }
@@ -96,79 +102,222 @@ impl Parser {
         self.lexer.is_empty()
     }
 
+    // Rational mode parses numeric literals as exact rationals
+    #[inline(always)]
+    pub fn is_rational(&self) -> bool {
+        self.rational
+    }
+
+    #[inline(always)]
+    pub fn set_rational(&mut self, flag: bool) {
+        self.rational = flag;
+        if flag {
+            self.decimal = false;
+        }
+    }
+
+    // Decimal mode parses numeric literals as exact decimals; it replaces rational mode
+    #[inline(always)]
+    pub fn is_decimal(&self) -> bool {
+        self.decimal
+    }
+
+    #[inline(always)]
+    pub fn set_decimal(&mut self, flag: bool) {
+        self.decimal = flag;
+        if flag {
+            self.rational = false;
+        }
+    }
+
     // --------------------------------------------------------------------------------
     // Private Functions
 
+    // Errors without a more specific token are located at the expression's first token,
+    // and code that can fail at eval time is wrapped with its token's location.
     fn make_code(&mut self) -> ParserResult {
         if let Some(first) = self.lexer.next_token() {
-            match first.ttype {
-                TokenType::Literal => self.make_literal(&first.tname),
+            let location = first.location();
+            let code = match first.ttype {
+                TokenType::Literal => self.make_literal(&first.tname).map_err(|err| err.with_kind(ErrorKind::Syntax)),
                 TokenType::Const => self.make_const(&first.tname),
                 TokenType::Define => self.make_variable(),
                 TokenType::Assign => self.make_set_variable(),
                 TokenType::Defun => self.make_function(),
                 TokenType::Funcall => self.make_funcall(),
-                TokenType::BinaryOp => self.make_binary_op(&first.tname),
-                TokenType::UnaryOp => self.make_unary_op(&first.tname),
-                TokenType::SpecialFtn => self.make_special_ftn(&first.tname),
-                TokenType::Identifier => self.make_get_variable(&first.tname),
-                TokenType::Begin => Err(ParserError::new("Invalid expression containing begin")),
-                TokenType::End | TokenType::CEnd => Err(ParserError::new("Invalid expression containing end")),
-                TokenType::If => self.make_conditional(&first.tname),
-                TokenType::Then => Err(ParserError::new("Invalid expression containing then")),
-                TokenType::Else => Err(ParserError::new("Invalid expression containing else")),
-                TokenType::Fi => Err(ParserError::new("Invalid expression containing fi"))
-            }
+                TokenType::BinaryOp => self.make_binary_op(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::UnaryOp => self.make_unary_op(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::TernaryOp => self.make_ternary_op(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::SpecialFtn => self.make_special_ftn(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::Identifier => self.make_get_variable(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::Begin => Err(ParserError::syntax("Invalid expression containing begin")),
+                TokenType::End | TokenType::CEnd => Err(ParserError::syntax("Invalid expression containing end")),
+                TokenType::If => self.make_conditional(&first.tname).map(|code| Self::locate(code, &location)),
+                TokenType::Then => Err(ParserError::syntax("Invalid expression containing then")),
+                TokenType::Else => Err(ParserError::syntax("Invalid expression containing else")),
+                TokenType::Fi => Err(ParserError::syntax("Invalid expression containing fi")),
+                TokenType::ListBegin => self.make_list(),
+                TokenType::ListEnd => Err(ParserError::syntax("Invalid expression containing ]")),
+                TokenType::RecordBegin => self.make_record(),
+                TokenType::RecordEnd => Err(ParserError::syntax("Invalid expression containing }"))
+            };
+            code.map_err(|err| err.at(&location))
         } else {
-            Err(ParserError::new("Expecting token"))
+            Err(ParserError::syntax("Expecting token"))
         }
     }
 
+    #[inline(always)]
+    fn locate(code: CodePtr, location: &Location) -> CodePtr {
+        Box::new(Located::new(code, location.clone()))
+    }
+
     fn make_literal(&self, tname: &str) -> ParserResult {
         let value = match tname {
             keywords::TRUE => Value::from_bool(true),
             keywords::FALSE => Value::from_bool(false),
-            _ => Value::from_num(tname.parse::<f64>()?)
+            keywords::NONE => Value::from_none(),
+            _ if Lexer::is_string_literal(tname) => Value::from_string(Self::parse_string(tname)?),
+            _ if Lexer::is_quantity_literal(tname) => Self::parse_quantity(tname)?,
+            _ if Lexer::is_datetime_literal(tname) => Value::from_datetime(DateTime::parse(tname)?),
+            _ if Lexer::is_duration_literal(tname) => Value::from_duration(Duration::parse(tname)?),
+            _ if Lexer::is_decimal_literal(tname) => Value::from_decimal(Decimal::parse(&tname[..tname.len() - 1])?),
+            _ if Lexer::is_radix_literal(tname) => Value::from_int(Self::parse_radix(tname)?),
+            _ if self.decimal => Value::from_decimal(Decimal::parse(tname)?),
+            _ if self.rational => match Self::parse_rational(tname)? {
+                Some(r) => Value::from_rational(r),
+                None => Value::from_num(tname.parse::<f64>()?)
+            },
+            _ => match Self::parse_int(tname)? {
+                Some(i) => Value::from_int(i),
+                None => Value::from_num(tname.parse::<f64>()?)
+            }
         };
         Ok(Box::new(Literal::new(value)))
     }
 
+    // Radix literals are bit patterns of the current word, e.g. 0xFF is -1 for i8 words
+    fn parse_radix(tname: &str) -> Result<i64, ParserError> {
+        let size = word::context().word();
+        match Lexer::radix_digits(tname).and_then(|(digits, radix)| u64::from_str_radix(digits, radix).ok()) {
+            Some(bits) if bits & !size.mask() == 0 => Ok(size.from_bits(bits)),
+            _ => Err(ParserError::syntax(&format!("Integer literal too large for {} - '{}'", size.name(), tname)))
+        }
+    }
+
+    // Decimal integers are values of the current word, e.g. 200 is too large for i8.
+    // Without a word size, integers outside i64 are parsed as floats.
+    fn parse_int(tname: &str) -> Result<Option<i64>, ParserError> {
+        let digits = tname.strip_prefix(['-', '+']).unwrap_or(tname);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(None);
+        }
+        let Some(size) = word::context().size() else {
+            return Ok(tname.parse::<i64>().ok());
+        };
+        match tname.parse::<i128>() {
+            Ok(n) if (size.min()..=size.max()).contains(&n) => Ok(Some(size.from_bits(n as u64))),
+            _ if tname.starts_with('-') => Err(ParserError::syntax(&format!("Integer literal too small for {} - '{}'", size.name(), tname))),
+            _ => Err(ParserError::syntax(&format!("Integer literal too large for {} - '{}'", size.name(), tname)))
+        }
+    }
+
+    // Magnitude defaults to 1 for a bare unit, e.g. _km
+    fn parse_quantity(tname: &str) -> Result<Value, ParserError> {
+        let (num, unit) = tname.split_once('_').unwrap_or(("", tname));
+        let magnitude = if num.is_empty() { 1.0 } else { num.parse::<f64>()? };
+        Ok(Value::from_quantity(Quantity::new(magnitude, Unit::parse(unit)?)))
+    }
+
+    // Strip the quotes and resolve escapes: \" \\ \n \t. Any other escape is an error.
+    fn parse_string(tname: &str) -> Result<String, ParserError> {
+        let mut result = String::new();
+        let mut chars = tname[1..tname.len() - 1].chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\\' {
+                match chars.next() {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some(other @ ('"' | '\\')) => result.push(other),
+                    Some(other) => return Err(ParserError::syntax(&format!("Invalid escape - '\\{}'", other))),
+                    None => result.push(ch)
+                }
+            } else {
+                result.push(ch);
+            }
+        }
+        Ok(result)
+    }
+
+    // Parse decimal notation, e.g. 0.1 or 2.5e-3, exactly.
+    // None for other numeric literals, e.g. inf, which are parsed as floats.
+    fn parse_rational(tname: &str) -> Result<Option<BigRational>, ParserError> {
+        let overflow = || ParserError::syntax(&format!("Rational literal out of range - '{}'", tname));
+        let (mantissa, exponent) = match tname.find(['e', 'E']) {
+            Some(pos) => (&tname[..pos], &tname[pos + 1..]),
+            None => (tname, "0")
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let Ok(numer) = format!("{}{}", int_part, frac_part).parse::<BigInt>() else {
+            return Ok(None);
+        };
+        let exponent = match exponent.parse::<i64>() {
+            Ok(exp) => exp,
+            Err(_) if exponent.trim_start_matches(['+', '-']).bytes().all(|b| b.is_ascii_digit()) => return Err(overflow()),
+            Err(_) => return Ok(None)
+        };
+
+        // 10^n has just over 3.3 n bits
+        let scale = i64::try_from(frac_part.len())
+            .ok()
+            .and_then(|len| exponent.checked_sub(len))
+            .ok_or_else(overflow)?;
+        if scale.unsigned_abs().saturating_mul(10) / 3 > MAX_RATIONAL_BITS {
+            return Err(overflow());
+        }
+        let ten = BigRational::from_integer(BigInt::from(10));
+        Ok(Some(BigRational::from_integer(numer) * ten.pow(i32::try_from(scale).map_err(|_| overflow())?)))
+    }
+
     fn make_const(&self, tname: &str) -> ParserResult {
         let value = match tname {
             keywords::PI => Some(Value::from_num(consts::PI)),
             keywords::TAU => Some(Value::from_num(consts::TAU)),
             keywords::E => Some(Value::from_num(consts::E)),
             keywords::PHI => Some(Value::from_num(1.618033988749895f64)),
+            keywords::I => Some(Value::from_complex(Complex64::i())),
             _ => None
         };
         if let Some(val) = value {
             Ok(Box::new(Literal::new(val)))
         } else {
-            Err(ParserError::new(&format!("Unknown constant - '{}'", tname)))
+            Err(ParserError::syntax(&format!("Unknown constant - '{}'", tname)))
         }
     }
 
     fn make_variable(&mut self) -> ParserResult {
         if let Some(name_token) = self.lexer.next_token() {
+            let location = name_token.location();
             if name_token.ttype == TokenType::Identifier {
-                Ok(Box::new(DefVar::new(name_token.tname, self.make_code()?)))
+                Ok(Self::locate(Box::new(DefVar::new(name_token.tname, self.make_code()?)), &location))
             } else {
-                Err(ParserError::new(&format!("Invalid variable definition name - '{}'", name_token.tname)))
+                Err(ParserError::syntax(&format!("Invalid variable definition name - '{}'", name_token.tname)).at(&location))
             }
         } else {
-            Err(ParserError::new("Incomplete variable definition"))
+            Err(ParserError::syntax("Incomplete variable definition"))
         }
     }
 
     fn make_set_variable(&mut self) -> ParserResult {
         if let Some(name_token) = self.lexer.next_token() {
+            let location = name_token.location();
             if name_token.ttype == TokenType::Identifier {
-                Ok(Box::new(SetVar::new(name_token.tname, self.make_code()?)))
+                Ok(Self::locate(Box::new(SetVar::new(name_token.tname, self.make_code()?)), &location))
             } else {
-                Err(ParserError::new(&format!("Invalid set variable name - '{}'", name_token.tname)))
+                Err(ParserError::syntax(&format!("Invalid set variable name - '{}'", name_token.tname)).at(&location))
             }
         } else {
-            Err(ParserError::new("Incomplete set variable"))
+            Err(ParserError::syntax("Incomplete set variable"))
         }
     }
 
@@ -186,7 +335,7 @@ impl Parser {
                     self.lexer.check_reserved(&ptok, "function parameter definition")?;
                     params.push(ptok.tname);
                 } else {
-                    return Err(ParserError::new("Invalid function definition/parameters"));
+                    return Err(ParserError::syntax("Invalid function definition/parameters"));
                 }
             }
             loop {
@@ -197,12 +346,13 @@ impl Parser {
                     }
                     body.push(self.make_code()?);
                 } else {
-                    return Err(ParserError::new("Invalid function definition/body"));
+                    return Err(ParserError::syntax("Invalid function definition/body"));
                 }
             }
-            Ok(Box::new(Defun::new(ftok.tname, params, body)))
+            let location = ftok.location();
+            Ok(Self::locate(Box::new(Defun::new(ftok.tname, params, body)), &location))
         } else {
-            Err(ParserError::new("Invalid function definition"))
+            Err(ParserError::syntax("Invalid function definition"))
         }
     }
 
@@ -217,12 +367,13 @@ impl Parser {
                     }
                     args.push(self.make_code()?);
                 } else {
-                    return Err(ParserError::new("Invalid function call/arguments"));
+                    return Err(ParserError::syntax("Invalid function call/arguments"));
                 }
             }
-            Ok(Box::new(Funcall::new(ftok.tname, args)))
+            let location = ftok.location();
+            Ok(Self::locate(Box::new(Funcall::new(ftok.tname, args)), &location))
         } else {
-            Err(ParserError::new("Invalid function call"))
+            Err(ParserError::syntax("Invalid function call"))
         }
     }
 
@@ -252,33 +403,124 @@ impl Parser {
                 self.lexer.next_token();
                 Ok((part, true))
             } else {
-                Err(ParserError::new(&format!("Invalid if expression - expecting '{}'", ends_with.to_string())))
+                Err(ParserError::syntax(&format!("Invalid if expression - expecting '{}'", ends_with.to_string())).at(&tok.location()))
             }
         } else {
-            Err(ParserError::new(&format!("Incomplete if expression - missing '{}'", ends_with.to_string())))
+            Err(ParserError::syntax(&format!("Incomplete if expression - missing '{}'", ends_with.to_string())))
         }
     }
 
     fn make_binary_op(&mut self, name: &str) -> ParserResult {
+        match name {
+            keywords::APPROX_EQUAL => return Ok(Box::new(ApproxEqual::new(approx_equal, self.make_code()?, self.make_code()?))),
+            keywords::NOT_APPROX_EQUAL => return Ok(Box::new(ApproxEqual::new(not_approx_equal, self.make_code()?, self.make_code()?))),
+            _ => {}
+        }
         if let Some(ftn) = bop2ftn(name) {
-            Ok(Box::new(BinaryOp::new(ftn, self.make_code()?, self.make_code()?)))
+            if is_logical(name) {
+                return Ok(Box::new(LogicalOp::new(name, ftn, self.make_code()?, self.make_code()?)));
+            }
+            Ok(Box::new(BinaryOp::new(name, ftn, self.make_code()?, self.make_code()?)))
         } else {
-            Err(ParserError::new(&format!("Unknown binary op - {}", name)))
+            Err(ParserError::syntax(&format!("Unknown binary op - {}", name)))
         }
     }
 
     fn make_unary_op(&mut self, name: &str) -> ParserResult {
+        if name == keywords::NOT {
+            return Ok(Box::new(LogicalNot::new(self.make_code()?)));
+        }
         if let Some(ftn) = uop2ftn(name) {
-            Ok(Box::new(UnaryOp::new(ftn, self.make_code()?)))
+            Ok(Box::new(UnaryOp::new(name, ftn, self.make_code()?)))
         } else {
-            Err(ParserError::new(&format!("Unknown unary op - {}", name)))
+            Err(ParserError::syntax(&format!("Unknown unary op - {}", name)))
+        }
+    }
+
+    fn make_ternary_op(&mut self, name: &str) -> ParserResult {
+        if let Some(ftn) = top2ftn(name) {
+            Ok(Box::new(TernaryOp::new(name, ftn, self.make_code()?, self.make_code()?, self.make_code()?)))
+        } else {
+            Err(ParserError::syntax(&format!("Unknown ternary op - {}", name)))
+        }
+    }
+
+    fn make_list(&mut self) -> ParserResult {
+        let mut items = Expressions::new();
+        loop {
+            if let Some(tok) = self.lexer.peek_token() {
+                if tok.ttype == TokenType::ListEnd {
+                    self.lexer.next_token();
+                    break;
+                }
+                items.push(self.make_code()?);
+            } else {
+                return Err(ParserError::syntax("Incomplete list - missing ']'"));
+            }
+        }
+        Ok(Box::new(ListExpr::new(items)))
+    }
+
+    // Field name and value pairs, e.g. {mean 5 sd 1.5}
+    fn make_record(&mut self) -> ParserResult {
+        let mut fields = Fields::new();
+        loop {
+            match self.lexer.peek_token() {
+                Some(tok) if tok.ttype == TokenType::RecordEnd => {
+                    self.lexer.next_token();
+                    break;
+                }
+                Some(tok) => {
+                    let location = tok.location();
+                    let name = self.make_field_name()?;
+                    if fields.iter().any(|(field, _)| *field == name) {
+                        return Err(ParserError::syntax(&format!("Duplicate record field '{}'", name)).at(&location));
+                    }
+                    fields.push((name, self.make_code()?));
+                }
+                None => return Err(ParserError::syntax("Incomplete record - missing '}'"))
+            }
+        }
+        Ok(Box::new(RecordExpr::new(fields)))
+    }
+
+    // Field names follow identifier rules, and may reuse keyword names, e.g. {min 1 max 5}
+    fn make_field_name(&mut self) -> Result<String, ParserError> {
+        match self.lexer.next_token() {
+            Some(ftok) if Lexer::is_valid_identifier(&ftok.tname) => Ok(ftok.tname),
+            Some(ftok) => Err(ParserError::syntax(&format!("Invalid record field name - '{}'", ftok.tname)).at(&ftok.location())),
+            None => Err(ParserError::syntax("Missing record field name"))
+        }
+    }
+
+    fn make_list_ftn_name(&mut self, name: &str) -> Result<String, ParserError> {
+        match self.lexer.next_token() {
+            Some(ftok) if ftok.ttype == TokenType::Identifier => Ok(ftok.tname),
+            Some(ftok) => Err(ParserError::syntax(&format!("Invalid {} function name - '{}'", name, ftok.tname)).at(&ftok.location())),
+            None => Err(ParserError::syntax(&format!("Incomplete {} expression", name)))
         }
     }
 
     fn make_special_ftn(&mut self, name: &str) -> ParserResult {
         match name {
             keywords::XPRINT => Ok(Box::new(XPrint::new(self.make_code()?))),
-            _ => Err(ParserError::new(&format!("Unknown special ftn - {}", name)))
+            keywords::NOW => Ok(Box::new(Now::new())),
+            keywords::GET => {
+                let rec = self.make_code()?;
+                Ok(Box::new(RecordGet::new(rec, self.make_field_name()?)))
+            }
+            keywords::WITH => {
+                let rec = self.make_code()?;
+                let field = self.make_field_name()?;
+                Ok(Box::new(RecordWith::new(rec, field, self.make_code()?)))
+            }
+            keywords::MAP => Ok(Box::new(ListMap::new(self.make_list_ftn_name(name)?, self.make_code()?))),
+            keywords::FILTER => Ok(Box::new(ListFilter::new(self.make_list_ftn_name(name)?, self.make_code()?))),
+            keywords::FOLD => {
+                let fname = self.make_list_ftn_name(name)?;
+                Ok(Box::new(ListFold::new(fname, self.make_code()?, self.make_code()?)))
+            }
+            _ => Err(ParserError::syntax(&format!("Unknown special ftn - {}", name)))
         }
     }
 }
@@ -296,6 +538,8 @@ impl Default for Parser {
 mod tests {
     use super::*;
     use crate::pcalc_environment::Environment;
+    use crate::pcalc_error::Span;
+    use crate::pcalc_record::Record;
 
     #[test]
     fn test_parser_literal() {
@@ -305,6 +549,257 @@ mod tests {
         test_parse(&mut parser, &mut env, "false", Value::from_bool(false));
         test_parse(&mut parser, &mut env, "5.0", Value::from_num(5.0));
         test_parse(&mut parser, &mut env, "-5.0", Value::from_num(-5.0));
+        test_parse(&mut parser, &mut env, "5", Value::from_int(5));
+        test_parse(&mut parser, &mut env, "-9223372036854775808", Value::from_int(i64::MIN));
+
+        assert!(parser.parse("5").unwrap().eval(&mut env).unwrap().is_int());
+        assert!(!parser.parse("5.0").unwrap().eval(&mut env).unwrap().is_int());
+        assert!(!parser.parse("9223372036854775808").unwrap().eval(&mut env).unwrap().is_int());
+    }
+
+    #[test]
+    fn test_parser_radix_literal() {
+        use crate::pcalc_word::{with_context, Overflow, WordContext, WordSize};
+
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        test_parse(&mut parser, &mut env, "0xFF", Value::from_int(255));
+        test_parse(&mut parser, &mut env, "0b1010", Value::from_int(10));
+        test_parse(&mut parser, &mut env, "0o17", Value::from_int(15));
+        test_parse(&mut parser, &mut env, "0xFFFFFFFFFFFFFFFF", Value::from_int(-1));
+        test_parse(&mut parser, &mut env, "band 0xF0 0b11000000", Value::from_int(0xC0));
+        test_parse_error(&mut parser, "0x1FFFFFFFFFFFFFFFF", "Integer literal too large for i64 - '0x1FFFFFFFFFFFFFFFF'");
+
+        with_context(WordContext::new(Some(WordSize::I8), Overflow::Check), || {
+            test_parse(&mut parser, &mut env, "0xFF", Value::from_int(-1));
+            test_parse_error(&mut parser, "0x100", "Integer literal too large for i8 - '0x100'");
+
+            // Decimal integers are checked against the word too
+            test_parse(&mut parser, &mut env, "-128", Value::from_int(-128));
+            test_parse_error(&mut parser, "128", "Integer literal too large for i8 - '128'");
+            test_parse_error(&mut parser, "-129", "Integer literal too small for i8 - '-129'");
+            test_parse(&mut parser, &mut env, "1.5", Value::from_num(1.5));
+        });
+
+        with_context(WordContext::new(Some(WordSize::U8), Overflow::Wrap), || {
+            test_parse_error(&mut parser, "300", "Integer literal too large for u8 - '300'");
+            test_parse_error(&mut parser, "-1", "Integer literal too small for u8 - '-1'");
+        });
+
+        with_context(WordContext::new(Some(WordSize::U64), Overflow::Check), || {
+            test_parse(&mut parser, &mut env, "18446744073709551615", Value::from_int(-1));
+            test_parse_error(
+                &mut parser,
+                "18446744073709551616",
+                "Integer literal too large for u64 - '18446744073709551616'"
+            );
+            test_parse_error(
+                &mut parser,
+                "99999999999999999999999999999999999999999",
+                "Integer literal too large for u64 - '99999999999999999999999999999999999999999'"
+            );
+        });
+    }
+
+    #[test]
+    fn test_parser_rational_literal() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let rat = |n: i64, d: i64| Value::from_rational(BigRational::new(n.into(), d.into()));
+
+        assert!(!parser.is_rational());
+        parser.set_rational(true);
+        assert!(parser.is_rational());
+
+        test_parse(&mut parser, &mut env, "5", rat(5, 1));
+        test_parse(&mut parser, &mut env, "0.1", rat(1, 10));
+        test_parse(&mut parser, &mut env, "-2.50", rat(-5, 2));
+        test_parse(&mut parser, &mut env, ".5", rat(1, 2));
+        test_parse(&mut parser, &mut env, "1.5e2", rat(150, 1));
+        test_parse(&mut parser, &mut env, "25E-3", rat(1, 40));
+        test_parse(&mut parser, &mut env, "true", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "pi", Value::from_num(consts::PI));
+        assert!(parser.parse("0.1").unwrap().eval(&mut env).unwrap().is_rational());
+
+        test_parse(&mut parser, &mut env, "== + 0.1 0.2 0.3", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "/ 1 3", rat(1, 3));
+        test_parse(&mut parser, &mut env, "^ / 2 3 3", rat(8, 27));
+        assert!(!parser.parse("sqrt 2").unwrap().eval(&mut env).unwrap().is_rational());
+
+        for expr in ["1.5e-2147483648", "1e2000000000", "1e99999999999999999999"] {
+            let err = parser.parse(expr).unwrap_err();
+            assert_eq!(err.message(), format!("Rational literal out of range - '{}'", expr));
+        }
+        test_parse(&mut parser, &mut env, "inf", Value::from_num(f64::INFINITY));
+
+        parser.set_rational(false);
+        test_parse(&mut parser, &mut env, "== + 0.1 0.2 0.3", Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_parser_decimal_literal() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let dec = |text: &str| Value::from_decimal(Decimal::parse(text).unwrap());
+
+        test_parse(&mut parser, &mut env, "19.99d", dec("19.99"));
+        test_parse(&mut parser, &mut env, "-5d", dec("-5"));
+        test_parse(&mut parser, &mut env, "== + 0.1d 0.2d 0.3d", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "0.1", Value::from_num(0.1));
+
+        assert!(!parser.is_decimal());
+        parser.set_decimal(true);
+        assert!(parser.is_decimal());
+
+        test_parse(&mut parser, &mut env, "0.1", dec("0.1"));
+        test_parse(&mut parser, &mut env, "5", dec("5"));
+        test_parse(&mut parser, &mut env, "1.5e2", dec("150"));
+        test_parse(&mut parser, &mut env, "== + 0.1 0.2 0.3", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "* 19.99 3", dec("59.97"));
+        assert!(parser.parse("+ 1 pi").unwrap().eval(&mut env).is_err());
+
+        // Decimal and rational modes are exclusive
+        parser.set_rational(true);
+        assert!(!parser.is_decimal());
+        parser.set_decimal(true);
+        assert!(!parser.is_rational());
+
+        parser.set_decimal(false);
+        test_parse(&mut parser, &mut env, "0.1", Value::from_num(0.1));
+    }
+
+    #[test]
+    fn test_parser_string_literal() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let str_value = |s: &str| Value::from_string(String::from(s));
+
+        test_parse(&mut parser, &mut env, "\"hello\"", str_value("hello"));
+        test_parse(&mut parser, &mut env, "\"hello world\"", str_value("hello world"));
+        test_parse(&mut parser, &mut env, "\"\"", str_value(""));
+        test_parse(&mut parser, &mut env, "\"5\"", str_value("5"));
+        test_parse(&mut parser, &mut env, "\"say \\\"hi\\\"\"", str_value("say \"hi\""));
+        test_parse(&mut parser, &mut env, "\"a\\nb\\tc\\\\\"", str_value("a\nb\tc\\"));
+        test_parse(&mut parser, &mut env, "concat \"foo \" \"bar\"", str_value("foo bar"));
+        test_parse(&mut parser, &mut env, "var s \"x y\"", str_value("x y"));
+        test_parse(&mut parser, &mut env, "upper s", str_value("X Y"));
+
+        test_parse_error(&mut parser, "len \"abc", "Unterminated string - '\"abc'");
+        test_parse_error(&mut parser, "\"a\\qb\"", "Invalid escape - '\\q'");
+
+        let err = parser.parse("len \"\\d\"").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+        assert_eq!(err.location(), Some(&Location::new("\"\\d\"", Span::new(1, 5, 4))));
+    }
+
+    #[test]
+    fn test_parser_list() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let ints = |v: Vec<i64>| Value::from_list(v.into_iter().map(Value::from_int).collect());
+
+        test_parse(&mut parser, &mut env, "[]", ints(vec![]));
+        test_parse(&mut parser, &mut env, "[1 2 3]", ints(vec![1, 2, 3]));
+        test_parse(&mut parser, &mut env, "[ + 1 2 * 3 4 ]", ints(vec![3, 12]));
+        test_parse(
+            &mut parser,
+            &mut env,
+            "var xs [1 [2] \"a\"]",
+            Value::from_list(vec![Value::from_int(1), ints(vec![2]), Value::from_string(String::from("a"))])
+        );
+        test_parse(&mut parser, &mut env, "len xs", Value::from_int(3));
+        test_parse(&mut parser, &mut env, "nth nth xs 1 0", Value::from_int(2));
+        test_parse(&mut parser, &mut env, "sum range 1 5", Value::from_int(10));
+        test_parse(&mut parser, &mut env, "prod push [2 3] 4", Value::from_int(24));
+
+        test_parse(&mut parser, &mut env, "def sq x begin * x x end", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "def odd x begin == % x 2 1 end", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "def add x y begin + x y end", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "map sq [1 2 3]", ints(vec![1, 4, 9]));
+        test_parse(&mut parser, &mut env, "filter odd range 0 6", ints(vec![1, 3, 5]));
+        test_parse(&mut parser, &mut env, "fold add 0 map sq [1 2 3]", Value::from_int(14));
+
+        test_parse_error(&mut parser, "[1 2", "Incomplete list - missing ']'");
+        test_parse_error(&mut parser, "]", "Invalid expression containing ]");
+        test_parse_error(&mut parser, "map 5 [1]", "Invalid map function name - '5'");
+        test_parse_error(&mut parser, "fold", "Incomplete fold expression");
+    }
+
+    #[test]
+    fn test_parser_record() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+
+        test_parse(&mut parser, &mut env, "{}", Value::from_record(Record::new()));
+        test_parse(
+            &mut parser,
+            &mut env,
+            "var r {mean / 6 2 tags [1] min {x 1}}",
+            Value::from_record(
+                Record::new()
+                    .with("mean", Value::from_int(3))
+                    .with("tags", Value::from_list(vec![Value::from_int(1)]))
+                    .with("min", Value::from_record(Record::new().with("x", Value::from_int(1))))
+            )
+        );
+        test_parse(&mut parser, &mut env, "get r mean", Value::from_int(3));
+        test_parse(&mut parser, &mut env, "get get r min x", Value::from_int(1));
+        test_parse(&mut parser, &mut env, "get with r mean 4 mean", Value::from_int(4));
+        test_parse(&mut parser, &mut env, "get with r sd + 1 1 sd", Value::from_int(2));
+        test_parse(&mut parser, &mut env, "get r mean", Value::from_int(3));
+
+        test_parse(
+            &mut parser,
+            &mut env,
+            "def stats xs begin var m / sum xs len xs {mean m n len xs} end",
+            Value::from_bool(true)
+        );
+        test_parse(&mut parser, &mut env, "get call stats [1 2 3] cend mean", Value::from_int(2));
+        test_parse(&mut parser, &mut env, "== {a 1 b 2} {b 2 a 1}", Value::from_bool(true));
+
+        test_parse_error(&mut parser, "{a 1", "Incomplete record - missing '}'");
+        test_parse_error(&mut parser, "}", "Invalid expression containing }");
+        test_parse_error(&mut parser, "{1 2}", "Invalid record field name - '1'");
+        test_parse_error(&mut parser, "{a 1 a 2}", "Duplicate record field 'a'");
+        test_parse_error(&mut parser, "get r", "Missing record field name");
+        assert!(parser.parse("get r sd").unwrap().eval(&mut env).is_err());
+        assert!(parser.parse("get 5 x").unwrap().eval(&mut env).is_err());
+    }
+
+    #[test]
+    fn test_parser_quantity_literal() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let parse_str = |parser: &mut Parser, env: &mut Environment, expr: &str| parser.parse(expr).unwrap().eval(env).unwrap().to_string();
+
+        assert_eq!(parse_str(&mut parser, &mut env, "3_m"), "3 m");
+        assert_eq!(parse_str(&mut parser, &mut env, "_km"), "1 km");
+        assert_eq!(parse_str(&mut parser, &mut env, "9.81_m/s^2"), "9.81 m/s^2");
+        assert_eq!(parse_str(&mut parser, &mut env, "/ * 3 _m * 2 _s"), "1.5 m/s");
+        assert_eq!(parse_str(&mut parser, &mut env, "to 100_degC _degF"), "212 degF");
+        assert_eq!(parse_str(&mut parser, &mut env, "/ 3_m 1_m"), "3");
+
+        test_parse_error(&mut parser, "5_parsec", "Unknown unit - 'parsec'");
+        test_parse_error(&mut parser, "5_m//s", "Invalid unit expression - 'm//s'");
+    }
+
+    #[test]
+    fn test_parser_datetime_literal() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        let parse_str = |parser: &mut Parser, env: &mut Environment, expr: &str| parser.parse(expr).unwrap().eval(env).unwrap().to_string();
+
+        assert_eq!(parse_str(&mut parser, &mut env, "2024-03-15"), "2024-03-15");
+        assert_eq!(parse_str(&mut parser, &mut env, "2024-03-15T10:30Z"), "2024-03-15T10:30:00");
+        assert_eq!(parse_str(&mut parser, &mut env, "P3DT4H12M"), "3d 4h 12m");
+        assert_eq!(parse_str(&mut parser, &mut env, "+ 2024-03-15 P1W"), "2024-03-22");
+
+        env.pin_now(Some(DateTime::parse("2024-03-15T10:30").unwrap()));
+        assert_eq!(parse_str(&mut parser, &mut env, "now"), "2024-03-15T10:30:00");
+        assert_eq!(parse_str(&mut parser, &mut env, "- now 2024-03-14"), "1d 10h 30m");
+
+        test_parse_error(&mut parser, "2024-02-30", "Invalid date - '2024-02-30'");
+        test_parse_error(&mut parser, "P1M", "Invalid duration - 'P1M'");
     }
 
     #[test]
@@ -314,7 +809,8 @@ mod tests {
         test_parse(&mut parser, &mut env, "pi", Value::from_num(consts::PI));
         test_parse(&mut parser, &mut env, "tau", Value::from_num(consts::TAU));
         test_parse(&mut parser, &mut env, "e", Value::from_num(consts::E));
-        test_parse(&mut parser, &mut env, "phi", Value::from_num(1.6180339887498949f64));
+        test_parse(&mut parser, &mut env, "phi", Value::from_num(1.618_033_988_749_895_f64));
+        test_parse(&mut parser, &mut env, "i", Value::from_complex(Complex64::i()));
     }
 
     #[test]
@@ -433,6 +929,21 @@ mod tests {
         test_parse_error(&mut parser, "sqrt", "Expecting token");
     }
 
+    #[test]
+    fn test_parser_ternary_op() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        test_parse(&mut parser, &mut env, "clamp 12 0 10", Value::from_int(10));
+        test_parse(&mut parser, &mut env, "lerp 10 20 0.25", Value::from_num(12.5));
+        test_parse(&mut parser, &mut env, "fma 2 3 + 2 2", Value::from_int(10));
+        test_parse(&mut parser, &mut env, "between 5 0 10", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "select < 1 2 10 20", Value::from_int(10));
+        test_parse(&mut parser, &mut env, "approx 100 100.5 0.01", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "modpow 4 13 497", Value::from_int(445));
+        test_parse(&mut parser, &mut env, "modpow + 1 3 - 15 2 * 7 71", Value::from_int(445));
+        test_parse_error(&mut parser, "modpow 4 13", "Expecting token");
+    }
+
     #[test]
     fn test_parser_special_ftn_xprint() {
         let mut env = Environment::new();
@@ -512,21 +1023,100 @@ mod tests {
         test_parse(&mut parser, &mut env, "var y 10", Value::from_num(10.0));
 
         test_parse(&mut parser, &mut env, "if true ? 1 fi", Value::from_num(1.0));
-        test_parse(&mut parser, &mut env, "if false ? 1 fi", Value::from_bool(false));
+        test_parse(&mut parser, &mut env, "if false ? 1 fi", Value::from_none());
 
         test_parse(&mut parser, &mut env, "if <= x 5 ? x fi", Value::from_num(5.0));
-        test_parse(&mut parser, &mut env, "if > x 5 ? x fi", Value::from_bool(false));
+        test_parse(&mut parser, &mut env, "if > x 5 ? x fi", Value::from_none());
 
         test_parse(&mut parser, &mut env, "if <= x 5 ? = x + x 1 fi", Value::from_num(6.0));
         test_parse(&mut parser, &mut env, "x", Value::from_num(6.0));
 
-        test_parse(&mut parser, &mut env, "if < y 10 ? = y + y 1 fi", Value::from_bool(false));
+        test_parse(&mut parser, &mut env, "if < y 10 ? = y + y 1 fi", Value::from_none());
         test_parse(&mut parser, &mut env, "y", Value::from_num(10.0));
 
+        test_parse(&mut parser, &mut env, "none", Value::from_none());
+        test_parse(&mut parser, &mut env, "isnone if > x 10 ? x fi", Value::from_bool(true));
+        test_parse(&mut parser, &mut env, "default if > x 10 ? x fi 0", Value::from_int(0));
+        test_parse(&mut parser, &mut env, "default if < x 10 ? x fi 0", Value::from_num(6.0));
+
         test_parse_error(&mut parser, "if true fi", "Invalid if expression - expecting 'Then'");
         test_parse_error(&mut parser, "if true ? 1", "Incomplete if expression - missing 'Else'");
     }
 
+    #[test]
+    fn test_parser_error_location() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+
+        let err = parser.parse("+ 1 foo$").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+        assert_eq!(err.location(), Some(&Location::new("foo$", Span::new(1, 5, 4))));
+
+        let err = parser.parse("+ 1 2 3").unwrap_err();
+        assert_eq!(err.location().unwrap().token, "3");
+        assert!(err.suggestions().is_empty());
+
+        let err = parser.parse("sqr 4").unwrap_err();
+        assert_eq!(err.location().unwrap().token, "sqr");
+        assert_eq!(err.did_you_mean().unwrap(), "did you mean 'sqrt', 'shr' or 'str'?");
+
+        let err = parser.parse("[1 2").unwrap_err();
+        assert_eq!(err.message(), "Incomplete list - missing ']'");
+        assert_eq!(err.location().unwrap().token, "[");
+
+        let err = parser.parse("2024-02-30").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+
+        let code = parser.parse("* 2 + 1 y").unwrap();
+        let err = code.eval(&mut env).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownVariable);
+        assert_eq!(err.location(), Some(&Location::new("y", Span::new(1, 9, 1))));
+        assert_eq!(err.caret("* 2 + 1 y").unwrap(), "* 2 + 1 y\n        ^");
+
+        let code = parser.parse("+ 1 true").unwrap();
+        let err = code.eval(&mut env).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+        assert_eq!(err.location().unwrap().token, "+");
+    }
+
+    #[test]
+    fn test_parser_error_trace() {
+        let mut env = Environment::new();
+        let mut parser = Parser::new();
+        assert!(!parser.parse("def half x").unwrap().is_evaluable());
+        test_parse(&mut parser, &mut env, "begin / x two end", Value::from_bool(true));
+        test_parse(
+            &mut parser,
+            &mut env,
+            "def quarter x begin call half call half x cend cend end",
+            Value::from_bool(true)
+        );
+
+        let code = parser.parse("+ 1 call quarter 8 cend").unwrap();
+        let err = code.eval(&mut env).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownVariable);
+        assert_eq!(err.location(), Some(&Location::new("quarter", Span::new(1, 10, 7))));
+        assert_eq!(err.trace().len(), 2);
+        assert_eq!(format!("{}", err.trace()[0]), "in function 'half' at 'two', line 2, column 11");
+        assert_eq!(format!("{}", err.trace()[1]), "in function 'quarter' at 'half', line 1, column 36");
+
+        let code = parser.parse("call half 1 2 cend").unwrap();
+        let err = code.eval(&mut env).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArityMismatch);
+        assert!(err.trace().is_empty());
+
+        let err = parser.parse("def loop x begin call loop x cend end").unwrap().eval(&mut env).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Recursion);
+        assert_eq!(err.location().unwrap().token, "loop");
+
+        let err = parser
+            .parse("var x 1")
+            .unwrap()
+            .eval(&mut env)
+            .and_then(|_| parser.parse("var x 2").unwrap().eval(&mut env));
+        assert_eq!(err.unwrap_err().kind(), ErrorKind::DuplicateDefinition);
+    }
+
     fn test_parse(parser: &mut Parser, env: &mut Environment, expr: &str, value: Value) {
         let code = parser.parse(expr).unwrap();
         assert_eq!(code.eval(env).unwrap(), value);
@@ -534,7 +1124,7 @@ mod tests {
 
     fn test_parse_error(parser: &mut Parser, expr: &str, error: &str) {
         match parser.parse(expr) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(err) => assert_eq!(format!("{}", err), error)
         };
     }
@@ -542,7 +1132,7 @@ mod tests {
     fn test_parse_eval_error(parser: &mut Parser, env: &mut Environment, expr: &str, error: &str) {
         let code = parser.parse(expr).unwrap();
         match code.eval(env) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(err) => assert_eq!(format!("{}", err), error)
         };
     }
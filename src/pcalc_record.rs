@@ -0,0 +1,101 @@
+use crate::pcalc_error::ErrorKind;
+use crate::pcalc_value::{Value, ValueError, ValueResult};
+use std::cmp;
+
+// --------------------------------------------------------------------------------
+// Record - Values keyed by field name, kept in definition order
+
+#[derive(Debug, Clone, Default)]
+pub struct Record {
+    fields: Vec<(String, Value)>
+}
+
+impl Record {
+    pub fn new() -> Self {
+        Record { fields: Vec::new() }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn fields(&self) -> &[(String, Value)] {
+        &self.fields
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields.iter().any(|(field, _)| field == name)
+    }
+
+    pub fn get(&self, name: &str) -> ValueResult {
+        match self.fields.iter().find(|(field, _)| field == name) {
+            Some((_, value)) => Ok(value.clone()),
+            None => Err(ValueError::new(ErrorKind::TypeMismatch, &format!("Unknown record field '{}'", name)))
+        }
+    }
+
+    // Copy with the field set to value, adding the field when missing
+    pub fn with(&self, name: &str, value: Value) -> Self {
+        let mut fields = self.fields.clone();
+        match fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, old)) => *old = value,
+            None => fields.push((String::from(name), value))
+        }
+        Record { fields }
+    }
+}
+
+// Records are equal when they have the same fields and values, in any order
+impl cmp::PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.fields.iter().all(|(name, value)| other.get(name).is_ok_and(|v| v == *value))
+    }
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_fields() {
+        let rec = Record::new().with("mean", Value::from_int(5)).with("sd", Value::from_num(1.5));
+        assert_eq!(rec.len(), 2);
+        assert!(!rec.is_empty());
+        assert!(rec.contains("sd"));
+        assert!(!rec.contains("var"));
+        assert_eq!(rec.get("mean").unwrap(), Value::from_int(5));
+        assert_eq!(format!("{}", rec.get("var").unwrap_err()), "Unknown record field 'var'");
+        assert_eq!(rec.fields()[1].0, "sd");
+        assert!(Record::new().is_empty());
+    }
+
+    #[test]
+    fn test_record_with() {
+        let rec = Record::new().with("x", Value::from_int(1)).with("y", Value::from_int(2));
+        let updated = rec.with("x", Value::from_int(10));
+        assert_eq!(updated.get("x").unwrap(), Value::from_int(10));
+        assert_eq!(rec.get("x").unwrap(), Value::from_int(1));
+        assert_eq!(updated.fields()[0].0, "x");
+        assert_eq!(rec.with("z", Value::from_int(3)).len(), 3);
+    }
+
+    #[test]
+    fn test_record_equal() {
+        let xy = Record::new().with("x", Value::from_int(1)).with("y", Value::from_int(2));
+        let yx = Record::new().with("y", Value::from_num(2.0)).with("x", Value::from_int(1));
+        assert!(xy == yx);
+        assert!(xy != xy.with("y", Value::from_int(3)));
+        assert!(xy != xy.with("z", Value::from_int(3)));
+        assert!(Record::new() == Record::new());
+    }
+}
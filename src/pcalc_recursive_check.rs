@@ -1,46 +1,24 @@
 use crate::pcalc_environment::Environment;
+use crate::pcalc_error::{Error, ErrorKind};
 use crate::pcalc_function::FunctionPtr;
-use crate::pcalc_value::ValueError;
 use std::collections::HashSet;
-use std::fmt;
 
 // --------------------------------------------------------------------------------
 // Check Error
 
-#[derive(Debug, Clone)]
-pub struct CheckError {
-    error_msg: String
-}
+pub type CheckError = Error;
 
 impl CheckError {
     pub fn self_recursive(name: &str) -> Self {
-        CheckError {
-            error_msg: format!("Self recursive function '{}'", name)
-        }
+        CheckError::from_string(ErrorKind::Recursion, format!("Self recursive function '{}'", name))
     }
 
     pub fn dual_recursive(name1: &str, name2: &str) -> Self {
-        CheckError {
-            error_msg: format!("Dual recursive functions '{}' and '{}'", name1, name2)
-        }
+        CheckError::from_string(ErrorKind::Recursion, format!("Dual recursive functions '{}' and '{}'", name1, name2))
     }
 
     pub fn cross_recursive(name1: &str, name2: &str) -> Self {
-        CheckError {
-            error_msg: format!("Cross recursive functions '{}' and '{}'", name1, name2)
-        }
-    }
-}
-
-impl From<CheckError> for ValueError {
-    fn from(item: CheckError) -> Self {
-        ValueError::new(&item.error_msg)
-    }
-}
-
-impl fmt::Display for CheckError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.error_msg)
+        CheckError::from_string(ErrorKind::Recursion, format!("Cross recursive functions '{}' and '{}'", name1, name2))
     }
 }
 
@@ -125,6 +103,7 @@ pub fn check_cross_recursive(name: &str, func: &FunctionPtr, env: &Environment)
 // --------------------------------------------------------------------------------
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
     use crate::pcalc_code::Funcall;
@@ -134,7 +113,7 @@ mod tests {
     fn test_check_self_recursive() {
         let fptr = make_func("foobar");
         match check_self_recursive("foobar", &fptr) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(e) => assert_eq!(format!("{}", e), "Self recursive function 'foobar'")
         };
     }
@@ -153,7 +132,7 @@ mod tests {
 
         let foo = make_func("bar");
         match check_dual_recursive("foo", &foo, &env) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(e) => assert_eq!(format!("{}", e), "Dual recursive functions 'foo' and 'bar'")
         };
     }
@@ -176,7 +155,7 @@ mod tests {
 
         let foo = make_func("bar");
         match check_cross_recursive("foo", &foo, &env) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(e) => assert_eq!(format!("{}", e), "Cross recursive functions 'foo' and 'bar'")
         };
     }
@@ -190,7 +169,7 @@ mod tests {
 
         let foo = make_func("bar");
         match check_cross_recursive("foo", &foo, &env) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(e) => assert_eq!(format!("{}", e), "Cross recursive functions 'foo' and 'tar'")
         };
     }
@@ -205,7 +184,7 @@ mod tests {
 
         let foo = make_func2("zar", "bar");
         match check_cross_recursive("foo", &foo, &env) {
-            Ok(_) => assert!(false),
+            Ok(_) => unreachable!(),
             Err(e) => assert_eq!(format!("{}", e), "Cross recursive functions 'foo' and 'tar'")
         };
     }
@@ -1,7 +1,13 @@
-use crate::pcalc_environment::Environment;
+use crate::pcalc_angle::Angle;
+use crate::pcalc_datetime::DateTime;
+use crate::pcalc_decimal::{DecimalContext, Rounding};
+use crate::pcalc_environment::{self, Environment};
+use crate::pcalc_error::Error;
+use crate::pcalc_format::RationalOutput;
 use crate::pcalc_help as help;
 use crate::pcalc_parser::Parser;
 use crate::pcalc_value::Value;
+use crate::pcalc_word::{Overflow, WordContext, WordSize};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::{self, BufReader, Write};
@@ -13,6 +19,15 @@ const CMD_BATCH: &str = ":batch";
 const CMD_LAST: &str = ":last";
 const CMD_HELP: &str = ":help";
 const CMD_EXAMPLES: &str = ":examples";
+const CMD_RATIONAL: &str = ":rational";
+const CMD_FRACTION: &str = ":fraction";
+const CMD_DECIMAL: &str = ":decimal";
+const CMD_WORD: &str = ":word";
+const CMD_FORMAT: &str = ":format";
+const CMD_STRICT: &str = ":strict";
+const CMD_TRUTHY: &str = ":truthy";
+const CMD_TOLERANCE: &str = ":tolerance";
+const CMD_ANGLE: &str = ":angle";
 
 pub struct REPL {
     prompt: String,
@@ -20,6 +35,7 @@ pub struct REPL {
     last_var: String,
     env: Environment,
     parser: Parser,
+    pending: String,
     batch: bool
 }
 
@@ -31,12 +47,147 @@ impl REPL {
             last_var: String::from("last"),
             env: Default::default(),
             parser: Default::default(),
+            pending: String::new(),
             batch
         };
         repl.reset_env();
         repl
     }
 
+    #[inline(always)]
+    pub fn set_rational(&mut self, flag: bool) {
+        self.parser.set_rational(flag);
+    }
+
+    #[inline(always)]
+    pub fn set_decimal(&mut self, flag: bool) {
+        self.parser.set_decimal(flag);
+    }
+
+    #[inline(always)]
+    pub fn set_strict(&mut self, flag: bool) {
+        self.env.set_strict(flag);
+    }
+
+    #[inline(always)]
+    pub fn set_truthy(&mut self, flag: bool) {
+        self.env.set_truthy(flag);
+    }
+
+    // Set angle mode for trig ops: rad, deg or grad
+    pub fn set_angle(&mut self, name: &str) -> bool {
+        match Angle::parse(name) {
+            Some(angle) => {
+                self.env.set_angle(angle);
+                true
+            }
+            None => {
+                eprintln!("AngleError: Invalid angle mode - '{}'", name);
+                false
+            }
+        }
+    }
+
+    // Set decimal scale (a number of fractional digits, or off) or rounding mode
+    pub fn set_decimal_option(&mut self, name: &str, value: &str) -> bool {
+        let ctx = self.env.decimal();
+        let new_ctx = match name {
+            "scale" if value == "off" => DecimalContext::new(None, ctx.rounding()),
+            "scale" => match value.parse::<u32>() {
+                Ok(scale) => DecimalContext::new(Some(scale), ctx.rounding()),
+                Err(_) => {
+                    eprintln!("DecimalError: Invalid decimal scale - '{}'", value);
+                    return false;
+                }
+            },
+            "rounding" => match Rounding::parse(value) {
+                Some(rounding) => DecimalContext::new(ctx.scale(), rounding),
+                None => {
+                    eprintln!("DecimalError: Invalid rounding mode - '{}'", value);
+                    return false;
+                }
+            },
+            _ => {
+                eprintln!("DecimalError: Unknown decimal option - '{}'", name);
+                return false;
+            }
+        };
+        match new_ctx {
+            Ok(new_ctx) => {
+                self.env.set_decimal(new_ctx);
+                true
+            }
+            Err(err) => {
+                eprintln!("DecimalError: {}", err);
+                false
+            }
+        }
+    }
+
+    // Set integer word size (programmer mode, or off) or overflow mode
+    pub fn set_word_option(&mut self, name: &str, value: &str) -> bool {
+        let ctx = self.env.word();
+        let new_ctx = match name {
+            "size" if value == "off" => WordContext::new(None, ctx.overflow()),
+            "size" => match WordSize::parse(value) {
+                Some(size) => WordContext::new(Some(size), ctx.overflow()),
+                None => {
+                    eprintln!("WordError: Invalid word size - '{}'", value);
+                    return false;
+                }
+            },
+            "overflow" => match Overflow::parse(value) {
+                Some(overflow) => WordContext::new(ctx.size(), overflow),
+                None => {
+                    eprintln!("WordError: Invalid overflow mode - '{}'", value);
+                    return false;
+                }
+            },
+            _ => {
+                eprintln!("WordError: Unknown word option - '{}'", name);
+                return false;
+            }
+        };
+        self.env.set_word(new_ctx);
+        true
+    }
+
+    // Apply output format settings, e.g. "fix 2 sep"
+    pub fn set_format(&mut self, settings: &str) -> bool {
+        match self.env.format_mut().apply(settings) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!("FormatError: {}", err);
+                false
+            }
+        }
+    }
+
+    // Apply approximate equality settings, e.g. "rel 1e-6 abs 0" or "ulp 4"
+    pub fn set_tolerance(&mut self, settings: &str) -> bool {
+        match self.env.tolerance_mut().apply(settings) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!("ToleranceError: {}", err);
+                false
+            }
+        }
+    }
+
+    // Pin now to a fixed date and time, e.g. for repeatable results
+    pub fn pin_now(&mut self, datetime: &str) -> bool {
+        match DateTime::parse(datetime) {
+            Ok(dt) => {
+                self.env.pin_now(Some(dt));
+                true
+            }
+            Err(err) => {
+                eprintln!("ParseError: {}", err);
+                false
+            }
+        }
+    }
+
     #[inline(always)]
     pub fn eval_expr(&mut self, expr: &str) {
         self.eval_and_print_line(expr);
@@ -114,7 +265,7 @@ impl REPL {
     }
 
     fn eval_and_print_line(&mut self, line: &str) -> bool {
-        for sub_expr in line.split(';').map(|e| e.trim()) {
+        for sub_expr in Self::split_exprs(line).into_iter().map(|e| e.trim()) {
             if sub_expr.is_empty() {
                 continue;
             }
@@ -125,6 +276,28 @@ impl REPL {
         true
     }
 
+    // Split on ';', ignoring separators inside string literals.
+    fn split_exprs(line: &str) -> Vec<&str> {
+        let mut exprs = Vec::new();
+        let mut start = 0;
+        let mut in_string = false;
+        let mut escaped = false;
+        for (idx, ch) in line.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if in_string && ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = !in_string;
+            } else if ch == ';' && !in_string {
+                exprs.push(&line[start..idx]);
+                start = idx + 1;
+            }
+        }
+        exprs.push(&line[start..]);
+        exprs
+    }
+
     fn eval_and_print_multi_line(&mut self, exprs: &str) -> bool {
         for line in exprs.lines() {
             if line.is_empty() {
@@ -137,37 +310,70 @@ impl REPL {
         true
     }
 
+    // Literals are parsed and results printed with the environment's decimal and word contexts
     fn eval_and_print(&mut self, expr: &str) -> bool {
+        let (decimal, word) = (self.env.decimal(), self.env.word());
+        pcalc_environment::bind_context(decimal, word, || self.parse_eval_and_print(expr))
+    }
+
+    fn parse_eval_and_print(&mut self, expr: &str) -> bool {
+        // Source of the expression for error carets, including earlier lines of a partial function
+        let source = if self.pending.is_empty() {
+            String::from(expr)
+        } else {
+            format!("{}\n{}", self.pending, expr)
+        };
+        self.pending.clear();
+
         match self.parser.parse(expr) {
             Ok(code) => {
                 if !code.is_evaluable() {
+                    self.pending = source;
                     return true;
                 }
 
                 match code.eval(&mut self.env) {
+                    Ok(value) if value.is_none() => true,
                     Ok(value) => {
                         if !self.batch {
-                            println!("{}", value);
+                            println!("{}", self.env.format().value(&value));
                         }
                         self.env.set_var(&self.last_var, value).unwrap();
                         true
                     }
                     Err(err) => {
                         eprintln!("EvalError: {}", err);
+                        Self::print_error_detail(&err, &source);
                         false
                     }
                 }
             }
             Err(err) => {
                 eprintln!("ParseError: {}", err);
+                Self::print_error_detail(&err, &source);
                 false
             }
         }
     }
 
+    // Caret under the failing token, the user functions the error passed through, and suggestions
+    fn print_error_detail(err: &Error, source: &str) {
+        if let Some(caret) = err.caret(source) {
+            for line in caret.lines() {
+                eprintln!("  {}", line);
+            }
+        }
+        for frame in err.trace() {
+            eprintln!("  {}", frame);
+        }
+        if let Some(hint) = err.did_you_mean() {
+            eprintln!("  {}", hint);
+        }
+    }
+
     fn reset_env(&mut self) {
         self.env.reset();
-        self.env.def_var(&self.last_var, Value::from_num(0.0)).unwrap();
+        self.env.def_var(&self.last_var, Value::from_int(0)).unwrap();
     }
 
     fn toggle_batch(&mut self) {
@@ -179,9 +385,105 @@ impl REPL {
         println!("batch mode {}", if self.batch { "on" } else { "off" });
     }
 
+    fn toggle_rational(&mut self) {
+        self.parser.set_rational(!self.parser.is_rational());
+        println!("rational mode {}", if self.parser.is_rational() { "on" } else { "off" });
+    }
+
+    fn toggle_decimal(&mut self) {
+        self.parser.set_decimal(!self.parser.is_decimal());
+        println!("decimal mode {}", if self.parser.is_decimal() { "on" } else { "off" });
+    }
+
+    // :decimal toggles decimal mode, :decimal scale <n|off> and :decimal rounding <mode> set the decimal context
+    fn decimal_command(&mut self, args: &str) {
+        match args.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [] => self.toggle_decimal(),
+            [name, value] => {
+                if self.set_decimal_option(name, value) {
+                    self.print_decimal_context();
+                }
+            }
+            _ => eprintln!("Usage: {} [scale <n|off> | rounding <half-even|half-up|truncate>]", CMD_DECIMAL)
+        }
+    }
+
+    fn print_decimal_context(&self) {
+        let ctx = self.env.decimal();
+        match ctx.scale() {
+            Some(scale) => println!("decimal scale {}, rounding {}", scale, ctx.rounding().name()),
+            None => println!("decimal scale off, rounding {}", ctx.rounding().name())
+        }
+    }
+
+    // :word shows the word context, :word size <size|off> and :word overflow <mode> set it
+    fn word_command(&mut self, args: &str) {
+        match args.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [] => self.print_word_context(),
+            [name, value] => {
+                if self.set_word_option(name, value) {
+                    self.print_word_context();
+                }
+            }
+            _ => eprintln!("Usage: {} [size <u8|u16|u32|u64|i8|i16|i32|i64|off> | overflow <check|wrap>]", CMD_WORD)
+        }
+    }
+
+    fn print_word_context(&self) {
+        let ctx = self.env.word();
+        match ctx.size() {
+            Some(size) => println!("word size {}, overflow {}", size.name(), ctx.overflow().name()),
+            None => println!("word size off, overflow {}", ctx.overflow().name())
+        }
+    }
+
+    // :format shows the output format, :format <settings> changes it
+    fn format_command(&mut self, args: &str) {
+        if args.trim().is_empty() || self.set_format(args) {
+            println!("format {}", self.env.format().settings());
+        }
+    }
+
+    // :tolerance shows the approximate equality settings, :tolerance <settings> changes them
+    fn tolerance_command(&mut self, args: &str) {
+        if args.trim().is_empty() || self.set_tolerance(args) {
+            println!("tolerance {}", self.env.tolerance().settings());
+        }
+    }
+
+    fn toggle_strict(&mut self) {
+        self.env.set_strict(!self.env.is_strict());
+        println!("strict mode {}", if self.env.is_strict() { "on" } else { "off" });
+    }
+
+    fn toggle_truthy(&mut self) {
+        self.env.set_truthy(!self.env.is_truthy());
+        println!("truthy mode {}", if self.env.is_truthy() { "on" } else { "off" });
+    }
+
+    // :angle shows the angle mode, :angle <rad|deg|grad> sets it
+    fn angle_command(&mut self, args: &str) {
+        match args.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [] => println!("angle mode {}", self.env.angle().name()),
+            [name] => {
+                if self.set_angle(name) {
+                    println!("angle mode {}", self.env.angle().name());
+                }
+            }
+            _ => eprintln!("Usage: {} [rad|deg|grad]", CMD_ANGLE)
+        }
+    }
+
+    fn toggle_fraction(&mut self) {
+        let format = self.env.format_mut();
+        let fraction = format.rational() == RationalOutput::Fraction;
+        format.set_rational(if fraction { RationalOutput::Decimal } else { RationalOutput::Fraction });
+        println!("fraction output {}", if fraction { "off" } else { "on" });
+    }
+
     fn print_last(&self) {
         match self.env.get_var(&self.last_var) {
-            Ok(val) => println!("{}", val),
+            Ok(val) => self.env.in_context(|| println!("{}", self.env.format().value(&val))),
             Err(err) => eprintln!("ParseError: {}", err)
         };
     }
@@ -189,13 +491,30 @@ impl REPL {
     fn print_help(&self) {
         help::print_help(
             &vec![&self.last_var],
-            &vec![CMD_ENV, CMD_RESET, CMD_QUIT, CMD_BATCH, CMD_LAST, CMD_HELP, CMD_EXAMPLES]
+            &vec![
+                CMD_ENV,
+                CMD_RESET,
+                CMD_QUIT,
+                CMD_BATCH,
+                CMD_RATIONAL,
+                CMD_FRACTION,
+                CMD_DECIMAL,
+                CMD_WORD,
+                CMD_FORMAT,
+                CMD_STRICT,
+                CMD_TRUTHY,
+                CMD_TOLERANCE,
+                CMD_ANGLE,
+                CMD_LAST,
+                CMD_HELP,
+                CMD_EXAMPLES,
+            ]
         );
     }
 
     fn try_repl_command(&mut self, cmd: &str) -> bool {
         if cmd == CMD_ENV {
-            self.env.show();
+            self.env.in_context(|| self.env.show());
             return true;
         } else if cmd == CMD_RESET {
             self.reset_env();
@@ -203,6 +522,33 @@ impl REPL {
         } else if cmd == CMD_BATCH {
             self.toggle_batch();
             return true;
+        } else if cmd == CMD_RATIONAL {
+            self.toggle_rational();
+            return true;
+        } else if let Some(args) = cmd.strip_prefix(CMD_DECIMAL).filter(|a| a.is_empty() || a.starts_with(' ')) {
+            self.decimal_command(args);
+            return true;
+        } else if let Some(args) = cmd.strip_prefix(CMD_WORD).filter(|a| a.is_empty() || a.starts_with(' ')) {
+            self.word_command(args);
+            return true;
+        } else if let Some(args) = cmd.strip_prefix(CMD_FORMAT).filter(|a| a.is_empty() || a.starts_with(' ')) {
+            self.format_command(args);
+            return true;
+        } else if cmd == CMD_STRICT {
+            self.toggle_strict();
+            return true;
+        } else if let Some(args) = cmd.strip_prefix(CMD_TOLERANCE).filter(|a| a.is_empty() || a.starts_with(' ')) {
+            self.tolerance_command(args);
+            return true;
+        } else if cmd == CMD_TRUTHY {
+            self.toggle_truthy();
+            return true;
+        } else if let Some(args) = cmd.strip_prefix(CMD_ANGLE).filter(|a| a.is_empty() || a.starts_with(' ')) {
+            self.angle_command(args);
+            return true;
+        } else if cmd == CMD_FRACTION {
+            self.toggle_fraction();
+            return true;
         } else if cmd == CMD_LAST {
             self.print_last();
             return true;
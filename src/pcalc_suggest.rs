@@ -0,0 +1,76 @@
+use std::cmp;
+
+// --------------------------------------------------------------------------------
+// Suggestions - Closest names to a misspelled name
+
+const MAX_SUGGESTIONS: usize = 3;
+
+// Optimal string alignment distance: insertions, deletions, substitutions and
+// transpositions of adjacent characters each count as one edit, e.g. dsit to dist is 1.
+pub fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let lhs: Vec<char> = lhs.chars().collect();
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut dist = vec![vec![0; rhs.len() + 1]; lhs.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    dist[0] = (0..=rhs.len()).collect();
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let cost = if lhs[i - 1] == rhs[j - 1] { 0 } else { 1 };
+            dist[i][j] = cmp::min(cmp::min(dist[i - 1][j] + 1, dist[i][j - 1] + 1), dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                dist[i][j] = cmp::min(dist[i][j], dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    dist[lhs.len()][rhs.len()]
+}
+
+// Candidates within a third of the name's length in edits (at least one), closest first,
+// and among equally close candidates, those sharing a longer prefix with the name first
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let len = name.chars().count();
+    let limit = cmp::max(1, len / 3);
+    let mut matches: Vec<(usize, cmp::Reverse<usize>, &str)> = candidates
+        .into_iter()
+        .filter(|cand| *cand != name)
+        .map(|cand| (edit_distance(name, cand), cmp::Reverse(common_prefix(name, cand)), cand))
+        .filter(|(dist, _, _)| *dist <= limit && *dist < len)
+        .collect();
+    matches.sort();
+    matches.dedup();
+    matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, _, cand)| String::from(cand)).collect()
+}
+
+#[inline(always)]
+fn common_prefix(lhs: &str, rhs: &str) -> usize {
+    lhs.chars().zip(rhs.chars()).take_while(|(l, r)| l == r).count()
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("sqrt", "sqrt"), 0);
+        assert_eq!(edit_distance("sqr", "sqrt"), 1);
+        assert_eq!(edit_distance("dsit", "dist"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest() {
+        let names = ["sqrt", "exp", "exp2", "sum", "sin", "sinh", "shr", "str"];
+        assert_eq!(closest("sqr", names.iter().copied()), vec!["sqrt", "shr", "str"]);
+        assert_eq!(closest("sinn", names.iter().copied()), vec!["sin", "sinh"]);
+        assert_eq!(closest("ex", names.iter().copied()), vec!["exp"]);
+        assert!(closest("x", names.iter().copied()).is_empty());
+        assert!(closest("tangent", names.iter().copied()).is_empty());
+        assert!(closest("sqrt", names.iter().copied()).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::pcalc_binary_ops::{add, approx_equal, greater, less_equal, maximum, minimum, modular_power, multiply, subtract};
+use crate::pcalc_error::ErrorKind;
+use crate::pcalc_keywords as keywords;
+use crate::pcalc_tolerance::Tolerance;
+use crate::pcalc_value::{Value, ValueError, ValueResult};
+use crate::pcalc_word as word;
+
+// x limited to the range lo to hi, e.g. clamp 12 0 10 is 10
+#[inline(always)]
+pub fn clamp(x: &Value, lo: &Value, hi: &Value) -> ValueResult {
+    check_bounds(keywords::CLAMP, x, lo, hi)?;
+    // NaN stays NaN, as with f64::clamp, so strict mode still rejects it
+    if matches!(x, Value::Num(n) if n.is_nan()) {
+        return Ok(x.clone());
+    }
+    minimum(&maximum(x, lo)?, hi)
+}
+
+// Linear interpolation from a at t = 0 to b at t = 1, e.g. lerp 10 20 0.25 is 12.5
+#[inline(always)]
+pub fn lerp(a: &Value, b: &Value, t: &Value) -> ValueResult {
+    if a.is_num() && b.is_num() && t.is_num() && !(a.is_exact() && b.is_exact() && t.is_exact()) {
+        let (a, b, t) = (a.to_num()?, b.to_num()?, t.to_num()?);
+        // Interpolate from the nearer end, so both ends are exact
+        let result = if t < 0.5 { t.mul_add(b - a, a) } else { (t - 1.0).mul_add(b - a, b) };
+        return Ok(Value::from_num(result));
+    }
+    add(a, &multiply(t, &subtract(b, a)?)?)
+}
+
+// a * b + c, with a single rounding for floating point numbers
+#[inline(always)]
+pub fn fused_multiply_add(a: &Value, b: &Value, c: &Value) -> ValueResult {
+    // Integers stay exact when the product overflows the word but the sum doesn't
+    if let (Value::Int(x), Value::Int(y), Value::Int(z)) = (a, b, c) {
+        let (x, y, z) = (word::widen(*x), word::widen(*y), word::widen(*z));
+        let result = if word::is_wrapping() {
+            Some(word::wrap(x.wrapping_mul(y).wrapping_add(z)))
+        } else {
+            x.checked_mul(y).and_then(|p| p.checked_add(z)).and_then(word::fit)
+        };
+        return match result {
+            Some(i) => Ok(Value::from_int(i)),
+            None => Err(ValueError::from_string(
+                ErrorKind::Domain,
+                format!("Integer overflow - '{} {} {} {}'", keywords::FMA, x, y, z)
+            ))
+        };
+    }
+    if a.is_num() && b.is_num() && c.is_num() && !(a.is_exact() && b.is_exact() && c.is_exact()) {
+        return Ok(Value::from_num(a.to_num()?.mul_add(b.to_num()?, c.to_num()?)));
+    }
+    add(&multiply(a, b)?, c)
+}
+
+// lo <= x <= hi, three-valued for intervals like the comparisons
+#[inline(always)]
+pub fn between(x: &Value, lo: &Value, hi: &Value) -> ValueResult {
+    check_bounds(keywords::BETWEEN, x, lo, hi)?;
+    match (less_equal(lo, x)?, less_equal(x, hi)?) {
+        (Value::Bool(false), _) | (_, Value::Bool(false)) => Ok(Value::from_bool(false)),
+        (Value::Bool(true), Value::Bool(true)) => Ok(Value::from_bool(true)),
+        _ => Ok(Value::from_truth(None))
+    }
+}
+
+// a when c holds as an if condition, else b; both are evaluated
+#[inline(always)]
+pub fn select(c: &Value, a: &Value, b: &Value) -> ValueResult {
+    if c.is_unknown() {
+        return Err(ValueError::new(ErrorKind::Domain, "Ambiguous interval comparison in select"));
+    }
+    Ok(if c.as_bool() { a.clone() } else { b.clone() })
+}
+
+// Approximate equality with epsilon as both the absolute and the relative tolerance
+#[inline(always)]
+pub fn approx_within(x: &Value, y: &Value, epsilon: &Value) -> ValueResult {
+    approx_equal(x, y, &Tolerance::with_epsilon(epsilon.to_num()?)?)
+}
+
+// Bounds of clamp and between, where lo must not be greater than hi
+fn check_bounds(name: &str, x: &Value, lo: &Value, hi: &Value) -> Result<(), ValueError> {
+    if greater(lo, hi)?.as_bool() {
+        return Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Invalid bounds - '{} {} {} {}'", name, x, lo, hi)
+        ));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------------------------------------
+
+pub type TernaryFtn = fn(&Value, &Value, &Value) -> ValueResult;
+
+pub fn top2ftn(name: &str) -> Option<TernaryFtn> {
+    match name {
+        keywords::CLAMP => Some(clamp),
+        keywords::LERP => Some(lerp),
+        keywords::FMA => Some(fused_multiply_add),
+        keywords::BETWEEN => Some(between),
+        keywords::SELECT => Some(select),
+        keywords::MODPOW => Some(modular_power),
+        keywords::APPROX => Some(approx_within),
+        _ => None
+    }
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcalc_interval::Interval;
+
+    fn int(i: i64) -> Value {
+        Value::from_int(i)
+    }
+
+    fn num(x: f64) -> Value {
+        Value::from_num(x)
+    }
+
+    #[test]
+    fn test_ternop_clamp() {
+        assert_eq!(clamp(&int(12), &int(0), &int(10)).unwrap(), int(10));
+        assert_eq!(clamp(&int(-3), &int(0), &int(10)).unwrap(), int(0));
+        assert_eq!(clamp(&num(2.5), &int(0), &int(10)).unwrap(), num(2.5));
+        assert_eq!(format!("{}", clamp(&int(1), &int(10), &int(0)).unwrap_err()), "Invalid bounds - 'clamp 1 10 0'");
+        assert!(clamp(&num(f64::NAN), &int(0), &int(10)).unwrap().to_num().unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_ternop_lerp_fma() {
+        assert_eq!(lerp(&int(10), &int(20), &num(0.25)).unwrap(), num(12.5));
+        assert_eq!(lerp(&num(0.1), &num(0.7), &num(1.0)).unwrap(), num(0.7));
+        assert_eq!(lerp(&num(0.1), &num(0.7), &num(0.0)).unwrap(), num(0.1));
+        assert_eq!(lerp(&int(0), &int(10), &int(2)).unwrap(), int(20));
+
+        assert_eq!(fused_multiply_add(&int(2), &int(3), &int(4)).unwrap(), int(10));
+        let x = 1.0 + f64::EPSILON;
+        let fma = fused_multiply_add(&num(x), &num(x), &num(-1.0)).unwrap();
+        assert_eq!(fma, num(2.0 * f64::EPSILON + f64::EPSILON * f64::EPSILON));
+        assert!(fused_multiply_add(&int(i64::MAX), &int(2), &int(0)).is_err());
+
+        // The product may overflow as long as the sum fits
+        let big = int(4_000_000_000);
+        assert_eq!(
+            fused_multiply_add(&big, &big, &int(-9_000_000_000_000_000_000)).unwrap(),
+            int(7_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            format!("{}", fused_multiply_add(&big, &big, &int(-1)).unwrap_err()),
+            "Integer overflow - 'fma 4000000000 4000000000 -1'"
+        );
+    }
+
+    #[test]
+    fn test_ternop_between_select() {
+        let yes = Value::from_bool(true);
+        let no = Value::from_bool(false);
+        assert_eq!(between(&int(5), &int(0), &int(10)).unwrap(), yes);
+        assert_eq!(between(&int(10), &int(0), &int(10)).unwrap(), yes);
+        assert_eq!(between(&num(10.5), &int(0), &int(10)).unwrap(), no);
+        let ival = Value::from_interval(Interval::new(8.0, 12.0).unwrap());
+        assert!(between(&ival, &int(0), &int(10)).unwrap().is_unknown());
+        assert_eq!(
+            format!("{}", between(&int(5), &int(10), &int(0)).unwrap_err()),
+            "Invalid bounds - 'between 5 10 0'"
+        );
+
+        assert_eq!(select(&yes, &int(1), &int(2)).unwrap(), int(1));
+        assert_eq!(select(&int(0), &int(1), &int(2)).unwrap(), int(2));
+        assert!(select(&Value::from_truth(None), &int(1), &int(2)).is_err());
+    }
+
+    #[test]
+    fn test_ternop_approx_within() {
+        assert_eq!(approx_within(&int(100), &num(100.5), &num(0.01)).unwrap(), Value::from_bool(true));
+        assert_eq!(approx_within(&int(1), &num(1.5), &num(0.01)).unwrap(), Value::from_bool(false));
+        let err = approx_within(&int(1), &int(1), &num(-0.01)).unwrap_err();
+        assert_eq!(format!("{}", err), "Invalid tolerance for epsilon - '-0.01'");
+    }
+
+    #[test]
+    fn test_top2ftn() {
+        for name in keywords::ternary_ops() {
+            assert!(top2ftn(name).is_some());
+        }
+        assert!(top2ftn("+").is_none());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::pcalc_error::{Error, ErrorKind};
+
+// --------------------------------------------------------------------------------
+// Tolerance Error
+
+pub type ToleranceError = Error;
+
+impl ToleranceError {
+    pub fn invalid(name: &str, value: &str) -> Self {
+        ToleranceError::from_string(ErrorKind::Domain, format!("Invalid tolerance for {} - '{}'", name, value))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Comparison - How approximately equal numbers are told apart
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Relative, // Within the larger of the absolute and the scaled relative tolerance
+    Ulp       // Within a number of representable doubles
+}
+
+// --------------------------------------------------------------------------------
+// Tolerance - Approximate equality settings of ~= and !~=
+
+const DEFAULT_REL: f64 = 1e-9;
+const DEFAULT_ABS: f64 = 1e-12;
+const DEFAULT_ULPS: u64 = 4;
+
+#[derive(Debug, Clone)]
+pub struct Tolerance {
+    comparison: Comparison,
+    rel: f64,
+    abs: f64,
+    ulps: u64
+}
+
+impl Tolerance {
+    pub fn new() -> Self {
+        Tolerance {
+            comparison: Comparison::Relative,
+            rel: DEFAULT_REL,
+            abs: DEFAULT_ABS,
+            ulps: DEFAULT_ULPS
+        }
+    }
+
+    // Relative comparison with epsilon as both the absolute and the relative tolerance
+    pub fn with_epsilon(epsilon: f64) -> Result<Self, ToleranceError> {
+        let epsilon = Self::check("epsilon", epsilon)?;
+        Ok(Tolerance {
+            comparison: Comparison::Relative,
+            rel: epsilon,
+            abs: epsilon,
+            ulps: DEFAULT_ULPS
+        })
+    }
+
+    #[inline(always)]
+    pub fn comparison(&self) -> Comparison {
+        self.comparison
+    }
+
+    // Apply space separated settings, e.g. "rel 1e-6 abs 0" or "ulp 8"; nothing changes on error.
+    // rel and abs select relative comparison, and ulp selects ULP distance comparison.
+    pub fn apply(&mut self, settings: &str) -> Result<(), ToleranceError> {
+        let mut tolerance = self.clone();
+        let mut tokens = settings.split_whitespace();
+        while let Some(name) = tokens.next() {
+            match name {
+                "default" => tolerance = Tolerance::new(),
+                "rel" => {
+                    tolerance.rel = Self::parse_tolerance(name, tokens.next())?;
+                    tolerance.comparison = Comparison::Relative;
+                }
+                "abs" => {
+                    tolerance.abs = Self::parse_tolerance(name, tokens.next())?;
+                    tolerance.comparison = Comparison::Relative;
+                }
+                "ulp" => {
+                    let value = tokens.next().unwrap_or("");
+                    tolerance.ulps = value.parse::<u64>().map_err(|_| ToleranceError::invalid(name, value))?;
+                    tolerance.comparison = Comparison::Ulp;
+                }
+                _ => {
+                    return Err(ToleranceError::from_string(
+                        ErrorKind::Syntax,
+                        format!("Unknown tolerance setting - '{}'", name)
+                    ))
+                }
+            }
+        }
+        *self = tolerance;
+        Ok(())
+    }
+
+    // Current settings, in the form accepted by apply
+    pub fn settings(&self) -> String {
+        match self.comparison {
+            Comparison::Relative => format!("rel {} abs {}", Self::show(self.rel), Self::show(self.abs)),
+            Comparison::Ulp => format!("ulp {}", self.ulps)
+        }
+    }
+
+    // Infinities are only equal to themselves, and NaN to nothing
+    pub fn approx_eq(&self, x: f64, y: f64) -> bool {
+        if x == y {
+            return true;
+        }
+        if !x.is_finite() || !y.is_finite() {
+            return false;
+        }
+        match self.comparison {
+            Comparison::Relative => (x - y).abs() <= self.abs.max(self.rel * x.abs().max(y.abs())),
+            Comparison::Ulp => ulp_distance(x, y) <= self.ulps
+        }
+    }
+
+    // --------------------------------------------------------------------------------
+    // Private Functions
+
+    fn parse_tolerance(name: &str, value: Option<&str>) -> Result<f64, ToleranceError> {
+        let value = value.unwrap_or("");
+        match value.parse::<f64>() {
+            Ok(tol) => Self::check(name, tol),
+            Err(_) => Err(ToleranceError::invalid(name, value))
+        }
+    }
+
+    fn show(tol: f64) -> String {
+        if tol == 0.0 {
+            String::from("0")
+        } else {
+            format!("{:e}", tol)
+        }
+    }
+
+    fn check(name: &str, tol: f64) -> Result<f64, ToleranceError> {
+        if tol.is_finite() && tol >= 0.0 {
+            Ok(tol)
+        } else {
+            Err(ToleranceError::invalid(name, &tol.to_string()))
+        }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Number of representable doubles from x to y, where 0.0 and -0.0 are the same
+pub fn ulp_distance(x: f64, y: f64) -> u64 {
+    ordered_bits(x).abs_diff(ordered_bits(y))
+}
+
+// Bits of x as an integer ordered like the doubles themselves
+#[inline(always)]
+fn ordered_bits(x: f64) -> i64 {
+    let bits = x.to_bits() as i64;
+    if bits < 0 {
+        i64::MIN - bits
+    } else {
+        bits
+    }
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerance_relative() {
+        let tol = Tolerance::new();
+        assert!(tol.approx_eq(0.1 + 0.2, 0.3));
+        assert!(tol.approx_eq(1e20, 1e20 + 1e10));
+        assert!(!tol.approx_eq(1.0, 1.0 + 1e-6));
+        assert!(tol.approx_eq(0.0, 1e-13));
+        assert!(!tol.approx_eq(0.0, 1e-11));
+        assert!(tol.approx_eq(f64::INFINITY, f64::INFINITY));
+        assert!(!tol.approx_eq(f64::INFINITY, f64::MAX));
+        assert!(!tol.approx_eq(f64::NAN, f64::NAN));
+
+        let tol = Tolerance::with_epsilon(0.01).unwrap();
+        assert!(tol.approx_eq(100.0, 100.9));
+        assert!(tol.approx_eq(0.0, 0.005));
+        assert!(!tol.approx_eq(1.0, 1.02));
+        assert!(Tolerance::with_epsilon(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_tolerance_ulp() {
+        assert_eq!(ulp_distance(1.0, 1.0 + f64::EPSILON), 1);
+        assert_eq!(ulp_distance(0.0, -0.0), 0);
+        assert_eq!(ulp_distance(-f64::MIN_POSITIVE, f64::MIN_POSITIVE), 2 * f64::MIN_POSITIVE.to_bits());
+        assert_eq!(ulp_distance(0.1 + 0.2, 0.3), 1);
+
+        let mut tol = Tolerance::new();
+        tol.apply("ulp 1").unwrap();
+        assert_eq!(tol.comparison(), Comparison::Ulp);
+        assert!(tol.approx_eq(0.1 + 0.2, 0.3));
+        assert!(!tol.approx_eq(1.0, 1.0 + 2.0 * f64::EPSILON));
+        assert!(!tol.approx_eq(f64::MAX, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_tolerance_settings() {
+        let mut tol = Tolerance::new();
+        assert_eq!(tol.settings(), "rel 1e-9 abs 1e-12");
+        tol.apply("rel 1e-6 abs 0").unwrap();
+        assert_eq!(tol.settings(), "rel 1e-6 abs 0");
+        tol.apply("ulp 8").unwrap();
+        assert_eq!(tol.settings(), "ulp 8");
+        tol.apply("default").unwrap();
+        assert_eq!(tol.settings(), "rel 1e-9 abs 1e-12");
+
+        assert_eq!(format!("{}", tol.apply("rel -1").unwrap_err()), "Invalid tolerance for rel - '-1'");
+        assert_eq!(format!("{}", tol.apply("ulp").unwrap_err()), "Invalid tolerance for ulp - ''");
+        assert_eq!(format!("{}", tol.apply("abs 0 foo").unwrap_err()), "Unknown tolerance setting - 'foo'");
+        assert_eq!(tol.settings(), "rel 1e-9 abs 1e-12");
+    }
+}
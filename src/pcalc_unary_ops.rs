@@ -1,139 +1,361 @@
+use crate::pcalc_angle::Angle;
+use crate::pcalc_binary_ops::{add, multiply};
+use crate::pcalc_decimal::Decimal;
+use crate::pcalc_error::ErrorKind;
 use crate::pcalc_keywords as keywords;
-use crate::pcalc_value::{Value, ValueResult};
+use crate::pcalc_number_theory as ntheory;
+use crate::pcalc_special_math as special;
+use crate::pcalc_uncertain::Uncertain;
+use crate::pcalc_value::{Value, ValueError, ValueResult};
+use crate::pcalc_word as word;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+use std::f64::consts;
 
 #[inline(always)]
 pub fn square_root(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.sqrt()))
+    match val {
+        Value::Quantity(q) => return Ok(Value::from_quantity(q.sqrt()?)),
+        Value::Interval(i) => return Ok(Value::from_interval(i.sqrt()?)),
+        Value::Uncertain(u) => return uncertain_map(keywords::SQRT, u, f64::sqrt, |x| 0.5 / x.sqrt()),
+        _ => {}
+    }
+    complex_aware(val, |x| x < 0.0, f64::sqrt, |z| z.sqrt())
 }
 
 #[inline(always)]
 pub fn exponential(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.exp()))
+    match val {
+        Value::Interval(i) => return Ok(Value::from_interval(i.exp())),
+        Value::Uncertain(u) => return uncertain_map(keywords::EXP, u, f64::exp, f64::exp),
+        _ => {}
+    }
+    complex_aware(val, |_| false, f64::exp, |z| z.exp())
 }
 
 #[inline(always)]
 pub fn exponential2(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.exp2()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::EXP2, u, f64::exp2, |x| x.exp2() * consts::LN_2);
+    }
+    complex_aware(val, |_| false, f64::exp2, |z| (z * consts::LN_2).exp())
 }
 
 #[inline(always)]
 pub fn natural_logarithm(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.ln()))
+    match val {
+        Value::Interval(i) => return Ok(Value::from_interval(i.ln()?)),
+        Value::Uncertain(u) => return uncertain_map(keywords::LN, u, f64::ln, |x| 1.0 / x),
+        _ => {}
+    }
+    complex_aware(val, |x| x < 0.0, f64::ln, |z| z.ln())
 }
 
 #[inline(always)]
 pub fn logarithm2(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.log2()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::LOG2, u, f64::log2, |x| 1.0 / (x * consts::LN_2));
+    }
+    complex_aware(val, |x| x < 0.0, f64::log2, |z| z.ln() / consts::LN_2)
 }
 
 #[inline(always)]
 pub fn logarithm10(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.log10()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::LOG10, u, f64::log10, |x| 1.0 / (x * consts::LN_10));
+    }
+    complex_aware(val, |x| x < 0.0, f64::log10, |z| z.ln() / consts::LN_10)
 }
 
 #[inline(always)]
 pub fn trig_sin(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.sin()))
+    match val {
+        Value::Interval(i) => return Ok(Value::from_interval(i.sin())),
+        Value::Uncertain(u) => return uncertain_map(keywords::SIN, u, f64::sin, f64::cos),
+        _ => {}
+    }
+    complex_aware(val, |_| false, f64::sin, |z| z.sin())
 }
 
 #[inline(always)]
 pub fn trig_cos(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.cos()))
+    match val {
+        Value::Interval(i) => return Ok(Value::from_interval(i.cos())),
+        Value::Uncertain(u) => return uncertain_map(keywords::COS, u, f64::cos, |x| -x.sin()),
+        _ => {}
+    }
+    complex_aware(val, |_| false, f64::cos, |z| z.cos())
 }
 
 #[inline(always)]
 pub fn trig_tan(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.tan()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::TAN, u, f64::tan, |x| 1.0 / (x.cos() * x.cos()));
+    }
+    complex_aware(val, |_| false, f64::tan, |z| z.tan())
 }
 
 #[inline(always)]
 pub fn trig_sinh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.sinh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::SINH, u, f64::sinh, f64::cosh);
+    }
+    complex_aware(val, |_| false, f64::sinh, |z| z.sinh())
 }
 
 #[inline(always)]
 pub fn trig_cosh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.cosh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::COSH, u, f64::cosh, f64::sinh);
+    }
+    complex_aware(val, |_| false, f64::cosh, |z| z.cosh())
 }
 
 #[inline(always)]
 pub fn trig_tanh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.tanh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::TANH, u, f64::tanh, |x| 1.0 - x.tanh() * x.tanh());
+    }
+    complex_aware(val, |_| false, f64::tanh, |z| z.tanh())
 }
 
 #[inline(always)]
 pub fn trig_asin(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.asin()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ASIN, u, f64::asin, |x| 1.0 / (1.0 - x * x).sqrt());
+    }
+    complex_aware(val, |x| x.abs() > 1.0, f64::asin, |z| z.asin())
 }
 
 #[inline(always)]
 pub fn trig_acos(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.acos()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ACOS, u, f64::acos, |x| -1.0 / (1.0 - x * x).sqrt());
+    }
+    complex_aware(val, |x| x.abs() > 1.0, f64::acos, |z| z.acos())
 }
 
 #[inline(always)]
 pub fn trig_atan(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.atan()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ATAN, u, f64::atan, |x| 1.0 / (1.0 + x * x));
+    }
+    complex_aware(val, |_| false, f64::atan, |z| z.atan())
 }
 
 #[inline(always)]
 pub fn trig_asinh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.asinh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ASINH, u, f64::asinh, |x| 1.0 / (x * x + 1.0).sqrt());
+    }
+    complex_aware(val, |_| false, f64::asinh, |z| z.asinh())
 }
 
 #[inline(always)]
 pub fn trig_acosh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.acosh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ACOSH, u, f64::acosh, |x| 1.0 / (x * x - 1.0).sqrt());
+    }
+    complex_aware(val, |x| x < 1.0, f64::acosh, |z| z.acosh())
 }
 
 #[inline(always)]
 pub fn trig_atanh(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.atanh()))
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ATANH, u, f64::atanh, |x| 1.0 / (1.0 - x * x));
+    }
+    complex_aware(val, |x| x.abs() > 1.0, f64::atanh, |z| z.atanh())
+}
+
+// Trig ops in degrees or gradians. Numbers are reduced by right angles exactly, so e.g.
+// sin 180 is 0, and other values are scaled to radians.
+#[inline(always)]
+pub fn angle_sin(val: &Value, angle: Angle) -> ValueResult {
+    if val.is_num() {
+        return Ok(Value::from_num(angle.sin_cos(val.to_num()?).0));
+    }
+    trig_sin(&angle_to_radians(val, angle)?)
+}
+
+#[inline(always)]
+pub fn angle_cos(val: &Value, angle: Angle) -> ValueResult {
+    if val.is_num() {
+        return Ok(Value::from_num(angle.sin_cos(val.to_num()?).1));
+    }
+    trig_cos(&angle_to_radians(val, angle)?)
+}
+
+#[inline(always)]
+pub fn angle_tan(val: &Value, angle: Angle) -> ValueResult {
+    if val.is_num() {
+        let (sin, cos) = angle.sin_cos(val.to_num()?);
+        return Ok(Value::from_num(sin / cos));
+    }
+    trig_tan(&angle_to_radians(val, angle)?)
+}
+
+#[inline(always)]
+pub fn angle_asin(val: &Value, angle: Angle) -> ValueResult {
+    angle_from_radians(&trig_asin(val)?, angle)
+}
+
+#[inline(always)]
+pub fn angle_acos(val: &Value, angle: Angle) -> ValueResult {
+    angle_from_radians(&trig_acos(val)?, angle)
+}
+
+#[inline(always)]
+pub fn angle_atan(val: &Value, angle: Angle) -> ValueResult {
+    angle_from_radians(&trig_atan(val)?, angle)
+}
+
+#[inline(always)]
+pub fn deg_to_rad(val: &Value) -> ValueResult {
+    angle_to_radians(val, Angle::Degrees)
+}
+
+#[inline(always)]
+pub fn rad_to_deg(val: &Value) -> ValueResult {
+    angle_from_radians(val, Angle::Degrees)
+}
+
+// Angle in the given unit to radians
+pub fn angle_to_radians(val: &Value, angle: Angle) -> ValueResult {
+    if scales_as_angle(val) {
+        multiply(val, &Value::from_num(angle.to_radians(1.0)))
+    } else {
+        Ok(Value::from_num(angle.to_radians(val.to_num()?)))
+    }
+}
+
+// Angle in radians to the given unit
+pub fn angle_from_radians(val: &Value, angle: Angle) -> ValueResult {
+    if scales_as_angle(val) {
+        multiply(val, &Value::from_num(angle.from_radians(1.0)))
+    } else {
+        Ok(Value::from_num(angle.from_radians(val.to_num()?)))
+    }
+}
+
+// Non-number angles, converted by scaling, so other operands report themselves, e.g. sin 1_m
+#[inline(always)]
+fn scales_as_angle(val: &Value) -> bool {
+    matches!(
+        val,
+        Value::Decimal(_) | Value::Complex(_) | Value::Interval(_) | Value::Uncertain(_) | Value::Vector(_) | Value::Matrix(_)
+    )
 }
 
 #[inline(always)]
 pub fn sign(val: &Value) -> ValueResult {
-    Ok(Value::from_num(if val.to_num()? < 0.0 { -1.0 } else { 1.0 }))
+    match val {
+        Value::Int(i) => Ok(Value::from_int(if word::widen(*i) < 0 { -1 } else { 1 })),
+        Value::Rational(r) => Ok(Value::from_rational(if r.is_negative() { -BigRational::one() } else { BigRational::one() })),
+        Value::Decimal(d) => Ok(Value::from_decimal(Decimal::from_int(if d.is_negative() { -1 } else { 1 }))),
+        Value::Quantity(q) => Ok(Value::from_num(if q.value() < 0.0 { -1.0 } else { 1.0 })),
+        Value::Uncertain(u) => Ok(Value::from_num(if u.value() < 0.0 { -1.0 } else { 1.0 })),
+        _ => Ok(Value::from_num(if val.to_num()? < 0.0 { -1.0 } else { 1.0 }))
+    }
 }
 
 #[inline(always)]
 pub fn absolute(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.abs()))
+    match val {
+        Value::Int(i) => int_result(keywords::ABS, *i, i128::checked_abs),
+        Value::Rational(r) => Ok(Value::from_rational(r.abs())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.abs())),
+        Value::Complex(z) => Ok(Value::from_num(z.norm())),
+        Value::Quantity(q) => Ok(Value::from_quantity(q.abs())),
+        Value::Interval(i) => Ok(Value::from_interval(i.abs())),
+        Value::Uncertain(u) => uncertain_map(keywords::ABS, u, f64::abs, f64::signum),
+        Value::Duration(dur) => Ok(Value::from_duration(dur.abs())),
+        _ => Ok(Value::from_num(val.to_num()?.abs()))
+    }
 }
 
 #[inline(always)]
 pub fn reciprocal(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.recip()))
+    match val {
+        Value::Rational(r) if r.is_zero() => Err(ValueError::new(ErrorKind::Domain, "Division by zero")),
+        Value::Rational(r) => Ok(Value::from_rational(r.recip())),
+        Value::Decimal(d) => Ok(Value::from_decimal(Decimal::from_int(1).div(d)?)),
+        Value::Complex(z) => Ok(Value::from_complex(z.inv())),
+        Value::Uncertain(u) => uncertain_map(keywords::RECIP, u, f64::recip, |x| -1.0 / (x * x)),
+        _ => Ok(Value::from_num(val.to_num()?.recip()))
+    }
 }
 
 #[inline(always)]
 pub fn fraction(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.fract()))
+    match val {
+        Value::Int(_) => Ok(Value::from_int(0)),
+        Value::Rational(r) => Ok(Value::from_rational(r.fract())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.fract())),
+        Value::Uncertain(u) => uncertain_map(keywords::FRACT, u, f64::fract, |_| 1.0),
+        _ => Ok(Value::from_num(val.to_num()?.fract()))
+    }
 }
 
 #[inline(always)]
 pub fn truncate(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.trunc()))
+    match val {
+        Value::Int(_) => Ok(val.clone()),
+        Value::Rational(r) => Ok(Value::from_rational(r.trunc())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.trunc())),
+        Value::Uncertain(u) => uncertain_map(keywords::TRUNC, u, f64::trunc, |_| 0.0),
+        _ => Ok(Value::from_num(val.to_num()?.trunc()))
+    }
 }
 
 #[inline(always)]
 pub fn ceiling(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.ceil()))
+    match val {
+        Value::Int(_) => Ok(val.clone()),
+        Value::Rational(r) => Ok(Value::from_rational(r.ceil())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.ceil())),
+        Value::Uncertain(u) => uncertain_map(keywords::CEIL, u, f64::ceil, |_| 0.0),
+        _ => Ok(Value::from_num(val.to_num()?.ceil()))
+    }
 }
 
 #[inline(always)]
 pub fn floor(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.floor()))
+    match val {
+        Value::Int(_) => Ok(val.clone()),
+        Value::Rational(r) => Ok(Value::from_rational(r.floor())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.floor())),
+        Value::Uncertain(u) => uncertain_map(keywords::FLOOR, u, f64::floor, |_| 0.0),
+        _ => Ok(Value::from_num(val.to_num()?.floor()))
+    }
 }
 
 #[inline(always)]
 pub fn round(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.to_num()?.round()))
+    match val {
+        Value::Int(_) => Ok(val.clone()),
+        Value::Rational(r) => Ok(Value::from_rational(r.round())),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.round())),
+        Value::Uncertain(u) => uncertain_map(keywords::ROUND, u, f64::round, |_| 0.0),
+        _ => Ok(Value::from_num(val.to_num()?.round()))
+    }
 }
 
 #[inline(always)]
 pub fn negate(val: &Value) -> ValueResult {
-    Ok(Value::from_num(-val.to_num()?))
+    match val {
+        Value::Int(i) => int_result(keywords::NEG, *i, i128::checked_neg),
+        Value::Rational(r) => Ok(Value::from_rational(-r)),
+        Value::Decimal(d) => Ok(Value::from_decimal(d.neg())),
+        Value::Complex(z) => Ok(Value::from_complex(-z)),
+        Value::Quantity(q) => Ok(Value::from_quantity(q.neg())),
+        Value::Interval(i) => Ok(Value::from_interval(i.neg())),
+        Value::Uncertain(u) => uncertain_map(keywords::NEG, u, |x| -x, |_| -1.0),
+        Value::Duration(dur) => Ok(Value::from_duration(dur.neg())),
+        Value::Vector(vec) => Ok(Value::from_vector(vec.map(|x| -x))),
+        Value::Matrix(mat) => Ok(Value::from_matrix(mat.map(|x| -x))),
+        _ => Ok(Value::from_num(-val.to_num()?))
+    }
 }
 
 #[inline(always)]
@@ -141,9 +363,60 @@ pub fn logical_not(val: &Value) -> ValueResult {
     Ok(Value::from_bool(!val.to_bool()?))
 }
 
+#[inline(always)]
+pub fn real_part(val: &Value) -> ValueResult {
+    match val {
+        Value::Complex(z) => Ok(Value::from_num(z.re)),
+        Value::Uncertain(_) => Ok(val.clone()),
+        _ => Ok(Value::from_num(val.to_num()?))
+    }
+}
+
+#[inline(always)]
+pub fn imaginary_part(val: &Value) -> ValueResult {
+    if let Value::Uncertain(_) = val {
+        return Ok(Value::from_num(0.0));
+    }
+    Ok(Value::from_num(val.to_complex()?.im))
+}
+
+#[inline(always)]
+pub fn conjugate(val: &Value) -> ValueResult {
+    match val {
+        Value::Complex(z) => Ok(Value::from_complex(z.conj())),
+        Value::Uncertain(_) => Ok(val.clone()),
+        _ => Ok(Value::from_num(val.to_num()?))
+    }
+}
+
+#[inline(always)]
+pub fn argument(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return Ok(Value::from_num(if u.value() < 0.0 { consts::PI } else { 0.0 }));
+    }
+    Ok(Value::from_num(val.to_complex()?.arg()))
+}
+
+#[inline(always)]
+pub fn complex_absolute(val: &Value) -> ValueResult {
+    if let Value::Uncertain(_) = val {
+        return absolute(val);
+    }
+    Ok(Value::from_num(val.to_complex()?.norm()))
+}
+
 #[inline(always)]
 pub fn num_cast(val: &Value) -> ValueResult {
-    Ok(Value::from_num(val.as_num()))
+    match val {
+        Value::Bool(b) => Ok(Value::from_int(i64::from(*b))),
+        Value::Str(_) => parse_number(val),
+        Value::Quantity(q) => Ok(Value::from_num(q.magnitude())),
+        Value::Uncertain(u) => Ok(Value::from_num(u.value())),
+        Value::Decimal(d) => Ok(Value::from_num(d.to_f64())),
+        Value::DateTime(dt) => Ok(Value::from_int(dt.secs())),
+        Value::Duration(dur) => Ok(Value::from_int(dur.secs())),
+        _ => Ok(val.clone())
+    }
 }
 
 #[inline(always)]
@@ -151,6 +424,352 @@ pub fn bool_cast(val: &Value) -> ValueResult {
     Ok(Value::from_bool(val.as_bool()))
 }
 
+#[inline(always)]
+pub fn length(val: &Value) -> ValueResult {
+    match val {
+        Value::List(items) => Ok(Value::from_int(items.len() as i64)),
+        Value::Str(s) => Ok(Value::from_int(s.chars().count() as i64)),
+        Value::Vector(vec) => Ok(Value::from_int(vec.len() as i64)),
+        _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a string or list", val)))
+    }
+}
+
+#[inline(always)]
+pub fn sum(val: &Value) -> ValueResult {
+    val.to_list()?.iter().try_fold(Value::from_int(0), |acc, item| add(&acc, item))
+}
+
+#[inline(always)]
+pub fn product(val: &Value) -> ValueResult {
+    val.to_list()?.iter().try_fold(Value::from_int(1), |acc, item| multiply(&acc, item))
+}
+
+#[inline(always)]
+pub fn to_string(val: &Value) -> ValueResult {
+    Ok(Value::from_string(val.to_string()))
+}
+
+#[inline(always)]
+pub fn parse_number(val: &Value) -> ValueResult {
+    let text = val.to_str()?.trim();
+    if let Ok(i) = text.parse::<i64>() {
+        Ok(Value::from_int(i))
+    } else if let Ok(x) = text.parse::<f64>() {
+        Ok(Value::from_num(x))
+    } else {
+        Err(ValueError::from_string(ErrorKind::Domain, format!("Cannot parse '{}' as a number", text)))
+    }
+}
+
+#[inline(always)]
+pub fn uppercase(val: &Value) -> ValueResult {
+    Ok(Value::from_string(val.to_str()?.to_uppercase()))
+}
+
+#[inline(always)]
+pub fn lowercase(val: &Value) -> ValueResult {
+    Ok(Value::from_string(val.to_str()?.to_lowercase()))
+}
+
+// Interval accessors treat real numbers as single point intervals
+#[inline(always)]
+pub fn interval_lo(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_interval()?.lo()))
+}
+
+#[inline(always)]
+pub fn interval_hi(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_interval()?.hi()))
+}
+
+#[inline(always)]
+pub fn interval_mid(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_interval()?.mid()))
+}
+
+#[inline(always)]
+pub fn interval_width(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_interval()?.width()))
+}
+
+// Standard uncertainty, which is 0 for real numbers
+#[inline(always)]
+pub fn uncertain_sigma(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_uncertain()?.sigma()))
+}
+
+#[inline(always)]
+pub fn weekday(val: &Value) -> ValueResult {
+    Ok(Value::from_string(String::from(val.to_datetime()?.weekday())))
+}
+
+// Explicit conversion to decimal; floats convert from their shortest decimal representation
+#[inline(always)]
+pub fn decimal_cast(val: &Value) -> ValueResult {
+    match val {
+        Value::Num(x) => Ok(Value::from_decimal(Decimal::from_f64(*x)?)),
+        Value::Rational(r) => Ok(Value::from_decimal(Decimal::from_rational(r)?)),
+        Value::Str(s) => Ok(Value::from_decimal(Decimal::parse(s.trim())?)),
+        _ => Ok(Value::from_decimal(val.to_decimal()?))
+    }
+}
+
+#[inline(always)]
+pub fn vector(val: &Value) -> ValueResult {
+    Ok(Value::from_vector(val.to_vector()?))
+}
+
+#[inline(always)]
+pub fn matrix(val: &Value) -> ValueResult {
+    Ok(Value::from_matrix(val.to_matrix()?))
+}
+
+#[inline(always)]
+pub fn norm(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_vector()?.norm()))
+}
+
+#[inline(always)]
+pub fn transpose(val: &Value) -> ValueResult {
+    Ok(Value::from_matrix(val.to_matrix()?.transpose()))
+}
+
+#[inline(always)]
+pub fn determinant(val: &Value) -> ValueResult {
+    Ok(Value::from_num(val.to_matrix()?.det()?))
+}
+
+#[inline(always)]
+pub fn inverse(val: &Value) -> ValueResult {
+    Ok(Value::from_matrix(val.to_matrix()?.inv()?))
+}
+
+#[inline(always)]
+pub fn bit_not(val: &Value) -> ValueResult {
+    Ok(Value::from_int(word::from_bits(!word::pattern(val.to_int()?))))
+}
+
+#[inline(always)]
+pub fn popcount(val: &Value) -> ValueResult {
+    Ok(Value::from_int(word::pattern(val.to_int()?).count_ones() as i64))
+}
+
+// Leading and trailing zeros count within the current word
+#[inline(always)]
+pub fn leading_zeros(val: &Value) -> ValueResult {
+    let unused = 64 - word::context().word().bits();
+    Ok(Value::from_int((word::pattern(val.to_int()?).leading_zeros() - unused) as i64))
+}
+
+#[inline(always)]
+pub fn trailing_zeros(val: &Value) -> ValueResult {
+    let bits = word::context().word().bits();
+    Ok(Value::from_int(word::pattern(val.to_int()?).trailing_zeros().min(bits) as i64))
+}
+
+#[inline(always)]
+pub fn factorial(val: &Value) -> ValueResult {
+    let n = natural_operand(keywords::FACT, val)?;
+    int_result(keywords::FACT, n, |n| ntheory::factorial(n as u128).and_then(|f| i128::try_from(f).ok()))
+}
+
+#[inline(always)]
+pub fn is_prime(val: &Value) -> ValueResult {
+    let n = word::widen(val.to_int()?);
+    Ok(Value::from_bool(n > 1 && ntheory::is_prime(n as u64)))
+}
+
+// Smallest prime greater than val
+#[inline(always)]
+pub fn next_prime(val: &Value) -> ValueResult {
+    int_result(keywords::NEXTPRIME, val.to_int()?, |n| {
+        if n < 2 {
+            Some(2)
+        } else {
+            ntheory::next_prime(n as u64).map(i128::from)
+        }
+    })
+}
+
+// List of prime factors, repeated by multiplicity, e.g. factor 12 is [2, 2, 3]
+#[inline(always)]
+pub fn factor(val: &Value) -> ValueResult {
+    match word::widen(val.to_int()?) {
+        n if n > 0 => Ok(Value::from_list(
+            ntheory::factor(n as u64).into_iter().map(|p| Value::from_int(word::from_bits(p))).collect()
+        )),
+        n => Err(ValueError::from_string(
+            ErrorKind::Domain,
+            format!("Invalid operand - '{} {}'", keywords::FACTOR, n)
+        ))
+    }
+}
+
+#[inline(always)]
+pub fn gamma(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::GAMMA, u, special::gamma, |x| special::gamma(x) * special::digamma(x));
+    }
+    Ok(Value::from_num(special::gamma(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn log_gamma(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::LGAMMA, u, special::lgamma, special::digamma);
+    }
+    Ok(Value::from_num(special::lgamma(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn digamma(val: &Value) -> ValueResult {
+    Ok(Value::from_num(special::digamma(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn error_ftn(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ERF, u, special::erf, |x| consts::FRAC_2_SQRT_PI * (-x * x).exp());
+    }
+    Ok(Value::from_num(special::erf(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn error_ftn_complement(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ERFC, u, special::erfc, |x| -consts::FRAC_2_SQRT_PI * (-x * x).exp());
+    }
+    Ok(Value::from_num(special::erfc(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn inverse_error_ftn(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::ERFINV, u, special::erfinv, |y| {
+            let x = special::erfinv(y);
+            0.5 * consts::PI.sqrt() * (x * x).exp()
+        });
+    }
+    Ok(Value::from_num(special::erfinv(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn bessel_j0(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::BESSEL_J0, u, special::bessel_j0, |x| -special::bessel_j1(x));
+    }
+    Ok(Value::from_num(special::bessel_j0(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn bessel_j1(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::BESSEL_J1, u, special::bessel_j1, |x| {
+            special::bessel_j0(x) - special::bessel_j1(x) / x
+        });
+    }
+    Ok(Value::from_num(special::bessel_j1(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn bessel_y0(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::BESSEL_Y0, u, special::bessel_y0, |x| -special::bessel_y1(x));
+    }
+    Ok(Value::from_num(special::bessel_y0(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn bessel_y1(val: &Value) -> ValueResult {
+    if let Value::Uncertain(u) = val {
+        return uncertain_map(keywords::BESSEL_Y1, u, special::bessel_y1, |x| {
+            special::bessel_y0(x) - special::bessel_y1(x) / x
+        });
+    }
+    Ok(Value::from_num(special::bessel_y1(val.to_num()?)))
+}
+
+#[inline(always)]
+pub fn is_none(val: &Value) -> ValueResult {
+    Ok(Value::from_bool(val.is_none()))
+}
+
+#[inline(always)]
+pub fn is_nan(val: &Value) -> ValueResult {
+    match val {
+        Value::Num(n) => Ok(Value::from_bool(n.is_nan())),
+        Value::Complex(z) => Ok(Value::from_bool(z.is_nan())),
+        _ => exact_class(val, false)
+    }
+}
+
+#[inline(always)]
+pub fn is_inf(val: &Value) -> ValueResult {
+    match val {
+        Value::Num(n) => Ok(Value::from_bool(n.is_infinite())),
+        Value::Complex(z) => Ok(Value::from_bool(z.is_infinite())),
+        _ => exact_class(val, false)
+    }
+}
+
+#[inline(always)]
+pub fn is_finite(val: &Value) -> ValueResult {
+    match val {
+        Value::Num(n) => Ok(Value::from_bool(n.is_finite())),
+        Value::Complex(z) => Ok(Value::from_bool(z.is_finite())),
+        _ => exact_class(val, true)
+    }
+}
+
+// Exact numbers are never NaN or infinite; other values are not numbers at all.
+#[inline(always)]
+fn exact_class(val: &Value, finite: bool) -> ValueResult {
+    match val {
+        Value::Int(_) | Value::Rational(_) | Value::Decimal(_) => Ok(Value::from_bool(finite)),
+        _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a number", val)))
+    }
+}
+
+// Propagate the uncertainty of u through ftn, using its derivative.
+#[inline(always)]
+fn uncertain_map(op: &str, u: &Uncertain, ftn: fn(f64) -> f64, deriv: fn(f64) -> f64) -> ValueResult {
+    Ok(Value::from_uncertain(u.map(op, ftn, deriv)?))
+}
+
+// Use the complex function for complex values, and for reals outside the real function's domain.
+#[inline(always)]
+fn complex_aware(val: &Value, needs_complex: fn(f64) -> bool, real_ftn: fn(f64) -> f64, complex_ftn: fn(Complex64) -> Complex64) -> ValueResult {
+    match val {
+        Value::Complex(z) => Ok(Value::from_complex(complex_ftn(*z))),
+        _ => {
+            let x = val.to_num()?;
+            if needs_complex(x) {
+                Ok(Value::from_complex(complex_ftn(Complex64::new(x, 0.0))))
+            } else {
+                Ok(Value::from_num(real_ftn(x)))
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn natural_operand(op: &str, val: &Value) -> Result<i64, ValueError> {
+    match val.to_int()? {
+        i if word::widen(i) < 0 => Err(ValueError::from_string(ErrorKind::Domain, format!("Negative operand - '{} {}'", op, i))),
+        i => Ok(i)
+    }
+}
+
+// Integer arithmetic is exact in i128, and the result is fit to the word size
+#[inline(always)]
+fn int_result(op: &str, val: i64, ftn: fn(i128) -> Option<i128>) -> ValueResult {
+    let i = word::widen(val);
+    match ftn(i).and_then(word::fit) {
+        Some(result) => Ok(Value::from_int(result)),
+        None => Err(ValueError::from_string(ErrorKind::Domain, format!("Integer overflow - '{} {}'", op, i)))
+    }
+}
+
 // --------------------------------------------------------------------------------
 
 pub type UnaryFtn = fn(&Value) -> ValueResult;
@@ -175,6 +794,8 @@ pub fn uop2ftn(name: &str) -> Option<UnaryFtn> {
         keywords::ASINH => Some(trig_asinh),
         keywords::ACOSH => Some(trig_acosh),
         keywords::ATANH => Some(trig_atanh),
+        keywords::DEG2RAD => Some(deg_to_rad),
+        keywords::RAD2DEG => Some(rad_to_deg),
         keywords::SIGN => Some(sign),
         keywords::ABS => Some(absolute),
         keywords::RECIP => Some(reciprocal),
@@ -187,6 +808,68 @@ pub fn uop2ftn(name: &str) -> Option<UnaryFtn> {
         keywords::NOT => Some(logical_not),
         keywords::ASNUM => Some(num_cast),
         keywords::ASBOOL => Some(bool_cast),
+        keywords::RE => Some(real_part),
+        keywords::IM => Some(imaginary_part),
+        keywords::CONJ => Some(conjugate),
+        keywords::ARG => Some(argument),
+        keywords::CABS => Some(complex_absolute),
+        keywords::LEN => Some(length),
+        keywords::STR => Some(to_string),
+        keywords::PARSE => Some(parse_number),
+        keywords::UPPER => Some(uppercase),
+        keywords::LOWER => Some(lowercase),
+        keywords::SUM => Some(sum),
+        keywords::PROD => Some(product),
+        keywords::LO => Some(interval_lo),
+        keywords::HI => Some(interval_hi),
+        keywords::MID => Some(interval_mid),
+        keywords::WIDTH => Some(interval_width),
+        keywords::SIGMA => Some(uncertain_sigma),
+        keywords::WEEKDAY => Some(weekday),
+        keywords::DEC => Some(decimal_cast),
+        keywords::VEC => Some(vector),
+        keywords::MAT => Some(matrix),
+        keywords::NORM => Some(norm),
+        keywords::TRANSPOSE => Some(transpose),
+        keywords::DET => Some(determinant),
+        keywords::INV => Some(inverse),
+        keywords::ISNONE => Some(is_none),
+        keywords::ISNAN => Some(is_nan),
+        keywords::ISINF => Some(is_inf),
+        keywords::ISFINITE => Some(is_finite),
+        keywords::BNOT => Some(bit_not),
+        keywords::POPCNT => Some(popcount),
+        keywords::CLZ => Some(leading_zeros),
+        keywords::CTZ => Some(trailing_zeros),
+        keywords::FACT => Some(factorial),
+        keywords::ISPRIME => Some(is_prime),
+        keywords::NEXTPRIME => Some(next_prime),
+        keywords::FACTOR => Some(factor),
+        keywords::GAMMA => Some(gamma),
+        keywords::LGAMMA => Some(log_gamma),
+        keywords::DIGAMMA => Some(digamma),
+        keywords::ERF => Some(error_ftn),
+        keywords::ERFC => Some(error_ftn_complement),
+        keywords::ERFINV => Some(inverse_error_ftn),
+        keywords::BESSEL_J0 => Some(bessel_j0),
+        keywords::BESSEL_J1 => Some(bessel_j1),
+        keywords::BESSEL_Y0 => Some(bessel_y0),
+        keywords::BESSEL_Y1 => Some(bessel_y1),
+        _ => None
+    }
+}
+
+pub type AngleUnaryFtn = fn(&Value, Angle) -> ValueResult;
+
+// Trig ops that depend on the angle mode, used instead of uop2ftn's when not in radians
+pub fn uop2angle_ftn(name: &str) -> Option<AngleUnaryFtn> {
+    match name {
+        keywords::SIN => Some(angle_sin),
+        keywords::COS => Some(angle_cos),
+        keywords::TAN => Some(angle_tan),
+        keywords::ASIN => Some(angle_asin),
+        keywords::ACOS => Some(angle_acos),
+        keywords::ATAN => Some(angle_atan),
         _ => None
     }
 }
@@ -196,6 +879,10 @@ pub fn uop2ftn(name: &str) -> Option<UnaryFtn> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pcalc_datetime::{DateTime, Duration};
+    use crate::pcalc_interval::Interval;
+    use crate::pcalc_uncertain::Uncertain;
+    use crate::pcalc_units::{Quantity, Unit};
 
     fn check_equal(lhs: Value, rhs: f64) -> bool {
         (lhs.to_num().unwrap() - rhs).abs() < 0.0001
@@ -215,7 +902,7 @@ mod tests {
         let v1 = Value::from_num(1.0);
         let v2 = Value::from_num(2.0);
         assert!(check_equal(exponential(&v0).unwrap(), 1.0));
-        assert!(check_equal(exponential(&v1).unwrap(), 2.7182));
+        assert!(check_equal(exponential(&v1).unwrap(), consts::E));
         assert!(check_equal(exponential(&v2).unwrap(), 7.3890));
     }
 
@@ -234,7 +921,7 @@ mod tests {
         let v1 = Value::from_num(1.0);
         let v2 = Value::from_num(2.0);
         assert!(check_equal(natural_logarithm(&v1).unwrap(), 0.0));
-        assert!(check_equal(natural_logarithm(&v2).unwrap(), 0.6931));
+        assert!(check_equal(natural_logarithm(&v2).unwrap(), consts::LN_2));
     }
 
     #[test]
@@ -256,8 +943,8 @@ mod tests {
     #[test]
     fn test_unop_trig_sin() {
         let v0 = Value::from_num(0.0);
-        let half_pi = Value::from_num(1.5707963267948966);
-        let pi = Value::from_num(3.141592653589793);
+        let half_pi = Value::from_num(consts::FRAC_PI_2);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_sin(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_sin(&half_pi).unwrap(), 1.0));
         assert!(check_equal(trig_sin(&pi).unwrap(), 0.0));
@@ -266,8 +953,8 @@ mod tests {
     #[test]
     fn test_unop_trig_cos() {
         let v0 = Value::from_num(0.0);
-        let half_pi = Value::from_num(1.5707963267948966);
-        let pi = Value::from_num(3.141592653589793);
+        let half_pi = Value::from_num(consts::FRAC_PI_2);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_cos(&v0).unwrap(), 1.0));
         assert!(check_equal(trig_cos(&half_pi).unwrap(), 0.0));
         assert!(check_equal(trig_cos(&pi).unwrap(), -1.0));
@@ -276,8 +963,8 @@ mod tests {
     #[test]
     fn test_unop_trig_tan() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
-        let pi = Value::from_num(3.141592653589793);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_tan(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_tan(&quarter_pi).unwrap(), 1.0));
         assert!(check_equal(trig_tan(&pi).unwrap(), 0.0));
@@ -286,8 +973,8 @@ mod tests {
     #[test]
     fn test_unop_trig_sinh() {
         let v0 = Value::from_num(0.0);
-        let half_pi = Value::from_num(1.5707963267948966);
-        let pi = Value::from_num(3.141592653589793);
+        let half_pi = Value::from_num(consts::FRAC_PI_2);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_sinh(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_sinh(&half_pi).unwrap(), 2.3012));
         assert!(check_equal(trig_sinh(&pi).unwrap(), 11.5487));
@@ -296,8 +983,8 @@ mod tests {
     #[test]
     fn test_unop_trig_cosh() {
         let v0 = Value::from_num(0.0);
-        let half_pi = Value::from_num(1.5707963267948966);
-        let pi = Value::from_num(3.141592653589793);
+        let half_pi = Value::from_num(consts::FRAC_PI_2);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_cosh(&v0).unwrap(), 1.0));
         assert!(check_equal(trig_cosh(&half_pi).unwrap(), 2.5091));
         assert!(check_equal(trig_cosh(&pi).unwrap(), 11.5919));
@@ -306,19 +993,19 @@ mod tests {
     #[test]
     fn test_unop_trig_asin() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
         let v1 = Value::from_num(1.0);
         assert!(check_equal(trig_asin(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_asin(&quarter_pi).unwrap(), 0.9033));
-        assert!(check_equal(trig_asin(&v1).unwrap(), 1.5707));
+        assert!(check_equal(trig_asin(&v1).unwrap(), consts::FRAC_PI_2));
     }
 
     #[test]
     fn test_unop_trig_acos() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
         let v1 = Value::from_num(1.0);
-        assert!(check_equal(trig_acos(&v0).unwrap(), 1.5707));
+        assert!(check_equal(trig_acos(&v0).unwrap(), consts::FRAC_PI_2));
         assert!(check_equal(trig_acos(&quarter_pi).unwrap(), 0.6674));
         assert!(check_equal(trig_acos(&v1).unwrap(), 0.0));
     }
@@ -326,17 +1013,17 @@ mod tests {
     #[test]
     fn test_unop_trig_atan() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
         let v1 = Value::from_num(1.0);
         assert!(check_equal(trig_atan(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_atan(&quarter_pi).unwrap(), 0.6657));
-        assert!(check_equal(trig_atan(&v1).unwrap(), 0.7853));
+        assert!(check_equal(trig_atan(&v1).unwrap(), consts::FRAC_PI_4));
     }
 
     #[test]
     fn test_unop_trig_asinh() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
         let v1 = Value::from_num(1.0);
         assert!(check_equal(trig_asinh(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_asinh(&quarter_pi).unwrap(), 0.7212));
@@ -346,8 +1033,8 @@ mod tests {
     #[test]
     fn test_unop_trig_acosh() {
         let v1 = Value::from_num(1.0);
-        let half_pi = Value::from_num(1.5707963267948966);
-        let pi = Value::from_num(3.141592653589793);
+        let half_pi = Value::from_num(consts::FRAC_PI_2);
+        let pi = Value::from_num(consts::PI);
         assert!(check_equal(trig_acosh(&v1).unwrap(), 0.0));
         assert!(check_equal(trig_acosh(&half_pi).unwrap(), 1.0232));
         assert!(check_equal(trig_acosh(&pi).unwrap(), 1.8115));
@@ -356,11 +1043,35 @@ mod tests {
     #[test]
     fn test_unop_trig_atanh() {
         let v0 = Value::from_num(0.0);
-        let quarter_pi = Value::from_num(0.7853981633974483);
+        let quarter_pi = Value::from_num(consts::FRAC_PI_4);
         assert!(check_equal(trig_atanh(&v0).unwrap(), 0.0));
         assert!(check_equal(trig_atanh(&quarter_pi).unwrap(), 1.0593));
     }
 
+    #[test]
+    fn test_unop_angle() {
+        let deg = Angle::Degrees;
+        assert_eq!(angle_sin(&Value::from_int(30), deg).unwrap(), Value::from_num(0.5));
+        assert_eq!(angle_cos(&Value::from_num(90.0), deg).unwrap(), Value::from_num(0.0));
+        assert_eq!(angle_tan(&Value::from_num(45.0), deg).unwrap(), Value::from_num(1.0));
+        assert_eq!(angle_sin(&Value::from_num(100.0), Angle::Gradians).unwrap(), Value::from_num(1.0));
+        assert_eq!(angle_acos(&Value::from_num(-1.0), deg).unwrap(), Value::from_num(180.0));
+        assert_eq!(angle_atan(&Value::from_num(1.0), deg).unwrap(), Value::from_num(45.0));
+        assert_eq!(angle_asin(&Value::from_num(1.0), Angle::Gradians).unwrap(), Value::from_num(100.0));
+        let ival = angle_sin(&Value::from_interval(Interval::new(0.0, 90.0).unwrap()), deg).unwrap();
+        assert!(check_equal(interval_hi(&ival).unwrap(), 1.0));
+        assert!(angle_sin(&Value::from_bool(true), deg).is_err());
+
+        assert_eq!(deg_to_rad(&Value::from_int(180)).unwrap(), Value::from_num(consts::PI));
+        assert_eq!(rad_to_deg(&Value::from_num(consts::FRAC_PI_2)).unwrap(), Value::from_num(90.0));
+        assert!(deg_to_rad(&Value::from_string(String::from("x"))).is_err());
+
+        // Operands that aren't angles report themselves, not a scaled copy
+        let metre = Value::from_quantity(Quantity::new(1.0, Unit::parse("m").unwrap()));
+        assert_eq!(format!("{}", angle_sin(&metre, deg).unwrap_err()), "1 m not a number");
+        assert_eq!(format!("{}", rad_to_deg(&metre).unwrap_err()), "1 m not a number");
+    }
+
     #[test]
     fn test_unop_sign() {
         let v0 = Value::from_num(0.0);
@@ -445,6 +1156,105 @@ mod tests {
         assert_eq!(logical_not(&no).unwrap(), yes);
     }
 
+    #[test]
+    fn test_unop_int() {
+        let five = Value::from_int(5);
+        let minus5 = Value::from_int(-5);
+        for ftn in [truncate, ceiling, floor, round] {
+            assert!(ftn(&five).unwrap().is_int());
+            assert_eq!(ftn(&five).unwrap(), five);
+        }
+        assert_eq!(absolute(&minus5).unwrap(), five);
+        assert!(absolute(&minus5).unwrap().is_int());
+        assert_eq!(negate(&five).unwrap(), minus5);
+        assert!(negate(&five).unwrap().is_int());
+        assert_eq!(sign(&minus5).unwrap(), Value::from_int(-1));
+        assert!(sign(&minus5).unwrap().is_int());
+        assert_eq!(fraction(&five).unwrap(), Value::from_int(0));
+        assert!(check_equal(square_root(&Value::from_int(16)).unwrap(), 4.0));
+
+        assert!(negate(&Value::from_int(i64::MIN)).is_err());
+        assert!(absolute(&Value::from_int(i64::MIN)).is_err());
+    }
+
+    #[test]
+    fn test_unop_rational() {
+        let rat = |n: i64, d: i64| Value::from_rational(BigRational::new(n.into(), d.into()));
+        let minus7_2 = rat(-7, 2);
+        assert_eq!(truncate(&minus7_2).unwrap(), rat(-3, 1));
+        assert_eq!(ceiling(&minus7_2).unwrap(), rat(-3, 1));
+        assert_eq!(floor(&minus7_2).unwrap(), rat(-4, 1));
+        assert_eq!(round(&minus7_2).unwrap(), rat(-4, 1));
+        assert_eq!(fraction(&minus7_2).unwrap(), rat(-1, 2));
+        assert_eq!(absolute(&minus7_2).unwrap(), rat(7, 2));
+        assert_eq!(negate(&minus7_2).unwrap(), rat(7, 2));
+        assert_eq!(sign(&minus7_2).unwrap(), rat(-1, 1));
+        assert_eq!(reciprocal(&minus7_2).unwrap(), rat(-2, 7));
+        assert!(reciprocal(&rat(0, 1)).is_err());
+        for ftn in [truncate, ceiling, floor, round, fraction, absolute, negate, sign, reciprocal, num_cast] {
+            assert!(ftn(&minus7_2).unwrap().is_rational());
+        }
+
+        // Transcendental ops fall back to f64
+        let sqrt4 = square_root(&rat(4, 1)).unwrap();
+        assert!(!sqrt4.is_rational());
+        assert!(check_equal(sqrt4, 2.0));
+        assert!(check_equal(natural_logarithm(&rat(1, 1)).unwrap(), 0.0));
+    }
+
+    #[test]
+    fn test_unop_complex() {
+        let check_complex = |val: Value, re: f64, im: f64| (val.to_complex().unwrap() - Complex64::new(re, im)).norm() < 0.0001;
+        let z = Value::from_complex(Complex64::new(3.0, 4.0));
+        let minus1 = Value::from_int(-1);
+
+        assert!(check_complex(square_root(&minus1).unwrap(), 0.0, 1.0));
+        assert!(check_complex(square_root(&Value::from_complex(Complex64::new(-3.0, 4.0))).unwrap(), 1.0, 2.0));
+        assert!(check_complex(natural_logarithm(&minus1).unwrap(), 0.0, consts::PI));
+        assert!(check_complex(logarithm10(&Value::from_num(-100.0)).unwrap(), 2.0, consts::PI / consts::LN_10));
+        assert!(check_complex(
+            exponential(&Value::from_complex(Complex64::new(0.0, consts::PI))).unwrap(),
+            -1.0,
+            0.0
+        ));
+        assert!(check_complex(trig_asin(&Value::from_int(2)).unwrap(), consts::FRAC_PI_2, -1.3169));
+        assert!(check_complex(trig_acosh(&Value::from_int(0)).unwrap(), 0.0, consts::FRAC_PI_2));
+        assert!(check_complex(trig_sin(&Value::from_complex(Complex64::new(0.0, 1.0))).unwrap(), 0.0, 1.1752));
+        assert!(check_complex(reciprocal(&z).unwrap(), 0.12, -0.16));
+        assert!(check_complex(negate(&z).unwrap(), -3.0, -4.0));
+
+        // Real results stay real in the real domain
+        assert!(!square_root(&Value::from_int(4)).unwrap().is_complex());
+        assert!(natural_logarithm(&Value::from_int(0)).unwrap().to_num().unwrap().is_infinite());
+
+        assert_eq!(absolute(&z).unwrap(), Value::from_num(5.0));
+        assert!(floor(&z).is_err());
+        assert!(sign(&z).is_err());
+    }
+
+    #[test]
+    fn test_unop_complex_parts() {
+        let z = Value::from_complex(Complex64::new(3.0, -4.0));
+        let minus2 = Value::from_int(-2);
+
+        assert_eq!(real_part(&z).unwrap(), Value::from_num(3.0));
+        assert_eq!(imaginary_part(&z).unwrap(), Value::from_num(-4.0));
+        assert_eq!(conjugate(&z).unwrap(), Value::from_complex(Complex64::new(3.0, 4.0)));
+        assert!(check_equal(argument(&z).unwrap(), -0.9273));
+        assert_eq!(complex_absolute(&z).unwrap(), Value::from_num(5.0));
+
+        assert_eq!(real_part(&minus2).unwrap(), Value::from_num(-2.0));
+        assert_eq!(imaginary_part(&minus2).unwrap(), Value::from_num(0.0));
+        assert_eq!(conjugate(&minus2).unwrap(), minus2);
+        assert!(check_equal(argument(&minus2).unwrap(), consts::PI));
+        assert_eq!(complex_absolute(&minus2).unwrap(), Value::from_num(2.0));
+
+        let yes = Value::from_bool(true);
+        for ftn in [real_part, imaginary_part, conjugate, argument, complex_absolute] {
+            assert!(ftn(&yes).is_err());
+        }
+    }
+
     #[test]
     fn test_type_cast() {
         let one = Value::from_num(1.0);
@@ -456,10 +1266,352 @@ mod tests {
         assert_eq!(num_cast(&zero).unwrap(), zero);
         assert_eq!(num_cast(&yes).unwrap(), one);
         assert_eq!(num_cast(&no).unwrap(), zero);
+        assert!(num_cast(&yes).unwrap().is_int());
+        assert!(num_cast(&Value::from_int(3)).unwrap().is_int());
 
         assert_eq!(bool_cast(&one).unwrap(), yes);
         assert_eq!(bool_cast(&zero).unwrap(), no);
         assert_eq!(bool_cast(&yes).unwrap(), yes);
         assert_eq!(bool_cast(&no).unwrap(), no);
+        assert_eq!(bool_cast(&Value::from_int(3)).unwrap(), yes);
+        assert_eq!(bool_cast(&Value::from_int(0)).unwrap(), no);
+    }
+
+    #[test]
+    fn test_unop_string() {
+        let s = |text: &str| Value::from_string(String::from(text));
+
+        assert_eq!(length(&s("hello")).unwrap(), Value::from_int(5));
+        assert_eq!(length(&s("")).unwrap(), Value::from_int(0));
+        assert_eq!(length(&s("héllo")).unwrap(), Value::from_int(5));
+        assert_eq!(format!("{}", length(&Value::from_int(5)).unwrap_err()), "5 not a string or list");
+
+        assert_eq!(to_string(&Value::from_int(42)).unwrap(), s("42"));
+        assert_eq!(to_string(&Value::from_num(2.5)).unwrap(), s("2.5"));
+        assert_eq!(to_string(&Value::from_bool(true)).unwrap(), s("true"));
+        assert_eq!(to_string(&s("abc")).unwrap(), s("abc"));
+
+        assert_eq!(parse_number(&s("42")).unwrap(), Value::from_int(42));
+        assert!(parse_number(&s("42")).unwrap().is_int());
+        assert_eq!(parse_number(&s(" -2.5 ")).unwrap(), Value::from_num(-2.5));
+        assert_eq!(format!("{}", parse_number(&s("abc")).unwrap_err()), "Cannot parse 'abc' as a number");
+        assert!(parse_number(&Value::from_int(1)).is_err());
+        assert_eq!(num_cast(&s("7")).unwrap(), Value::from_int(7));
+
+        assert_eq!(uppercase(&s("Hello")).unwrap(), s("HELLO"));
+        assert_eq!(lowercase(&s("Hello")).unwrap(), s("hello"));
+        assert!(uppercase(&Value::from_num(1.0)).is_err());
+        assert!(lowercase(&Value::from_bool(false)).is_err());
+
+        assert_eq!(bool_cast(&s("x")).unwrap(), Value::from_bool(true));
+        assert_eq!(bool_cast(&s("")).unwrap(), Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_unop_list() {
+        let ints = |v: Vec<i64>| Value::from_list(v.into_iter().map(Value::from_int).collect());
+
+        assert_eq!(length(&ints(vec![1, 2, 3])).unwrap(), Value::from_int(3));
+        assert_eq!(length(&ints(vec![])).unwrap(), Value::from_int(0));
+
+        assert_eq!(sum(&ints(vec![1, 2, 3])).unwrap(), Value::from_int(6));
+        assert_eq!(sum(&ints(vec![])).unwrap(), Value::from_int(0));
+        assert_eq!(
+            sum(&Value::from_list(vec![Value::from_int(1), Value::from_num(0.5)])).unwrap(),
+            Value::from_num(1.5)
+        );
+        assert_eq!(product(&ints(vec![2, 3, 4])).unwrap(), Value::from_int(24));
+        assert_eq!(product(&ints(vec![])).unwrap(), Value::from_int(1));
+
+        assert!(sum(&Value::from_int(1)).is_err());
+        assert!(sum(&Value::from_list(vec![Value::from_bool(true)])).is_err());
+        assert!(product(&ints(vec![i64::MAX, 2])).is_err());
+    }
+
+    #[test]
+    fn test_unop_quantity() {
+        let qty = |magnitude: f64, unit: &str| Value::from_quantity(Quantity::new(magnitude, Unit::parse(unit).unwrap()));
+
+        assert_eq!(square_root(&qty(9.0, "m^2")).unwrap().to_string(), "3 m");
+        assert!(square_root(&qty(9.0, "m")).is_err());
+        assert_eq!(absolute(&qty(-2.0, "km")).unwrap().to_string(), "2 km");
+        assert_eq!(negate(&qty(2.0, "km")).unwrap().to_string(), "-2 km");
+        assert_eq!(sign(&qty(-2.0, "km")).unwrap(), Value::from_num(-1.0));
+        assert_eq!(num_cast(&qty(2.5, "km")).unwrap(), Value::from_num(2.5));
+        assert_eq!(bool_cast(&qty(0.0, "m")).unwrap(), Value::from_bool(false));
+
+        assert_eq!(format!("{}", trig_sin(&qty(1.0, "m")).unwrap_err()), "1 m not a number");
+        assert!(exponential(&qty(1.0, "s")).is_err());
+    }
+
+    #[test]
+    fn test_unop_interval() {
+        let ival = |lo: f64, hi: f64| Value::from_interval(Interval::new(lo, hi).unwrap());
+        let i = ival(1.0, 3.0);
+
+        assert_eq!(interval_lo(&i).unwrap(), Value::from_num(1.0));
+        assert_eq!(interval_hi(&i).unwrap(), Value::from_num(3.0));
+        assert_eq!(interval_mid(&i).unwrap(), Value::from_num(2.0));
+        assert_eq!(interval_width(&i).unwrap(), Value::from_num(2.0));
+        assert_eq!(interval_lo(&Value::from_int(5)).unwrap(), Value::from_num(5.0));
+        assert_eq!(interval_width(&Value::from_int(5)).unwrap(), Value::from_num(0.0));
+        assert!(interval_mid(&Value::from_bool(true)).is_err());
+
+        assert_eq!(square_root(&ival(4.0, 9.0)).unwrap(), ival(2.0, 3.0));
+        assert!(square_root(&ival(-4.0, 9.0)).is_err());
+        assert!(exponential(&ival(0.0, 1.0)).unwrap().to_interval().unwrap().contains(consts::E));
+        assert!(natural_logarithm(&ival(1.0, 2.0)).unwrap().to_interval().unwrap().contains(consts::LN_2));
+        assert!(natural_logarithm(&ival(-1.0, 2.0)).is_err());
+        assert_eq!(trig_sin(&ival(0.0, 4.0)).unwrap().to_interval().unwrap().hi(), 1.0);
+        assert_eq!(trig_cos(&ival(3.0, 4.0)).unwrap().to_interval().unwrap().lo(), -1.0);
+        assert_eq!(negate(&i).unwrap(), ival(-3.0, -1.0));
+        assert_eq!(absolute(&ival(-2.0, 1.0)).unwrap(), ival(0.0, 2.0));
+        assert!(trig_tan(&i).is_err());
+    }
+
+    #[test]
+    fn test_unop_datetime() {
+        let date = |text: &str| Value::from_datetime(DateTime::parse(text).unwrap());
+        let dur = |secs: i64| Value::from_duration(Duration::new(secs));
+
+        assert_eq!(weekday(&date("2024-03-15")).unwrap(), Value::from_string(String::from("Friday")));
+        assert_eq!(weekday(&date("1970-01-01T12:00")).unwrap(), Value::from_string(String::from("Thursday")));
+        assert_eq!(format!("{}", weekday(&Value::from_int(1)).unwrap_err()), "1 not a date");
+
+        assert_eq!(num_cast(&dur(274_320)).unwrap(), Value::from_int(274_320));
+        assert_eq!(num_cast(&date("1970-01-02")).unwrap(), Value::from_int(86_400));
+        assert_eq!(negate(&dur(90)).unwrap(), dur(-90));
+        assert_eq!(absolute(&dur(-90)).unwrap(), dur(90));
+        assert_eq!(to_string(&dur(90)).unwrap(), Value::from_string(String::from("1m 30s")));
+        assert!(negate(&date("2024-03-15")).is_err());
+    }
+
+    #[test]
+    fn test_unop_uncertain() {
+        let uval = |x: f64, sigma: f64| Value::from_uncertain(Uncertain::new(x, sigma).unwrap());
+        let sigma = |v: ValueResult| v.unwrap().to_uncertain().unwrap().sigma();
+        let approx = |x: f64, y: f64| (x - y).abs() < 1e-12;
+        let x = uval(0.5, 0.01);
+
+        assert_eq!(uncertain_sigma(&x).unwrap(), Value::from_num(0.01));
+        assert_eq!(uncertain_sigma(&Value::from_int(5)).unwrap(), Value::from_num(0.0));
+        assert!(uncertain_sigma(&Value::from_bool(true)).is_err());
+
+        // Sigma scales by the absolute derivative
+        assert!(approx(sigma(square_root(&uval(4.0, 0.2))), 0.05));
+        assert!(approx(sigma(exponential(&x)), 0.5_f64.exp() * 0.01));
+        assert!(approx(sigma(exponential2(&x)), 0.5_f64.exp2() * consts::LN_2 * 0.01));
+        assert!(approx(sigma(natural_logarithm(&x)), 0.02));
+        assert!(approx(sigma(logarithm2(&x)), 0.02 / consts::LN_2));
+        assert!(approx(sigma(logarithm10(&x)), 0.02 / consts::LN_10));
+        assert!(approx(sigma(trig_sin(&x)), 0.5_f64.cos() * 0.01));
+        assert!(approx(sigma(trig_cos(&x)), 0.5_f64.sin() * 0.01));
+        assert!(approx(sigma(trig_tan(&x)), 0.01 / 0.5_f64.cos().powi(2)));
+        assert!(approx(sigma(trig_sinh(&x)), 0.5_f64.cosh() * 0.01));
+        assert!(approx(sigma(trig_cosh(&x)), 0.5_f64.sinh() * 0.01));
+        assert!(approx(sigma(trig_tanh(&x)), (1.0 - 0.5_f64.tanh().powi(2)) * 0.01));
+        assert!(approx(sigma(trig_asin(&x)), 0.01 / 0.75_f64.sqrt()));
+        assert!(approx(sigma(trig_acos(&x)), 0.01 / 0.75_f64.sqrt()));
+        assert!(approx(sigma(trig_atan(&x)), 0.008));
+        assert!(approx(sigma(trig_asinh(&x)), 0.01 / 1.25_f64.sqrt()));
+        assert!(approx(sigma(trig_acosh(&uval(2.0, 0.01))), 0.01 / 3.0_f64.sqrt()));
+        assert!(approx(sigma(trig_atanh(&x)), 0.01 / 0.75));
+        assert!(approx(sigma(reciprocal(&x)), 0.04));
+        assert!(approx(sigma(absolute(&uval(-0.5, 0.01))), 0.01));
+        assert!(approx(sigma(negate(&x)), 0.01));
+        assert!(approx(sigma(fraction(&uval(2.5, 0.01))), 0.01));
+        assert_eq!(sigma(round(&x)), 0.0);
+        assert_eq!(floor(&uval(2.5, 0.01)).unwrap().to_string(), "2 ± 0");
+
+        assert_eq!(
+            format!("{}", natural_logarithm(&uval(-1.0, 0.1)).unwrap_err()),
+            "Uncertain value outside ln domain - '-1 ± 0.1'"
+        );
+        assert!(trig_asin(&uval(2.0, 0.1)).is_err());
+
+        assert_eq!(sign(&uval(-2.0, 0.1)).unwrap(), Value::from_num(-1.0));
+        assert_eq!(num_cast(&x).unwrap(), Value::from_num(0.5));
+        assert_eq!(real_part(&x).unwrap().to_string(), "0.5 ± 0.01");
+        assert_eq!(imaginary_part(&x).unwrap(), Value::from_num(0.0));
+        assert_eq!(conjugate(&x).unwrap().to_string(), "0.5 ± 0.01");
+        assert_eq!(argument(&uval(-2.0, 0.1)).unwrap(), Value::from_num(consts::PI));
+        assert_eq!(complex_absolute(&uval(-2.0, 0.1)).unwrap().to_string(), "2 ± 0.1");
+        assert_eq!(to_string(&x).unwrap(), Value::from_string(String::from("0.5 ± 0.01")));
+    }
+
+    #[test]
+    fn test_unop_decimal() {
+        use crate::pcalc_decimal::{with_context, DecimalContext, Rounding};
+
+        let dec = |text: &str| Value::from_decimal(Decimal::parse(text).unwrap());
+
+        assert_eq!(sign(&dec("-2.5")).unwrap(), dec("-1"));
+        assert_eq!(absolute(&dec("-2.5")).unwrap(), dec("2.5"));
+        assert_eq!(negate(&dec("2.5")).unwrap(), dec("-2.5"));
+        assert_eq!(reciprocal(&dec("4")).unwrap().to_string(), "0.25");
+        assert_eq!(format!("{}", reciprocal(&dec("0")).unwrap_err()), "Division by zero");
+        assert_eq!(fraction(&dec("-2.75")).unwrap().to_string(), "-0.75");
+        assert_eq!(truncate(&dec("-2.75")).unwrap().to_string(), "-2");
+        assert_eq!(ceiling(&dec("2.25")).unwrap().to_string(), "3");
+        assert_eq!(floor(&dec("-2.25")).unwrap().to_string(), "-3");
+        assert_eq!(round(&dec("2.5")).unwrap().to_string(), "2");
+        assert_eq!(round(&dec("3.5")).unwrap().to_string(), "4");
+
+        with_context(DecimalContext::new(Some(2), Rounding::HalfUp).unwrap(), || {
+            assert_eq!(round(&dec("2.345")).unwrap().to_string(), "2.35");
+        });
+
+        assert_eq!(num_cast(&dec("19.99")).unwrap(), Value::from_num(19.99));
+        // Ops without a decimal version compute in floating point
+        assert_eq!(square_root(&dec("4")).unwrap(), Value::from_num(2.0));
+        assert_eq!(trig_sin(&dec("0")).unwrap(), Value::from_num(0.0));
+
+        assert_eq!(decimal_cast(&Value::from_num(0.1)).unwrap().to_string(), "0.1");
+        assert_eq!(decimal_cast(&Value::from_int(42)).unwrap().to_string(), "42");
+        assert_eq!(
+            decimal_cast(&Value::from_rational(BigRational::new(1.into(), 8.into()))).unwrap().to_string(),
+            "0.125"
+        );
+        assert_eq!(decimal_cast(&Value::from_string(String::from("19.99"))).unwrap().to_string(), "19.99");
+        assert_eq!(decimal_cast(&dec("1.5")).unwrap().to_string(), "1.5");
+        assert_eq!(
+            format!("{}", decimal_cast(&Value::from_string(String::from("abc"))).unwrap_err()),
+            "Invalid decimal - 'abc'"
+        );
+        assert!(decimal_cast(&Value::from_num(f64::NAN)).is_err());
+        assert!(decimal_cast(&Value::from_bool(true)).is_err());
+    }
+
+    #[test]
+    fn test_unop_vector_matrix() {
+        let list = |items: &[f64]| Value::from_list(items.iter().map(|x| Value::from_num(*x)).collect());
+        let rows = |rows: &[&[f64]]| Value::from_list(rows.iter().map(|row| list(row)).collect());
+
+        let v = vector(&list(&[3.0, 4.0])).unwrap();
+        assert!(v.is_vector());
+        assert_eq!(v.to_string(), "(3, 4)");
+        assert_eq!(norm(&v).unwrap(), Value::from_num(5.0));
+        assert_eq!(norm(&list(&[1.0, 2.0, 2.0])).unwrap(), Value::from_num(3.0));
+        assert_eq!(negate(&v).unwrap().to_string(), "(-3, -4)");
+        assert_eq!(length(&v).unwrap(), Value::from_int(2));
+        assert!(vector(&Value::from_int(1)).is_err());
+
+        let m = matrix(&rows(&[&[1.0, 2.0], &[3.0, 4.0]])).unwrap();
+        assert!(m.is_matrix());
+        assert_eq!(m.to_string(), "[1  2]\n[3  4]");
+        assert_eq!(transpose(&m).unwrap().to_string(), "[1  3]\n[2  4]");
+        assert_eq!(determinant(&m).unwrap(), Value::from_num(-2.0));
+        assert_eq!(inverse(&m).unwrap().to_string(), "[ -2     1]\n[1.5  -0.5]");
+        assert_eq!(negate(&m).unwrap().to_string(), "[-1  -2]\n[-3  -4]");
+        assert_eq!(determinant(&rows(&[&[2.0]])).unwrap(), Value::from_num(2.0));
+
+        let rect = rows(&[&[1.0, 2.0, 3.0]]);
+        assert_eq!(transpose(&rect).unwrap().to_string(), "[1]\n[2]\n[3]");
+        assert_eq!(format!("{}", determinant(&rect).unwrap_err()), "det requires a square matrix - 1x3 matrix");
+        assert_eq!(
+            format!("{}", inverse(&rows(&[&[1.0, 2.0], &[2.0, 4.0]])).unwrap_err()),
+            "Singular matrix for inv"
+        );
+        assert!(matrix(&list(&[1.0, 2.0])).is_err());
+    }
+
+    #[test]
+    fn test_unop_bitwise() {
+        use crate::pcalc_word::{with_context, Overflow, WordContext, WordSize};
+
+        let int = Value::from_int;
+        assert_eq!(bit_not(&int(0)).unwrap(), int(-1));
+        assert_eq!(popcount(&int(0xFF)).unwrap(), int(8));
+        assert_eq!(popcount(&int(-1)).unwrap(), int(64));
+        assert_eq!(leading_zeros(&int(1)).unwrap(), int(63));
+        assert_eq!(trailing_zeros(&int(8)).unwrap(), int(3));
+        assert_eq!(trailing_zeros(&int(0)).unwrap(), int(64));
+        assert!(bit_not(&Value::from_num(0.5)).is_err());
+
+        with_context(WordContext::new(Some(WordSize::U8), Overflow::Check), || {
+            assert_eq!(bit_not(&int(0)).unwrap(), int(255));
+            assert_eq!(popcount(&int(-1)).unwrap(), int(8));
+            assert_eq!(leading_zeros(&int(1)).unwrap(), int(7));
+            assert_eq!(trailing_zeros(&int(0)).unwrap(), int(8));
+            assert_eq!(format!("{}", negate(&int(1)).unwrap_err()), "Integer overflow - 'neg 1'");
+        });
+
+        with_context(WordContext::new(Some(WordSize::U8), Overflow::Wrap), || {
+            assert_eq!(negate(&int(1)).unwrap(), int(255));
+        });
+
+        with_context(WordContext::new(Some(WordSize::I16), Overflow::Check), || {
+            assert_eq!(bit_not(&int(0)).unwrap(), int(-1));
+            assert!(absolute(&int(-32768)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_unop_is_nan_inf_finite() {
+        assert_eq!(is_nan(&Value::from_num(f64::NAN)).unwrap(), Value::from_bool(true));
+        assert_eq!(is_nan(&Value::from_num(f64::INFINITY)).unwrap(), Value::from_bool(false));
+        assert_eq!(is_nan(&Value::from_int(1)).unwrap(), Value::from_bool(false));
+        assert_eq!(is_inf(&Value::from_num(f64::NEG_INFINITY)).unwrap(), Value::from_bool(true));
+        assert_eq!(is_inf(&Value::from_num(1.0)).unwrap(), Value::from_bool(false));
+        assert_eq!(
+            is_inf(&Value::from_complex(Complex64::new(f64::INFINITY, 0.0))).unwrap(),
+            Value::from_bool(true)
+        );
+        assert_eq!(is_finite(&Value::from_num(2.5)).unwrap(), Value::from_bool(true));
+        assert_eq!(is_finite(&Value::from_num(f64::NAN)).unwrap(), Value::from_bool(false));
+        assert_eq!(is_finite(&Value::from_int(i64::MAX)).unwrap(), Value::from_bool(true));
+        assert_eq!(format!("{}", is_finite(&Value::from_bool(true)).unwrap_err()), "true not a number");
+    }
+
+    #[test]
+    fn test_unop_number_theory() {
+        let int = Value::from_int;
+        assert_eq!(factorial(&int(0)).unwrap(), int(1));
+        assert_eq!(factorial(&int(20)).unwrap(), int(2432902008176640000));
+        assert_eq!(format!("{}", factorial(&int(21)).unwrap_err()), "Integer overflow - 'fact 21'");
+        assert_eq!(format!("{}", factorial(&int(-1)).unwrap_err()), "Negative operand - 'fact -1'");
+        assert_eq!(factorial(&Value::from_num(0.5)).unwrap_err().kind(), ErrorKind::TypeMismatch);
+
+        assert_eq!(is_prime(&int(97)).unwrap(), Value::from_bool(true));
+        assert_eq!(is_prime(&int(91)).unwrap(), Value::from_bool(false));
+        assert_eq!(is_prime(&int(-7)).unwrap(), Value::from_bool(false));
+        assert_eq!(next_prime(&int(-5)).unwrap(), int(2));
+        assert_eq!(next_prime(&int(97)).unwrap(), int(101));
+        assert!(next_prime(&int(i64::MAX)).is_err());
+
+        let list = |items: &[i64]| Value::from_list(items.iter().map(|i| int(*i)).collect());
+        assert_eq!(factor(&int(360)).unwrap(), list(&[2, 2, 2, 3, 3, 5]));
+        assert_eq!(factor(&int(1)).unwrap(), list(&[]));
+        assert_eq!(format!("{}", factor(&int(0)).unwrap_err()), "Invalid operand - 'factor 0'");
+    }
+
+    #[test]
+    fn test_unop_special_math() {
+        let num = Value::from_num;
+        assert_eq!(gamma(&Value::from_int(5)).unwrap(), num(24.0));
+        assert_eq!(log_gamma(&Value::from_int(2)).unwrap(), num(0.0));
+        assert!((digamma(&Value::from_int(1)).unwrap().to_num().unwrap() + 0.577_215_664_901_532_9).abs() < 1e-14);
+        assert_eq!(error_ftn(&Value::from_int(0)).unwrap(), num(0.0));
+        assert_eq!(error_ftn_complement(&Value::from_int(0)).unwrap(), num(1.0));
+        assert_eq!(inverse_error_ftn(&Value::from_int(1)).unwrap(), num(f64::INFINITY));
+        assert_eq!(bessel_j0(&Value::from_int(0)).unwrap(), num(1.0));
+        assert_eq!(bessel_j1(&Value::from_int(0)).unwrap(), num(0.0));
+        assert_eq!(bessel_y0(&Value::from_int(0)).unwrap(), num(f64::NEG_INFINITY));
+        assert!(bessel_y1(&Value::from_int(-1)).unwrap().to_num().unwrap().is_nan());
+        assert!(gamma(&Value::from_bool(true)).is_err());
+
+        // Uncertainty propagates through the derivative, gamma'(3) = gamma(3) digamma(3)
+        let x = Value::from_uncertain(Uncertain::new(3.0, 0.1).unwrap());
+        let g = gamma(&x).unwrap().to_uncertain().unwrap();
+        assert!((g.value() - 2.0).abs() < 1e-14);
+        assert!((g.sigma() - 0.1 * 2.0 * 0.922_784_335_098_467_1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unop_is_none() {
+        assert_eq!(is_none(&Value::from_none()).unwrap(), Value::from_bool(true));
+        assert_eq!(is_none(&Value::from_bool(false)).unwrap(), Value::from_bool(false));
+        assert_eq!(is_none(&Value::from_int(0)).unwrap(), Value::from_bool(false));
+        assert!(negate(&Value::from_none()).is_err());
     }
 }
@@ -0,0 +1,326 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use crate::pcalc_value::display_round;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// --------------------------------------------------------------------------------
+// Uncertain Error
+
+pub type UncertainError = Error;
+
+impl UncertainError {
+    pub fn invalid_sigma(sigma: f64) -> Self {
+        UncertainError::from_string(ErrorKind::Domain, format!("Invalid uncertainty - '{}'", sigma))
+    }
+
+    pub fn uncertain_domain(op: &str, operands: &str) -> Self {
+        UncertainError::from_string(ErrorKind::Domain, format!("Uncertain value outside {} domain - '{}'", op, operands))
+    }
+}
+
+pub type UncertainResult = Result<Uncertain, UncertainError>;
+
+// --------------------------------------------------------------------------------
+// Uncertain - Value with standard uncertainty, propagated to first order
+//
+// Each pm creates an independent error source. A value keeps its sensitivity to every
+// source it depends on, scaled by the derivative of each operation, and sigma is the
+// root sum of squares of those sensitivities. Reusing a variable reuses its sources,
+// so correlated terms add or cancel, e.g. - x x is exactly 0 ± 0.
+
+static NEXT_SOURCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct Uncertain {
+    value: f64,
+    terms: BTreeMap<u64, f64>
+}
+
+impl Uncertain {
+    pub fn new(value: f64, sigma: f64) -> UncertainResult {
+        if !sigma.is_finite() || sigma < 0.0 {
+            return Err(UncertainError::invalid_sigma(sigma));
+        }
+        let mut terms = BTreeMap::new();
+        if sigma > 0.0 {
+            terms.insert(NEXT_SOURCE.fetch_add(1, Ordering::Relaxed), sigma);
+        }
+        Ok(Uncertain { value, terms })
+    }
+
+    #[inline(always)]
+    pub fn exact(value: f64) -> Self {
+        Uncertain { value, terms: BTreeMap::new() }
+    }
+
+    #[inline(always)]
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn sigma(&self) -> f64 {
+        // hypot keeps large terms from overflowing when squared
+        self.terms.values().fold(0.0, |acc, t| acc.hypot(*t))
+    }
+
+    // Value and sigma, rounded for display and printed with fmt_num, e.g. 1.5 ± 0.1
+    pub fn to_string_with(&self, fmt_num: impl Fn(f64) -> String) -> String {
+        format!("{} ± {}", fmt_num(display_round(self.value)), fmt_num(display_round(self.sigma())))
+    }
+
+    // Apply ftn, whose derivative is deriv
+    pub fn map(&self, op: &str, ftn: fn(f64) -> f64, deriv: fn(f64) -> f64) -> UncertainResult {
+        let value = ftn(self.value);
+        if value.is_nan() && !self.value.is_nan() {
+            return Err(UncertainError::uncertain_domain(op, &self.to_string()));
+        }
+        let slope = deriv(self.value);
+        Ok(Uncertain {
+            value,
+            terms: self.terms.iter().map(|(src, t)| (*src, t * slope)).collect()
+        })
+    }
+
+    pub fn add(&self, other: &Uncertain) -> Self {
+        self.combine(other, self.value + other.value, 1.0, 1.0)
+    }
+
+    pub fn sub(&self, other: &Uncertain) -> Self {
+        self.combine(other, self.value - other.value, 1.0, -1.0)
+    }
+
+    pub fn mul(&self, other: &Uncertain) -> Self {
+        self.combine(other, self.value * other.value, other.value, self.value)
+    }
+
+    pub fn div(&self, other: &Uncertain) -> Self {
+        let (x, y) = (self.value, other.value);
+        self.combine(other, x / y, 1.0 / y, -x / (y * y))
+    }
+
+    pub fn rem(&self, other: &Uncertain) -> UncertainResult {
+        let (x, y) = (self.value, other.value);
+        if y == 0.0 {
+            return Err(UncertainError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        Ok(self.combine(other, x % y, 1.0, -(x / y).trunc()))
+    }
+
+    // Quotient rounded toward negative infinity, which is flat between steps
+    pub fn floor_div(&self, other: &Uncertain) -> UncertainResult {
+        let (x, y) = (self.value, other.value);
+        if y == 0.0 {
+            return Err(UncertainError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        Ok(self.combine(other, (x / y).floor(), 0.0, 0.0))
+    }
+
+    // Remainder with the sign of other
+    pub fn modulo(&self, other: &Uncertain) -> UncertainResult {
+        let (x, y) = (self.value, other.value);
+        if y == 0.0 {
+            return Err(UncertainError::new(ErrorKind::Domain, "Division by zero"));
+        }
+        let rem = x % y;
+        let value = if rem != 0.0 && (rem < 0.0) != (y < 0.0) { rem + y } else { rem };
+        Ok(self.combine(other, value, 1.0, -(x / y).floor()))
+    }
+
+    pub fn pow(&self, other: &Uncertain) -> UncertainResult {
+        let (x, y) = (self.value, other.value);
+        let value = x.powf(y);
+        if value.is_nan() && !x.is_nan() && !y.is_nan() {
+            return Err(UncertainError::uncertain_domain("^", &format!("{} {}", self, other)));
+        }
+        // The exponent's sensitivity involves ln x, so only use it when the exponent is uncertain
+        let dy = if other.terms.is_empty() { 0.0 } else { value * x.ln() };
+        Ok(self.combine(other, value, y * x.powf(y - 1.0), dy))
+    }
+
+    // Apply ftn to self and other, whose partial derivatives are deriv
+    pub fn map2(&self, other: &Uncertain, op: &str, ftn: fn(f64, f64) -> f64, deriv: fn(f64, f64) -> (f64, f64)) -> UncertainResult {
+        let (x, y) = (self.value, other.value);
+        let value = ftn(x, y);
+        if value.is_nan() && !x.is_nan() && !y.is_nan() {
+            return Err(UncertainError::uncertain_domain(op, &format!("{} {}", self, other)));
+        }
+        let (dx, dy) = deriv(x, y);
+        Ok(self.combine(other, value, dx, dy))
+    }
+
+    // Result value with sensitivities dx * self + dy * other
+    fn combine(&self, other: &Uncertain, value: f64, dx: f64, dy: f64) -> Self {
+        let mut terms: BTreeMap<u64, f64> = self.terms.iter().map(|(src, t)| (*src, t * dx)).collect();
+        for (src, t) in &other.terms {
+            *terms.entry(*src).or_insert(0.0) += t * dy;
+        }
+        Uncertain { value, terms }
+    }
+}
+
+impl fmt::Display for Uncertain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(|x| x.to_string()))
+    }
+}
+
+// Uncertain values compare by their nominal values
+impl cmp::PartialEq for Uncertain {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl cmp::PartialOrd for Uncertain {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(x: f64, y: f64) -> bool {
+        (x - y).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_uncertain_new() {
+        let u = Uncertain::new(9.81, 0.02).unwrap();
+        assert_eq!(u.value(), 9.81);
+        assert_eq!(u.sigma(), 0.02);
+        assert_eq!(format!("{}", u), "9.81 ± 0.02");
+
+        assert_eq!(Uncertain::new(1.0, 0.0).unwrap().sigma(), 0.0);
+        assert_eq!(Uncertain::exact(2.0).sigma(), 0.0);
+        assert_eq!(format!("{}", Uncertain::new(1.0, -0.5).unwrap_err()), "Invalid uncertainty - '-0.5'");
+        assert!(Uncertain::new(1.0, f64::NAN).is_err());
+        assert!(Uncertain::new(1.0, f64::INFINITY).is_err());
+
+        // Large uncertainties do not overflow when combined
+        let big = Uncertain::new(1e308, 1e308).unwrap();
+        assert_eq!(big.sigma(), 1e308);
+        assert!(!format!("{}", big).contains("inf"));
+        assert!(approx(big.add(&Uncertain::new(1e308, 1e308).unwrap()).sigma() / 1e308, 2.0_f64.sqrt()));
+    }
+
+    #[test]
+    fn test_uncertain_independent() {
+        let x = Uncertain::new(10.0, 0.3).unwrap();
+        let y = Uncertain::new(5.0, 0.4).unwrap();
+
+        let sum = x.add(&y);
+        assert_eq!(sum.value(), 15.0);
+        assert!(approx(sum.sigma(), 0.5));
+
+        let diff = x.sub(&y);
+        assert_eq!(diff.value(), 5.0);
+        assert!(approx(diff.sigma(), 0.5));
+
+        // Relative errors add in quadrature for products and quotients
+        let prod = x.mul(&y);
+        assert_eq!(prod.value(), 50.0);
+        assert!(approx(prod.sigma(), 50.0 * (0.03_f64.powi(2) + 0.08_f64.powi(2)).sqrt()));
+
+        let quot = x.div(&y);
+        assert_eq!(quot.value(), 2.0);
+        assert!(approx(quot.sigma(), 2.0 * (0.03_f64.powi(2) + 0.08_f64.powi(2)).sqrt()));
+
+        let rem = x.rem(&Uncertain::exact(3.0)).unwrap();
+        assert_eq!(rem.value(), 1.0);
+        assert!(approx(rem.sigma(), 0.3));
+        assert_eq!(format!("{}", x.rem(&Uncertain::exact(0.0)).unwrap_err()), "Division by zero");
+        assert_eq!(format!("{}", y.rem(&Uncertain::new(0.0, 0.1).unwrap()).unwrap_err()), "Division by zero");
+    }
+
+    #[test]
+    fn test_uncertain_correlated() {
+        let x = Uncertain::new(3.0, 0.1).unwrap();
+
+        let zero = x.sub(&x);
+        assert_eq!(zero.value(), 0.0);
+        assert_eq!(zero.sigma(), 0.0);
+
+        let double = x.add(&x);
+        assert!(approx(double.sigma(), 0.2));
+
+        let square = x.mul(&x);
+        assert_eq!(square.value(), 9.0);
+        assert!(approx(square.sigma(), 0.6));
+
+        let one = x.div(&x);
+        assert_eq!(one.value(), 1.0);
+        assert_eq!(one.sigma(), 0.0);
+    }
+
+    #[test]
+    fn test_uncertain_map() {
+        let x = Uncertain::new(4.0, 0.2).unwrap();
+        let root = x.map("sqrt", f64::sqrt, |v| 0.5 / v.sqrt()).unwrap();
+        assert_eq!(root.value(), 2.0);
+        assert!(approx(root.sigma(), 0.05));
+
+        let neg = Uncertain::new(-1.0, 0.1).unwrap();
+        assert_eq!(
+            format!("{}", neg.map("sqrt", f64::sqrt, |v| 0.5 / v.sqrt()).unwrap_err()),
+            "Uncertain value outside sqrt domain - '-1 ± 0.1'"
+        );
+    }
+
+    #[test]
+    fn test_uncertain_pow() {
+        let x = Uncertain::new(2.0, 0.1).unwrap();
+
+        let cube = x.pow(&Uncertain::exact(3.0)).unwrap();
+        assert_eq!(cube.value(), 8.0);
+        assert!(approx(cube.sigma(), 1.2));
+
+        let y = Uncertain::new(3.0, 0.2).unwrap();
+        let p = x.pow(&y).unwrap();
+        let expected = ((12.0_f64 * 0.1).powi(2) + (8.0 * 2.0_f64.ln() * 0.2).powi(2)).sqrt();
+        assert!(approx(p.sigma(), expected));
+
+        // Exact exponent of a negative base skips the ln sensitivity
+        let neg = Uncertain::new(-2.0, 0.1).unwrap();
+        assert_eq!(neg.pow(&Uncertain::exact(2.0)).unwrap().value(), 4.0);
+        assert!(neg.pow(&Uncertain::exact(0.5)).is_err());
+    }
+
+    #[test]
+    fn test_uncertain_map2() {
+        let x = Uncertain::new(3.0, 0.3).unwrap();
+        let y = Uncertain::new(4.0, 0.4).unwrap();
+        let h = x.map2(&y, "hypot", f64::hypot, |a, b| (a / a.hypot(b), b / a.hypot(b))).unwrap();
+        assert_eq!(h.value(), 5.0);
+        assert!(approx(h.sigma(), (0.18_f64.powi(2) + 0.32_f64.powi(2)).sqrt()));
+
+        // Correlated operands combine their sensitivities
+        let d = x.map2(&x, "fdim", |a, b| a - b, |_, _| (1.0, -1.0)).unwrap();
+        assert_eq!(d.sigma(), 0.0);
+
+        assert_eq!(
+            format!("{}", x.map2(&y, "-", |a, b| (a - b).sqrt(), |_, _| (0.0, 0.0)).unwrap_err()),
+            "Uncertain value outside - domain - '3 ± 0.3 4 ± 0.4'"
+        );
+
+        let m = Uncertain::new(-7.0, 0.1).unwrap().modulo(&Uncertain::exact(3.0)).unwrap();
+        assert_eq!(m.value(), 2.0);
+        assert!(approx(m.sigma(), 0.1));
+        assert_eq!(x.floor_div(&Uncertain::exact(2.0)).unwrap().value(), 1.0);
+        assert!(x.floor_div(&Uncertain::exact(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_uncertain_compare() {
+        let x = Uncertain::new(1.0, 0.5).unwrap();
+        let y = Uncertain::new(2.0, 0.5).unwrap();
+        assert!(x < y);
+        assert!(x == Uncertain::exact(1.0));
+        assert!(x != y);
+    }
+}
@@ -0,0 +1,618 @@
+use crate::pcalc_error::{Error, ErrorKind};
+use crate::pcalc_keywords::NameList;
+use crate::pcalc_value::display_round;
+use std::cmp;
+use std::fmt;
+
+// --------------------------------------------------------------------------------
+// Unit Error
+
+pub type UnitError = Error;
+
+impl UnitError {
+    pub fn unknown_unit(name: &str) -> Self {
+        UnitError::from_string(ErrorKind::Syntax, format!("Unknown unit - '{}'", name))
+    }
+
+    pub fn invalid_unit(expr: &str) -> Self {
+        UnitError::from_string(ErrorKind::Syntax, format!("Invalid unit expression - '{}'", expr))
+    }
+
+    pub fn incompatible(lhs: &Quantity, rhs: &Quantity) -> Self {
+        UnitError::from_string(
+            ErrorKind::TypeMismatch,
+            format!("Incompatible units - '{}' and '{}'", lhs.unit_name(), rhs.unit_name())
+        )
+    }
+
+    pub fn exponent_range() -> Self {
+        UnitError::new(ErrorKind::Domain, "Unit exponent out of range")
+    }
+
+    pub fn invalid_root(qty: &Quantity) -> Self {
+        UnitError::from_string(ErrorKind::TypeMismatch, format!("Invalid unit for square root - '{}'", qty.unit_name()))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Dimension - Exponents of the SI base units m, kg, s, A, K, mol, cd
+
+pub type Dim = [i8; 7];
+
+const DIMENSIONLESS: Dim = [0, 0, 0, 0, 0, 0, 0];
+const BASE_SYMBOLS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+// Named units used when displaying results without an explicit unit.
+const DERIVED_SYMBOLS: [(&str, Dim); 7] = [
+    ("N", [1, 1, -2, 0, 0, 0, 0]),
+    ("J", [2, 1, -2, 0, 0, 0, 0]),
+    ("W", [2, 1, -3, 0, 0, 0, 0]),
+    ("Pa", [-1, 1, -2, 0, 0, 0, 0]),
+    ("C", [0, 0, 1, 1, 0, 0, 0]),
+    ("V", [2, 1, -3, -1, 0, 0, 0]),
+    ("ohm", [2, 1, -3, -2, 0, 0, 0])
+];
+
+// Exponents stay within -127 to 127, so they can always be negated
+#[inline(always)]
+fn dim_exp(exp: Option<i8>) -> Result<i8, UnitError> {
+    exp.filter(|e| *e != i8::MIN).ok_or_else(UnitError::exponent_range)
+}
+
+fn dim_combine(lhs: &Dim, rhs: &Dim, sign: i8) -> Result<Dim, UnitError> {
+    let mut dim = DIMENSIONLESS;
+    for (idx, d) in dim.iter_mut().enumerate() {
+        *d = dim_exp(sign.checked_mul(rhs[idx]).and_then(|r| lhs[idx].checked_add(r)))?;
+    }
+    Ok(dim)
+}
+
+fn dim_scale(dim: &Dim, factor: i8) -> Result<Dim, UnitError> {
+    let mut scaled = DIMENSIONLESS;
+    for (idx, d) in scaled.iter_mut().enumerate() {
+        *d = dim_exp(dim[idx].checked_mul(factor))?;
+    }
+    Ok(scaled)
+}
+
+// Simplified SI name for a dimension, e.g. m/s^2 or N.
+fn dim_name(dim: &Dim) -> String {
+    if *dim == DIMENSIONLESS {
+        return String::from("1");
+    }
+    if let Some((name, _)) = DERIVED_SYMBOLS.iter().find(|(_, d)| d == dim) {
+        return String::from(*name);
+    }
+
+    let terms: Vec<(&str, i8)> = BASE_SYMBOLS.iter().copied().zip(dim.iter().copied()).collect();
+    terms_name(&terms)
+}
+
+// Name for units raised to exponents, e.g. [(kg, 1), (m, 2), (s, -2)] is kg*m^2/s^2.
+fn terms_name(terms: &[(&str, i8)]) -> String {
+    let term = |name: &str, exp: i8| {
+        if exp == 1 {
+            String::from(name)
+        } else {
+            format!("{}^{}", name, exp)
+        }
+    };
+    let numer: Vec<String> = terms.iter().filter(|(_, exp)| *exp > 0).map(|(name, exp)| term(name, *exp)).collect();
+    let denom: Vec<String> = terms.iter().filter(|(_, exp)| *exp < 0).map(|(name, exp)| term(name, -exp)).collect();
+
+    let numer = if numer.is_empty() { String::from("1") } else { numer.join("*") };
+    if denom.is_empty() {
+        numer
+    } else {
+        format!("{}/{}", numer, denom.join("/"))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Unit Table - (scale, offset, dimension), where SI value = value * scale + offset
+
+type UnitDef = (f64, f64, Dim);
+
+const LENGTH: Dim = [1, 0, 0, 0, 0, 0, 0];
+const MASS: Dim = [0, 1, 0, 0, 0, 0, 0];
+const TIME: Dim = [0, 0, 1, 0, 0, 0, 0];
+const CURRENT: Dim = [0, 0, 0, 1, 0, 0, 0];
+const TEMPERATURE: Dim = [0, 0, 0, 0, 1, 0, 0];
+const AMOUNT: Dim = [0, 0, 0, 0, 0, 1, 0];
+const LUMINOSITY: Dim = [0, 0, 0, 0, 0, 0, 1];
+const VOLUME: Dim = [3, 0, 0, 0, 0, 0, 0];
+const FREQUENCY: Dim = [0, 0, -1, 0, 0, 0, 0];
+const FORCE: Dim = [1, 1, -2, 0, 0, 0, 0];
+const ENERGY: Dim = [2, 1, -2, 0, 0, 0, 0];
+const POWER: Dim = [2, 1, -3, 0, 0, 0, 0];
+const PRESSURE: Dim = [-1, 1, -2, 0, 0, 0, 0];
+const CHARGE: Dim = [0, 0, 1, 1, 0, 0, 0];
+const VOLTAGE: Dim = [2, 1, -3, -1, 0, 0, 0];
+const RESISTANCE: Dim = [2, 1, -3, -2, 0, 0, 0];
+
+const FAHRENHEIT_SCALE: f64 = 5.0 / 9.0;
+
+#[rustfmt::skip]
+fn lookup_unit(name: &str) -> Option<UnitDef> {
+    let def = match name {
+        // Length
+        "m" => (1.0, 0.0, LENGTH),
+        "km" => (1e3, 0.0, LENGTH),
+        "cm" => (1e-2, 0.0, LENGTH),
+        "mm" => (1e-3, 0.0, LENGTH),
+        "um" => (1e-6, 0.0, LENGTH),
+        "nm" => (1e-9, 0.0, LENGTH),
+        "in" => (0.0254, 0.0, LENGTH),
+        "ft" => (0.3048, 0.0, LENGTH),
+        "yd" => (0.9144, 0.0, LENGTH),
+        "mi" => (1609.344, 0.0, LENGTH),
+        "nmi" => (1852.0, 0.0, LENGTH),
+        // Mass
+        "kg" => (1.0, 0.0, MASS),
+        "g" => (1e-3, 0.0, MASS),
+        "mg" => (1e-6, 0.0, MASS),
+        "t" => (1e3, 0.0, MASS),
+        "lb" => (0.45359237, 0.0, MASS),
+        "oz" => (0.028349523125, 0.0, MASS),
+        // Time
+        "s" => (1.0, 0.0, TIME),
+        "ms" => (1e-3, 0.0, TIME),
+        "us" => (1e-6, 0.0, TIME),
+        "ns" => (1e-9, 0.0, TIME),
+        "min" => (60.0, 0.0, TIME),
+        "h" => (3600.0, 0.0, TIME),
+        "day" => (86400.0, 0.0, TIME),
+        // Current, temperature, amount, luminosity
+        "A" => (1.0, 0.0, CURRENT),
+        "mA" => (1e-3, 0.0, CURRENT),
+        "K" => (1.0, 0.0, TEMPERATURE),
+        "degC" => (1.0, 273.15, TEMPERATURE),
+        "degF" => (FAHRENHEIT_SCALE, 273.15 - 32.0 * FAHRENHEIT_SCALE, TEMPERATURE),
+        "mol" => (1.0, 0.0, AMOUNT),
+        "cd" => (1.0, 0.0, LUMINOSITY),
+        // Derived
+        "L" => (1e-3, 0.0, VOLUME),
+        "mL" => (1e-6, 0.0, VOLUME),
+        "Hz" => (1.0, 0.0, FREQUENCY),
+        "kHz" => (1e3, 0.0, FREQUENCY),
+        "MHz" => (1e6, 0.0, FREQUENCY),
+        "N" => (1.0, 0.0, FORCE),
+        "kN" => (1e3, 0.0, FORCE),
+        "lbf" => (4.4482216152605, 0.0, FORCE),
+        "J" => (1.0, 0.0, ENERGY),
+        "kJ" => (1e3, 0.0, ENERGY),
+        "cal" => (4.184, 0.0, ENERGY),
+        "kcal" => (4184.0, 0.0, ENERGY),
+        "Wh" => (3600.0, 0.0, ENERGY),
+        "kWh" => (3.6e6, 0.0, ENERGY),
+        "W" => (1.0, 0.0, POWER),
+        "kW" => (1e3, 0.0, POWER),
+        "hp" => (745.699_871_582_270_2, 0.0, POWER),
+        "Pa" => (1.0, 0.0, PRESSURE),
+        "kPa" => (1e3, 0.0, PRESSURE),
+        "bar" => (1e5, 0.0, PRESSURE),
+        "atm" => (101325.0, 0.0, PRESSURE),
+        "psi" => (6894.757293168361, 0.0, PRESSURE),
+        "C" => (1.0, 0.0, CHARGE),
+        "V" => (1.0, 0.0, VOLTAGE),
+        "ohm" => (1.0, 0.0, RESISTANCE),
+        _ => return None
+    };
+    Some(def)
+}
+
+#[inline(always)]
+#[rustfmt::skip]
+pub fn unit_names() -> NameList<'static> {
+    vec!["m", "km", "cm", "mm", "um", "nm", "in", "ft", "yd", "mi", "nmi",
+         "kg", "g", "mg", "t", "lb", "oz",
+         "s", "ms", "us", "ns", "min", "h", "day",
+         "A", "mA", "K", "degC", "degF", "mol", "cd",
+         "L", "mL", "Hz", "kHz", "MHz",
+         "N", "kN", "lbf", "J", "kJ", "cal", "kcal", "Wh", "kWh",
+         "W", "kW", "hp", "Pa", "kPa", "bar", "atm", "psi",
+         "C", "V", "ohm"]
+}
+
+// --------------------------------------------------------------------------------
+// Unit
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    name: String,
+    scale: f64,
+    offset: f64,
+    dim: Dim
+}
+
+impl Unit {
+    // Parse a unit expression, e.g. km, m/s^2 or kg*m/s^2.
+    // Offset units (degC, degF) must appear alone. A unit named more than once is
+    // simplified, e.g. m/s/s is m/s^2, and kg*m*m/s^2, made of SI units only, is J.
+    pub fn parse(expr: &str) -> Result<Unit, UnitError> {
+        let mut unit = Unit {
+            name: String::from(expr),
+            scale: 1.0,
+            offset: 0.0,
+            dim: DIMENSIONLESS
+        };
+
+        let mut terms: Vec<(&str, i8)> = Vec::new();
+        let mut repeated = false;
+        let mut sign: i8 = 1;
+        let mut rest = expr;
+        loop {
+            let end = rest.find(['*', '/']).unwrap_or(rest.len());
+            let (name, exp) = match rest[..end].split_once('^') {
+                Some((name, exp)) => match exp.parse::<i64>() {
+                    Ok(exp) => (name, dim_exp(i8::try_from(exp).ok())?),
+                    Err(_) => return Err(UnitError::invalid_unit(expr))
+                },
+                None => (&rest[..end], 1)
+            };
+            if name.is_empty() {
+                return Err(UnitError::invalid_unit(expr));
+            }
+
+            let (scale, offset, dim) = lookup_unit(name).ok_or_else(|| UnitError::unknown_unit(name))?;
+            if offset != 0.0 {
+                if end != expr.len() || exp != 1 {
+                    return Err(UnitError::invalid_unit(expr));
+                }
+                unit.offset = offset;
+            }
+            unit.scale *= scale.powi(i32::from(sign * exp));
+            unit.dim = dim_combine(&unit.dim, &dim_scale(&dim, exp)?, sign)?;
+            match terms.iter_mut().find(|(term, _)| *term == name) {
+                Some((_, term_exp)) => {
+                    *term_exp = dim_exp(term_exp.checked_add(sign * exp))?;
+                    repeated = true;
+                }
+                None => terms.push((name, sign * exp))
+            }
+
+            if end == rest.len() {
+                break;
+            }
+            sign = if rest[end..].starts_with('/') { -1 } else { 1 };
+            rest = &rest[end + 1..];
+        }
+        if repeated {
+            unit.name = if unit.scale == 1.0 { dim_name(&unit.dim) } else { terms_name(&terms) };
+        }
+        Ok(unit)
+    }
+
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline(always)]
+    pub fn dim(&self) -> Dim {
+        self.dim
+    }
+
+    #[inline(always)]
+    fn is_affine(&self) -> bool {
+        self.offset != 0.0
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Quantity - Value in SI base units, with dimension and optional display unit
+
+#[derive(Debug, Clone)]
+pub struct Quantity {
+    value: f64,
+    dim: Dim,
+    unit: Option<Unit>
+}
+
+impl Quantity {
+    pub fn new(magnitude: f64, unit: Unit) -> Self {
+        Quantity {
+            value: magnitude * unit.scale + unit.offset,
+            dim: unit.dim,
+            unit: Some(unit)
+        }
+    }
+
+    pub fn dimensionless(value: f64) -> Self {
+        Quantity {
+            value,
+            dim: DIMENSIONLESS,
+            unit: None
+        }
+    }
+
+    // Value in SI base units
+    #[inline(always)]
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // Value in the display unit
+    #[inline(always)]
+    pub fn magnitude(&self) -> f64 {
+        match &self.unit {
+            Some(unit) => (self.value - unit.offset) / unit.scale,
+            None => self.value
+        }
+    }
+
+    #[inline(always)]
+    pub fn dim(&self) -> Dim {
+        self.dim
+    }
+
+    #[inline(always)]
+    pub fn is_dimensionless(&self) -> bool {
+        self.dim == DIMENSIONLESS
+    }
+
+    pub fn unit_name(&self) -> String {
+        match &self.unit {
+            Some(unit) => String::from(unit.name()),
+            None => dim_name(&self.dim)
+        }
+    }
+
+    pub fn add(&self, other: &Quantity) -> Result<Quantity, UnitError> {
+        self.check_compatible(other)?;
+        Ok(self.with_value(self.value + other.value, self.dim))
+    }
+
+    pub fn sub(&self, other: &Quantity) -> Result<Quantity, UnitError> {
+        self.check_compatible(other)?;
+        Ok(self.with_value(self.value - other.value, self.dim))
+    }
+
+    pub fn mul(&self, other: &Quantity) -> Result<Quantity, UnitError> {
+        let value = self.value * other.value;
+        if other.is_dimensionless() {
+            Ok(self.with_value(value, self.dim))
+        } else if self.is_dimensionless() {
+            Ok(other.with_value(value, other.dim))
+        } else {
+            Ok(Self::si(value, dim_combine(&self.dim, &other.dim, 1)?))
+        }
+    }
+
+    pub fn div(&self, other: &Quantity) -> Result<Quantity, UnitError> {
+        let value = self.value / other.value;
+        if other.is_dimensionless() {
+            Ok(self.with_value(value, self.dim))
+        } else {
+            Ok(Self::si(value, dim_combine(&self.dim, &other.dim, -1)?))
+        }
+    }
+
+    pub fn powi(&self, exp: i8) -> Result<Quantity, UnitError> {
+        Ok(Self::si(self.value.powi(i32::from(exp)), dim_scale(&self.dim, exp)?))
+    }
+
+    pub fn sqrt(&self) -> Result<Quantity, UnitError> {
+        if self.dim.iter().any(|d| d % 2 != 0) {
+            return Err(UnitError::invalid_root(self));
+        }
+        Ok(Self::si(self.value.sqrt(), self.dim.map(|d| d / 2)))
+    }
+
+    pub fn neg(&self) -> Quantity {
+        self.with_value(-self.value, self.dim)
+    }
+
+    pub fn abs(&self) -> Quantity {
+        self.with_value(self.value.abs(), self.dim)
+    }
+
+    // Magnitude, rounded for display and printed with fmt_num, and unit, e.g. 1.5 km
+    pub fn to_string_with(&self, fmt_num: impl Fn(f64) -> String) -> String {
+        format!("{} {}", fmt_num(display_round(self.magnitude())), self.unit_name())
+    }
+
+    // Same quantity, displayed in the target's unit.
+    pub fn convert(&self, target: &Quantity) -> Result<Quantity, UnitError> {
+        self.check_compatible(target)?;
+        Ok(Quantity {
+            value: self.value,
+            dim: self.dim,
+            unit: target.unit.clone()
+        })
+    }
+
+    // --------------------------------------------------------------------------------
+    // Private Functions
+
+    fn si(value: f64, dim: Dim) -> Quantity {
+        Quantity { value, dim, unit: None }
+    }
+
+    // Keep the display unit, unless it is an offset unit whose meaning does not survive arithmetic.
+    fn with_value(&self, value: f64, dim: Dim) -> Quantity {
+        Quantity {
+            value,
+            dim,
+            unit: self.unit.clone().filter(|unit| !unit.is_affine())
+        }
+    }
+
+    fn check_compatible(&self, other: &Quantity) -> Result<(), UnitError> {
+        if self.dim == other.dim {
+            Ok(())
+        } else {
+            Err(UnitError::incompatible(self, other))
+        }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(|x| x.to_string()))
+    }
+}
+
+impl cmp::PartialEq for Quantity {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(cmp::Ordering::Equal)
+    }
+}
+
+impl cmp::PartialOrd for Quantity {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self.dim == other.dim {
+            self.value.partial_cmp(&other.value)
+        } else {
+            None
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qty(magnitude: f64, unit: &str) -> Quantity {
+        Quantity::new(magnitude, Unit::parse(unit).unwrap())
+    }
+
+    fn check_equal(lhs: f64, rhs: f64) -> bool {
+        (lhs - rhs).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_unit_parse() {
+        let km = Unit::parse("km").unwrap();
+        assert_eq!(km.name(), "km");
+        assert_eq!(km.scale, 1000.0);
+        assert_eq!(km.dim(), LENGTH);
+
+        let accel = Unit::parse("m/s^2").unwrap();
+        assert_eq!(accel.scale, 1.0);
+        assert_eq!(accel.dim(), [1, 0, -2, 0, 0, 0, 0]);
+
+        let speed = Unit::parse("km/h").unwrap();
+        assert!(check_equal(speed.scale, 1000.0 / 3600.0));
+        assert_eq!(speed.dim(), [1, 0, -1, 0, 0, 0, 0]);
+
+        assert_eq!(Unit::parse("kg*m/s/s").unwrap().dim(), FORCE);
+        assert_eq!(Unit::parse("m^-1").unwrap().dim(), [-1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Unit::parse("degF").unwrap().offset, 273.15 - 32.0 * FAHRENHEIT_SCALE);
+
+        assert_eq!(Unit::parse("furlong").unwrap_err().message(), "Unknown unit - 'furlong'");
+        assert_eq!(Unit::parse("m/").unwrap_err().message(), "Invalid unit expression - 'm/'");
+        assert_eq!(Unit::parse("m^x").unwrap_err().message(), "Invalid unit expression - 'm^x'");
+        assert_eq!(Unit::parse("degC/s").unwrap_err().message(), "Invalid unit expression - 'degC/s'");
+        assert_eq!(Unit::parse("degC^2").unwrap_err().message(), "Invalid unit expression - 'degC^2'");
+        assert_eq!(Unit::parse("m^127").unwrap().dim(), [127, 0, 0, 0, 0, 0, 0]);
+
+        // Repeated units are simplified, SI units to named units where possible
+        assert_eq!(Unit::parse("m/s/s").unwrap().name(), "m/s^2");
+        assert_eq!(Unit::parse("kg*m*m/s^2").unwrap().name(), "J");
+        assert_eq!(Unit::parse("km/h/h").unwrap().name(), "km/h^2");
+        assert_eq!(Unit::parse("ft*ft*ft").unwrap().name(), "ft^3");
+        assert_eq!(Unit::parse("kg*m/s^2").unwrap().name(), "kg*m/s^2");
+        assert_eq!(Unit::parse("m^127*m").unwrap_err().message(), "Unit exponent out of range");
+        assert_eq!(Unit::parse("m^-128").unwrap_err().message(), "Unit exponent out of range");
+        assert_eq!(Unit::parse("m^300").unwrap_err().message(), "Unit exponent out of range");
+
+        // Malformed units are syntax errors, mismatched dimensions type mismatches
+        assert_eq!(Unit::parse("furlong").unwrap_err().kind(), ErrorKind::Syntax);
+        assert_eq!(Unit::parse("m/").unwrap_err().kind(), ErrorKind::Syntax);
+        let err = qty(1.0, "m").add(&qty(1.0, "s")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+        assert_eq!(Unit::parse("m^100*m^100").unwrap_err().message(), "Unit exponent out of range");
+
+        for name in unit_names() {
+            assert!(Unit::parse(name).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_dim_name() {
+        assert_eq!(dim_name(&DIMENSIONLESS), "1");
+        assert_eq!(dim_name(&LENGTH), "m");
+        assert_eq!(dim_name(&[1, 0, -1, 0, 0, 0, 0]), "m/s");
+        assert_eq!(dim_name(&[1, 0, -2, 0, 0, 0, 0]), "m/s^2");
+        assert_eq!(dim_name(&[2, 0, 0, 0, 0, 0, 0]), "m^2");
+        assert_eq!(dim_name(&FREQUENCY), "1/s");
+        assert_eq!(dim_name(&[1, 1, 0, 0, 0, 0, 0]), "m*kg");
+        assert_eq!(dim_name(&FORCE), "N");
+        assert_eq!(dim_name(&ENERGY), "J");
+        assert_eq!(dim_name(&POWER), "W");
+    }
+
+    #[test]
+    fn test_quantity_arithmetic() {
+        let speed = qty(3.0, "m").div(&qty(2.0, "s")).unwrap();
+        assert_eq!(speed.to_string(), "1.5 m/s");
+
+        let sum = qty(1.0, "km").add(&qty(500.0, "m")).unwrap();
+        assert_eq!(sum.to_string(), "1.5 km");
+        assert_eq!(sum.value(), 1500.0);
+
+        let diff = qty(1.0, "m").sub(&qty(50.0, "cm")).unwrap();
+        assert_eq!(diff.to_string(), "0.5 m");
+
+        assert_eq!(qty(1.0, "m").add(&qty(1.0, "s")).unwrap_err().message(), "Incompatible units - 'm' and 's'");
+        assert_eq!(
+            qty(1.0, "m").add(&Quantity::dimensionless(1.0)).unwrap_err().message(),
+            "Incompatible units - 'm' and '1'"
+        );
+
+        assert_eq!(Quantity::dimensionless(3.0).mul(&qty(1.0, "km")).unwrap().to_string(), "3 km");
+        assert_eq!(qty(6.0, "km").div(&Quantity::dimensionless(2.0)).unwrap().to_string(), "3 km");
+        assert_eq!(Quantity::dimensionless(2.0).div(&qty(4.0, "s")).unwrap().to_string(), "0.5 1/s");
+        assert_eq!(qty(2.0, "kg").mul(&qty(3.0, "m/s^2")).unwrap().to_string(), "6 N");
+        assert!(qty(2.0, "m").div(&qty(4.0, "m")).unwrap().is_dimensionless());
+
+        assert_eq!(qty(3.0, "m").powi(2).unwrap().to_string(), "9 m^2");
+        assert_eq!(qty(9.0, "m^2").sqrt().unwrap().to_string(), "3 m");
+        assert!(qty(9.0, "m").sqrt().is_err());
+
+        let big = qty(1.0, "m^100");
+        assert_eq!(big.powi(2).unwrap_err().message(), "Unit exponent out of range");
+        assert_eq!(big.mul(&big).unwrap_err().message(), "Unit exponent out of range");
+        assert_eq!(big.div(&qty(1.0, "m^-100")).unwrap_err().message(), "Unit exponent out of range");
+        assert_eq!(qty(1.0, "m^-127").powi(-1).unwrap().to_string(), "1 m^127");
+
+        assert_eq!(qty(2.0, "km").neg().to_string(), "-2 km");
+        assert_eq!(qty(-2.0, "km").abs().to_string(), "2 km");
+    }
+
+    #[test]
+    fn test_quantity_convert() {
+        let target = qty(1.0, "mi");
+        let dist = qty(1.609344, "km").convert(&target).unwrap();
+        assert!(check_equal(dist.magnitude(), 1.0));
+        assert_eq!(dist.unit_name(), "mi");
+
+        let temp = qty(100.0, "degC").convert(&qty(1.0, "degF")).unwrap();
+        assert!(check_equal(temp.magnitude(), 212.0));
+        let temp = qty(-40.0, "degF").convert(&qty(1.0, "degC")).unwrap();
+        assert!(check_equal(temp.magnitude(), -40.0));
+        let temp = qty(0.0, "degC").convert(&qty(1.0, "K")).unwrap();
+        assert!(check_equal(temp.magnitude(), 273.15));
+
+        assert!(qty(1.0, "km").convert(&qty(1.0, "s")).is_err());
+        assert_eq!(qty(20.0, "degC").convert(&qty(1.0, "degF")).unwrap().to_string(), "68 degF");
+        assert_eq!(qty(5.0, "km").convert(&target).unwrap().to_string(), "3.10685596118667 mi");
+
+        // Offset units do not survive arithmetic
+        let warmer = qty(20.0, "degC").add(&qty(1.0, "K")).unwrap();
+        assert_eq!(warmer.unit_name(), "K");
+    }
+
+    #[test]
+    fn test_quantity_compare() {
+        assert!(qty(1.0, "km") == qty(1000.0, "m"));
+        assert!(qty(1.0, "mi") > qty(1.0, "km"));
+        assert!(qty(1.0, "h") < qty(61.0, "min"));
+        assert!(qty(1.0, "m").partial_cmp(&qty(1.0, "s")).is_none());
+        assert!(qty(1.0, "m") != qty(1.0, "s"));
+    }
+}
@@ -1,39 +1,47 @@
+use crate::pcalc_datetime::{DateTime, Duration};
+use crate::pcalc_decimal::Decimal;
+use crate::pcalc_error::{Error, ErrorKind};
+use crate::pcalc_interval::Interval;
+use crate::pcalc_matrix::{Matrix, Vector};
+use crate::pcalc_record::Record;
+use crate::pcalc_uncertain::Uncertain;
+use crate::pcalc_units::Quantity;
+use crate::pcalc_word as word;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
 use std::cmp;
 use std::fmt;
 
 // --------------------------------------------------------------------------------
 // Value Error
 
-#[derive(Debug, Clone)]
-pub struct ValueError {
-    error_msg: String
-}
-
-impl ValueError {
-    pub fn new(err_msg: &str) -> ValueError {
-        ValueError {
-            error_msg: String::from(err_msg)
-        }
-    }
-
-    pub fn from_string(err_msg: String) -> ValueError {
-        ValueError { error_msg: err_msg }
-    }
-}
-
-impl fmt::Display for ValueError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.error_msg)
-    }
-}
+pub type ValueError = Error;
 
 // --------------------------------------------------------------------------------
 // Value
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Value {
-    Num(f64),   // Number
-    Bool(bool)  // Boolean
+    Num(f64),              // Number
+    Int(i64),              // Integer
+    Rational(BigRational), // Exact rational number
+    Decimal(Decimal),      // Exact decimal number
+    Complex(Complex64),    // Complex number
+    Bool(bool),            // Boolean
+    Str(String),           // String
+    List(Vec<Value>),      // List of values
+    Record(Record),        // Values keyed by field name
+    Vector(Vector),        // Vector of reals
+    Matrix(Matrix),        // Matrix of reals
+    Quantity(Quantity),    // Number with physical unit
+    Interval(Interval),    // Range of real numbers
+    Uncertain(Uncertain),  // Number with standard uncertainty
+    DateTime(DateTime),    // Date, or date and time
+    Duration(Duration),    // Length of time
+    Unknown,               // Indeterminate comparison, e.g. of overlapping intervals
+    None                   // No result, e.g. of if without else
 }
 
 impl Value {
@@ -42,14 +50,189 @@ impl Value {
         Value::Num(n)
     }
 
+    #[inline(always)]
+    pub fn from_int(i: i64) -> Value {
+        Value::Int(i)
+    }
+
+    #[inline(always)]
+    pub fn from_rational(r: BigRational) -> Value {
+        Value::Rational(r)
+    }
+
+    #[inline(always)]
+    pub fn from_decimal(d: Decimal) -> Value {
+        Value::Decimal(d)
+    }
+
+    #[inline(always)]
+    pub fn from_complex(z: Complex64) -> Value {
+        Value::Complex(z)
+    }
+
     #[inline(always)]
     pub fn from_bool(b: bool) -> Value {
         Value::Bool(b)
     }
 
+    #[inline(always)]
+    pub fn from_string(s: String) -> Value {
+        Value::Str(s)
+    }
+
+    #[inline(always)]
+    pub fn from_list(items: Vec<Value>) -> Value {
+        Value::List(items)
+    }
+
+    #[inline(always)]
+    pub fn from_record(rec: Record) -> Value {
+        Value::Record(rec)
+    }
+
+    #[inline(always)]
+    pub fn from_vector(vec: Vector) -> Value {
+        Value::Vector(vec)
+    }
+
+    #[inline(always)]
+    pub fn from_matrix(mat: Matrix) -> Value {
+        Value::Matrix(mat)
+    }
+
+    // Dimensionless results are plain numbers
+    #[inline(always)]
+    pub fn from_quantity(q: Quantity) -> Value {
+        if q.is_dimensionless() {
+            Value::Num(q.value())
+        } else {
+            Value::Quantity(q)
+        }
+    }
+
+    #[inline(always)]
+    pub fn from_interval(i: Interval) -> Value {
+        Value::Interval(i)
+    }
+
+    #[inline(always)]
+    pub fn from_uncertain(u: Uncertain) -> Value {
+        Value::Uncertain(u)
+    }
+
+    #[inline(always)]
+    pub fn from_datetime(dt: DateTime) -> Value {
+        Value::DateTime(dt)
+    }
+
+    #[inline(always)]
+    pub fn from_duration(dur: Duration) -> Value {
+        Value::Duration(dur)
+    }
+
+    #[inline(always)]
+    pub fn from_none() -> Value {
+        Value::None
+    }
+
+    // Three-valued comparison result, where None is unknown
+    #[inline(always)]
+    pub fn from_truth(t: Option<bool>) -> Value {
+        match t {
+            Some(b) => Value::Bool(b),
+            None => Value::Unknown
+        }
+    }
+
     #[inline(always)]
     pub fn is_num(&self) -> bool {
-        matches!(self, Value::Num(_))
+        matches!(self, Value::Num(_) | Value::Int(_) | Value::Rational(_))
+    }
+
+    #[inline(always)]
+    pub fn is_int(&self) -> bool {
+        matches!(self, Value::Int(_))
+    }
+
+    #[inline(always)]
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Value::Rational(_))
+    }
+
+    #[inline(always)]
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, Value::Decimal(_))
+    }
+
+    #[inline(always)]
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Value::Complex(_))
+    }
+
+    #[inline(always)]
+    pub fn is_str(&self) -> bool {
+        matches!(self, Value::Str(_))
+    }
+
+    #[inline(always)]
+    pub fn is_list(&self) -> bool {
+        matches!(self, Value::List(_))
+    }
+
+    #[inline(always)]
+    pub fn is_record(&self) -> bool {
+        matches!(self, Value::Record(_))
+    }
+
+    #[inline(always)]
+    pub fn is_vector(&self) -> bool {
+        matches!(self, Value::Vector(_))
+    }
+
+    #[inline(always)]
+    pub fn is_matrix(&self) -> bool {
+        matches!(self, Value::Matrix(_))
+    }
+
+    #[inline(always)]
+    pub fn is_quantity(&self) -> bool {
+        matches!(self, Value::Quantity(_))
+    }
+
+    #[inline(always)]
+    pub fn is_interval(&self) -> bool {
+        matches!(self, Value::Interval(_))
+    }
+
+    #[inline(always)]
+    pub fn is_uncertain(&self) -> bool {
+        matches!(self, Value::Uncertain(_))
+    }
+
+    #[inline(always)]
+    pub fn is_datetime(&self) -> bool {
+        matches!(self, Value::DateTime(_))
+    }
+
+    #[inline(always)]
+    pub fn is_duration(&self) -> bool {
+        matches!(self, Value::Duration(_))
+    }
+
+    #[inline(always)]
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Value::Unknown)
+    }
+
+    #[inline(always)]
+    pub fn is_none(&self) -> bool {
+        matches!(self, Value::None)
+    }
+
+    // Integer or rational, i.e. representable without rounding.
+    #[inline(always)]
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::Rational(_))
     }
 
     #[inline(always)]
@@ -60,20 +243,160 @@ impl Value {
     pub fn to_num(&self) -> Result<f64, ValueError> {
         match self {
             Value::Num(n) => Ok(*n),
-            Value::Bool(_) => Err(ValueError::from_string(format!("{} not a number", self)))
+            Value::Int(i) => Ok(word::widen(*i) as f64),
+            Value::Rational(r) => Ok(r.to_f64().unwrap_or(f64::NAN)),
+            Value::Complex(_) => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a real number", self))),
+            // Ops without an exact decimal version, e.g. sin, compute in floating point
+            Value::Decimal(d) => Ok(d.to_f64()),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a number", self)))
+        }
+    }
+
+    // Real numbers are dimensionless quantities
+    pub fn to_quantity(&self) -> Result<Quantity, ValueError> {
+        match self {
+            Value::Quantity(q) => Ok(q.clone()),
+            _ => Ok(Quantity::dimensionless(self.to_num()?))
+        }
+    }
+
+    // Real numbers are single point intervals
+    pub fn to_interval(&self) -> Result<Interval, ValueError> {
+        match self {
+            Value::Interval(i) => Ok(*i),
+            _ => Ok(Interval::point(self.to_num()?))
+        }
+    }
+
+    // Real numbers have no uncertainty
+    pub fn to_uncertain(&self) -> Result<Uncertain, ValueError> {
+        match self {
+            Value::Uncertain(u) => Ok(u.clone()),
+            _ => Ok(Uncertain::exact(self.to_num()?))
+        }
+    }
+
+    pub fn to_datetime(&self) -> Result<DateTime, ValueError> {
+        match self {
+            Value::DateTime(dt) => Ok(*dt),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a date", self)))
+        }
+    }
+
+    pub fn to_duration(&self) -> Result<Duration, ValueError> {
+        match self {
+            Value::Duration(dur) => Ok(*dur),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a duration", self)))
+        }
+    }
+
+    pub fn to_complex(&self) -> Result<Complex64, ValueError> {
+        match self {
+            Value::Complex(z) => Ok(*z),
+            _ => Ok(Complex64::new(self.to_num()?, 0.0))
+        }
+    }
+
+    pub fn to_int(&self) -> Result<i64, ValueError> {
+        match self {
+            Value::Int(i) => Ok(*i),
+            Value::Num(n) if n.fract() == 0.0 && *n >= -TWO_POW_63 && *n < TWO_POW_63 => Ok(*n as i64),
+            Value::Rational(r) if r.is_integer() && r.numer().to_i64().is_some() => Ok(r.numer().to_i64().unwrap()),
+            Value::Decimal(d) if d.to_i64().is_some() => Ok(d.to_i64().unwrap()),
+            // Whole numbers that don't fit are out of range rather than not integers
+            Value::Num(n) if n.is_finite() && n.fract() == 0.0 => Err(self.out_of_int_range()),
+            Value::Rational(r) if r.is_integer() => Err(self.out_of_int_range()),
+            Value::Decimal(d) if d.is_integer() => Err(self.out_of_int_range()),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not an integer", self)))
+        }
+    }
+
+    #[inline(always)]
+    fn out_of_int_range(&self) -> ValueError {
+        ValueError::from_string(ErrorKind::Domain, format!("{} out of i64 range", self))
+    }
+
+    pub fn to_rational(&self) -> Result<BigRational, ValueError> {
+        match self {
+            Value::Int(i) => Ok(BigRational::from_integer(BigInt::from(*i))),
+            Value::Rational(r) => Ok(r.clone()),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not an exact number", self)))
+        }
+    }
+
+    // Integers convert exactly; floats and rationals need an explicit conversion
+    pub fn to_decimal(&self) -> Result<Decimal, ValueError> {
+        match self {
+            Value::Decimal(d) => Ok(d.clone()),
+            Value::Int(i) => Ok(Decimal::from_int(*i)),
+            Value::Num(_) | Value::Rational(_) => Err(ValueError::from_string(
+                ErrorKind::TypeMismatch,
+                format!("{} not a decimal - convert explicitly with dec", self)
+            )),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a decimal", self)))
         }
     }
 
     pub fn to_bool(&self) -> Result<bool, ValueError> {
         match self {
-            Value::Num(_) => Err(ValueError::from_string(format!("{} not a boolean", self))),
-            Value::Bool(b) => Ok(*b)
+            Value::Bool(b) => Ok(*b),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a boolean", self)))
+        }
+    }
+
+    pub fn to_str(&self) -> Result<&str, ValueError> {
+        match self {
+            Value::Str(s) => Ok(s),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a string", self)))
+        }
+    }
+
+    pub fn to_list(&self) -> Result<&[Value], ValueError> {
+        match self {
+            Value::List(items) => Ok(items),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a list", self)))
+        }
+    }
+
+    pub fn to_record(&self) -> Result<&Record, ValueError> {
+        match self {
+            Value::Record(rec) => Ok(rec),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a record", self)))
+        }
+    }
+
+    // Lists of numbers are vectors
+    pub fn to_vector(&self) -> Result<Vector, ValueError> {
+        match self {
+            Value::Vector(vec) => Ok(vec.clone()),
+            Value::List(items) => Ok(Vector::new(items.iter().map(|item| item.to_num()).collect::<Result<Vec<f64>, ValueError>>()?)?),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a vector", self)))
+        }
+    }
+
+    // Lists of rows, each a list of numbers or a vector, are matrices
+    pub fn to_matrix(&self) -> Result<Matrix, ValueError> {
+        match self {
+            Value::Matrix(mat) => Ok(mat.clone()),
+            Value::List(rows) => Ok(Matrix::new(
+                rows.iter()
+                    .map(|row| match row {
+                        Value::List(_) | Value::Vector(_) => Ok(row.to_vector()?.items().to_vec()),
+                        _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a matrix row", row)))
+                    })
+                    .collect::<Result<Vec<Vec<f64>>, ValueError>>()?
+            )?),
+            _ => Err(ValueError::from_string(ErrorKind::TypeMismatch, format!("{} not a matrix", self)))
         }
     }
 
     pub fn as_num(&self) -> f64 {
         match self {
             Value::Num(n) => *n,
+            Value::Int(i) => word::widen(*i) as f64,
+            Value::Rational(r) => r.to_f64().unwrap_or(f64::NAN),
+            Value::Decimal(d) => d.to_f64(),
+            Value::Complex(z) => z.re,
             Value::Bool(b) => {
                 if *b {
                     1.0
@@ -81,13 +404,52 @@ impl Value {
                     0.0
                 }
             }
+            Value::Str(s) => s.trim().parse::<f64>().unwrap_or(f64::NAN),
+            Value::List(_) | Value::Record(_) | Value::Vector(_) | Value::Matrix(_) => f64::NAN,
+            Value::Quantity(q) => q.magnitude(),
+            Value::Interval(i) => i.mid(),
+            Value::Uncertain(u) => u.value(),
+            Value::DateTime(dt) => dt.secs() as f64,
+            Value::Duration(dur) => dur.secs() as f64,
+            Value::Unknown | Value::None => f64::NAN
         }
     }
 
     pub fn as_bool(&self) -> bool {
         match self {
             Value::Num(n) => *n != 0.0,
-            Value::Bool(b) => *b
+            Value::Int(i) => *i != 0,
+            Value::Rational(r) => !r.is_zero(),
+            Value::Decimal(d) => !d.is_zero(),
+            Value::Complex(z) => !z.is_zero(),
+            Value::Bool(b) => *b,
+            Value::Str(s) => !s.is_empty(),
+            Value::List(items) => !items.is_empty(),
+            Value::Record(rec) => !rec.is_empty(),
+            Value::Vector(vec) => !vec.is_zero(),
+            Value::Matrix(mat) => !mat.is_zero(),
+            Value::Quantity(q) => q.value() != 0.0,
+            Value::Interval(i) => i.lo() != 0.0 || i.hi() != 0.0,
+            Value::Uncertain(u) => u.value() != 0.0,
+            Value::DateTime(_) => true,
+            Value::Duration(dur) => dur.secs() != 0,
+            Value::Unknown | Value::None => false
+        }
+    }
+
+    // No NaN or infinite parts; exact and non-numeric values are always finite
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Value::Num(n) => n.is_finite(),
+            Value::Complex(z) => z.is_finite(),
+            Value::List(items) => items.iter().all(|item| item.is_finite()),
+            Value::Record(rec) => rec.fields().iter().all(|(_, value)| value.is_finite()),
+            Value::Vector(vec) => vec.items().iter().all(|x| x.is_finite()),
+            Value::Matrix(mat) => (0..mat.rows()).all(|r| (0..mat.cols()).all(|c| mat.get(r, c).is_finite())),
+            Value::Quantity(q) => q.value().is_finite(),
+            Value::Interval(i) => i.lo().is_finite() && i.hi().is_finite(),
+            Value::Uncertain(u) => u.value().is_finite() && u.sigma().is_finite(),
+            _ => true
         }
     }
 }
@@ -96,32 +458,150 @@ impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Value::Num(n) => write!(f, "{}", n),
-            Value::Bool(b) => write!(f, "{}", b)
+            Value::Int(i) => write!(f, "{}", word::widen(*i)),
+            Value::Rational(r) => write!(f, "{}", r),
+            Value::Decimal(d) => write!(f, "{}", d),
+            Value::Complex(z) => write!(f, "{}", complex_to_string(z, |x| x.to_string())),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::List(items) => write!(f, "{}", list_to_string(items, |v| v.to_string())),
+            Value::Record(rec) => write!(f, "{}", record_to_string(rec, |v| v.to_string())),
+            Value::Vector(vec) => write!(f, "{}", vec),
+            Value::Matrix(mat) => write!(f, "{}", mat),
+            Value::Quantity(q) => write!(f, "{}", q),
+            Value::Interval(i) => write!(f, "{}", i),
+            Value::Uncertain(u) => write!(f, "{}", u),
+            Value::DateTime(dt) => write!(f, "{}", dt),
+            Value::Duration(dur) => write!(f, "{}", dur),
+            Value::Unknown => write!(f, "unknown"),
+            Value::None => write!(f, "none")
         }
     }
 }
 
+// Complex numbers print as a+bi or a-bi, omitting zero parts and writing a unit imaginary part as i,
+// e.g. i, -1 and 2-i.
+pub fn complex_to_string(z: &Complex64, fmt_num: impl Fn(f64) -> String) -> String {
+    let imag = |b: f64| if b == 1.0 { String::from("i") } else { format!("{}i", fmt_num(b)) };
+    let sign = if z.im < 0.0 { "-" } else { "+" };
+    match (z.re == 0.0, z.im == 0.0) {
+        (_, true) => fmt_num(z.re),
+        (true, false) if z.im < 0.0 => format!("-{}", imag(-z.im)),
+        (true, false) => imag(z.im),
+        (false, false) => format!("{}{}{}", fmt_num(z.re), sign, imag(z.im.abs()))
+    }
+}
+
+// Lists print as [a, b, c], with string elements quoted.
+pub fn list_to_string(items: &[Value], fmt_value: impl Fn(&Value) -> String) -> String {
+    let elements: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            Value::Str(s) => format!("{:?}", s),
+            _ => fmt_value(item)
+        })
+        .collect();
+    format!("[{}]", elements.join(", "))
+}
+
+// Records print as {name: value, ...}, with string values quoted.
+pub fn record_to_string(rec: &Record, fmt_value: impl Fn(&Value) -> String) -> String {
+    let fields: Vec<String> = rec
+        .fields()
+        .iter()
+        .map(|(name, value)| match value {
+            Value::Str(s) => format!("{}: {:?}", name, s),
+            _ => format!("{}: {}", name, fmt_value(value))
+        })
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+// Unit conversion and uncertainty propagation leave rounding noise in the last digits,
+// e.g. 67.99999999999999 degF, so quantities and uncertain values display 15 significant digits.
+const DISPLAY_DIGITS: usize = 15;
+
+// x rounded to DISPLAY_DIGITS significant digits
+#[inline(always)]
+pub fn display_round(x: f64) -> f64 {
+    format!("{:.*e}", DISPLAY_DIGITS - 1, x).parse::<f64>().unwrap_or(x)
+}
+
 impl cmp::PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
-        match self {
-            Value::Num(n) => other.is_num() && *n == other.to_num().unwrap(),
-            Value::Bool(b) => other.is_bool() && *b == other.to_bool().unwrap()
-        }
+        self.partial_cmp(other) == Some(cmp::Ordering::Equal)
     }
 }
 
 impl cmp::PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        if self.is_num() && other.is_num() {
-            self.to_num().unwrap().partial_cmp(&other.to_num().unwrap())
-        } else if self.is_bool() && other.is_bool() {
-            self.to_bool().unwrap().partial_cmp(&other.to_bool().unwrap())
-        } else {
-            None
+        match (self, other) {
+            (Value::Num(l), Value::Num(r)) => l.partial_cmp(r),
+            (Value::Int(l), Value::Int(r)) => word::widen(*l).partial_cmp(&word::widen(*r)),
+            (Value::Int(l), Value::Num(r)) => int_float_cmp(*l, *r),
+            (Value::Num(l), Value::Int(r)) => int_float_cmp(*r, *l).map(cmp::Ordering::reverse),
+            (Value::Rational(l), Value::Num(r)) => rational_float_cmp(l, *r),
+            (Value::Num(l), Value::Rational(r)) => rational_float_cmp(r, *l).map(cmp::Ordering::reverse),
+            // Decimals compare exactly with integers, and not at all with floats and rationals
+            (Value::Decimal(_), Value::Decimal(_) | Value::Int(_)) | (Value::Int(_), Value::Decimal(_)) => {
+                self.to_decimal().ok()?.partial_cmp(&other.to_decimal().ok()?)
+            }
+            (Value::Rational(_), _) | (_, Value::Rational(_)) if self.is_exact() && other.is_exact() => {
+                self.to_rational().ok()?.partial_cmp(&other.to_rational().ok()?)
+            }
+            // Complex numbers are unordered, so only equality is defined
+            (Value::Complex(_), _) | (_, Value::Complex(_)) => match (self.to_complex(), other.to_complex()) {
+                (Ok(l), Ok(r)) if l == r => Some(cmp::Ordering::Equal),
+                _ => None
+            },
+            // Uncertain values compare by their nominal values
+            (Value::Uncertain(_), _) | (_, Value::Uncertain(_)) => match (self.to_uncertain(), other.to_uncertain()) {
+                (Ok(l), Ok(r)) => l.partial_cmp(&r),
+                _ => None
+            },
+            (Value::Bool(l), Value::Bool(r)) => l.partial_cmp(r),
+            (Value::DateTime(l), Value::DateTime(r)) => l.partial_cmp(r),
+            (Value::Duration(l), Value::Duration(r)) => l.partial_cmp(r),
+            (Value::Str(l), Value::Str(r)) => l.partial_cmp(r),
+            (Value::List(l), Value::List(r)) => l.partial_cmp(r),
+            // Records are unordered, so only equality is defined
+            (Value::Record(l), Value::Record(r)) if l == r => Some(cmp::Ordering::Equal),
+            // Vectors and matrices are unordered, so only equality is defined
+            (Value::Vector(l), Value::Vector(r)) if l == r => Some(cmp::Ordering::Equal),
+            (Value::Matrix(l), Value::Matrix(r)) if l == r => Some(cmp::Ordering::Equal),
+            (Value::Quantity(l), Value::Quantity(r)) => l.partial_cmp(r),
+            (Value::Interval(l), Value::Interval(r)) => l.partial_cmp(r),
+            (Value::None, Value::None) => Some(cmp::Ordering::Equal),
+            // Unknown compares with nothing, not even itself, so == unknown unknown is unknown
+            _ => None
         }
     }
 }
 
+// Exact comparison of an integer with a float, without rounding the integer first.
+fn int_float_cmp(i: i64, n: f64) -> Option<cmp::Ordering> {
+    match (i as f64).partial_cmp(&n)? {
+        cmp::Ordering::Equal if n >= TWO_POW_63 => Some(cmp::Ordering::Less),
+        cmp::Ordering::Equal => Some(i.cmp(&(n as i64))),
+        ord => Some(ord)
+    }
+}
+
+// Exact comparison of a rational with a float, using the float's exact binary value.
+fn rational_float_cmp(r: &BigRational, n: f64) -> Option<cmp::Ordering> {
+    match BigRational::from_float(n) {
+        Some(nr) => Some(r.cmp(&nr)),
+        None if n.is_nan() => None,
+        None if n > 0.0 => Some(cmp::Ordering::Less),
+        None => Some(cmp::Ordering::Greater)
+    }
+}
+
+const TWO_POW_63: f64 = 9223372036854775808.0;
+
+// Largest numerator or denominator, in bits, of rational literals and powers, about 315,000 digits
+pub const MAX_RATIONAL_BITS: u64 = 1 << 20;
+
 // --------------------------------------------------------------------------------
 // Value Result
 
@@ -138,9 +618,12 @@ mod tests {
     fn test_value_error() {
         let five = Value::from_num(5.0);
         let yes = Value::from_bool(true);
-        assert_eq!(format!("{}", ValueError::new("foobar")), "foobar");
+        let err = ValueError::new(ErrorKind::Domain, "foobar");
+        assert_eq!(format!("{}", err), "foobar");
+        assert_eq!(err.kind(), ErrorKind::Domain);
         assert_eq!(format!("{}", five.to_bool().unwrap_err()), "5 not a boolean");
         assert_eq!(format!("{}", yes.to_num().unwrap_err()), "true not a number");
+        assert_eq!(yes.to_num().unwrap_err().kind(), ErrorKind::TypeMismatch);
     }
 
     #[test]
@@ -155,6 +638,83 @@ mod tests {
         assert_eq!(five.to_string(), "5");
     }
 
+    #[test]
+    fn test_display_round() {
+        assert_eq!(display_round(67.99999999999999), 68.0);
+        assert_eq!(display_round(0.1 + 0.2), 0.3);
+        assert_eq!(display_round(1.23456789012345e-20), 1.23456789012345e-20);
+        assert!(display_round(f64::NAN).is_nan());
+        assert_eq!(display_round(f64::INFINITY), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_value_int() {
+        let five = Value::from_int(5);
+        assert!(five.is_num());
+        assert!(five.is_int());
+        assert!(!five.is_bool());
+
+        assert_eq!(five.to_num().unwrap(), 5.0);
+        assert_eq!(five.to_int().unwrap(), 5);
+        assert!(five.to_bool().is_err());
+
+        assert_eq!(Value::from_num(5.0).to_int().unwrap(), 5);
+        assert!(Value::from_num(5.5).to_int().is_err());
+        let err = Value::from_num(1e20).to_int().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Domain);
+        assert_eq!(format!("{}", err), "100000000000000000000 out of i64 range");
+        assert!(Value::from_bool(true).to_int().is_err());
+
+        assert_eq!(five.to_string(), "5");
+        assert_eq!(Value::from_int(i64::MAX).to_string(), "9223372036854775807");
+    }
+
+    #[test]
+    fn test_value_rational() {
+        let third = Value::from_rational(BigRational::new(BigInt::from(1), BigInt::from(3)));
+        assert!(third.is_num());
+        assert!(third.is_rational());
+        assert!(third.is_exact());
+        assert!(!third.is_int());
+        assert!(!third.is_bool());
+
+        assert!((third.to_num().unwrap() - 1.0 / 3.0).abs() < 1e-15);
+        assert!(third.to_int().is_err());
+        assert!(third.to_bool().is_err());
+        assert_eq!(Value::from_int(2).to_rational().unwrap(), BigRational::from_integer(BigInt::from(2)));
+        assert!(Value::from_num(0.5).to_rational().is_err());
+
+        let two = Value::from_rational(BigRational::from_integer(BigInt::from(2)));
+        assert_eq!(two.to_int().unwrap(), 2);
+
+        assert_eq!(third.to_string(), "1/3");
+        assert_eq!(two.to_string(), "2");
+    }
+
+    #[test]
+    fn test_value_complex() {
+        let z = Value::from_complex(Complex64::new(3.0, 4.0));
+        assert!(z.is_complex());
+        assert!(!z.is_num());
+        assert!(!z.is_bool());
+
+        assert_eq!(z.to_complex().unwrap(), Complex64::new(3.0, 4.0));
+        assert_eq!(Value::from_int(2).to_complex().unwrap(), Complex64::new(2.0, 0.0));
+        assert!(Value::from_bool(true).to_complex().is_err());
+        assert_eq!(format!("{}", z.to_num().unwrap_err()), "3+4i not a real number");
+        assert!(z.to_int().is_err());
+
+        assert_eq!(z.to_string(), "3+4i");
+        assert_eq!(Value::from_complex(Complex64::new(3.0, -4.5)).to_string(), "3-4.5i");
+        assert_eq!(Value::from_complex(Complex64::new(0.0, 1.0)).to_string(), "i");
+        assert_eq!(Value::from_complex(Complex64::new(0.0, -2.5)).to_string(), "-2.5i");
+        assert_eq!(Value::from_complex(Complex64::new(2.0, -1.0)).to_string(), "2-i");
+        assert_eq!(Value::from_complex(Complex64::new(-1.0, 0.0)).to_string(), "-1");
+        assert_eq!(Value::from_complex(Complex64::new(0.0, 0.0)).to_string(), "0");
+        assert!(z.as_bool());
+        assert!(!Value::from_complex(Complex64::new(0.0, 0.0)).as_bool());
+    }
+
     #[test]
     fn test_value_bool() {
         let flag = Value::from_bool(true);
@@ -162,11 +722,230 @@ mod tests {
         assert!(flag.is_bool());
 
         assert!(flag.to_num().is_err());
-        assert_eq!(flag.to_bool().unwrap(), true);
+        assert!(flag.to_bool().unwrap());
 
         assert_eq!(flag.to_string(), "true");
     }
 
+    #[test]
+    fn test_value_str() {
+        let hello = Value::from_string(String::from("hello"));
+        assert!(hello.is_str());
+        assert!(!hello.is_num());
+        assert!(!hello.is_bool());
+
+        assert_eq!(hello.to_str().unwrap(), "hello");
+        assert_eq!(format!("{}", hello.to_num().unwrap_err()), "hello not a number");
+        assert!(hello.to_bool().is_err());
+        assert!(Value::from_int(5).to_str().is_err());
+
+        assert_eq!(hello.to_string(), "hello");
+        assert!(hello.as_bool());
+        assert!(!Value::from_string(String::new()).as_bool());
+        assert_eq!(Value::from_string(String::from(" 2.5 ")).as_num(), 2.5);
+        assert!(hello.as_num().is_nan());
+    }
+
+    #[test]
+    fn test_value_list() {
+        let list = Value::from_list(vec![Value::from_int(1), Value::from_num(2.5), Value::from_string(String::from("a b"))]);
+        assert!(list.is_list());
+        assert!(!list.is_num());
+        assert!(!list.is_str());
+
+        assert_eq!(list.to_list().unwrap().len(), 3);
+        assert_eq!(format!("{}", list.to_num().unwrap_err()), "[1, 2.5, \"a b\"] not a number");
+        assert!(Value::from_int(1).to_list().is_err());
+
+        assert_eq!(list.to_string(), "[1, 2.5, \"a b\"]");
+        assert_eq!(Value::from_list(vec![]).to_string(), "[]");
+        assert_eq!(Value::from_list(vec![Value::from_list(vec![Value::from_bool(true)])]).to_string(), "[[true]]");
+
+        assert!(list.as_bool());
+        assert!(!Value::from_list(vec![]).as_bool());
+        assert!(list.as_num().is_nan());
+    }
+
+    #[test]
+    fn test_value_none() {
+        let none = Value::from_none();
+        assert!(none.is_none());
+        assert!(!none.is_num());
+        assert!(!Value::from_bool(false).is_none());
+        assert_eq!(none.to_string(), "none");
+        assert_eq!(format!("{}", none.to_num().unwrap_err()), "none not a number");
+        assert!(none.to_bool().is_err());
+        assert!(none.as_num().is_nan());
+        assert!(!none.as_bool());
+        assert!(none == Value::from_none());
+        assert!(none != Value::from_bool(false));
+        assert!(none != Value::from_int(0));
+    }
+
+    #[test]
+    fn test_value_is_finite() {
+        assert!(Value::from_num(1.5).is_finite());
+        assert!(!Value::from_num(f64::NAN).is_finite());
+        assert!(!Value::from_num(f64::INFINITY).is_finite());
+        assert!(Value::from_int(i64::MAX).is_finite());
+        assert!(Value::from_string(String::from("inf")).is_finite());
+        assert!(Value::from_list(vec![Value::from_num(1.0), Value::from_int(2)]).is_finite());
+        assert!(!Value::from_list(vec![Value::from_num(1.0), Value::from_num(f64::NAN)]).is_finite());
+        assert!(Value::from_none().is_finite());
+    }
+
+    #[test]
+    fn test_value_interval() {
+        let ival = Value::from_interval(Interval::new(1.0, 3.0).unwrap());
+        assert!(ival.is_interval());
+        assert!(!ival.is_num());
+        assert_eq!(ival.to_string(), "[1 .. 3]");
+        assert_eq!(ival.to_interval().unwrap(), Interval::new(1.0, 3.0).unwrap());
+        assert_eq!(Value::from_int(2).to_interval().unwrap(), Interval::point(2.0));
+        assert!(Value::from_bool(true).to_interval().is_err());
+        assert_eq!(format!("{}", ival.to_num().unwrap_err()), "[1 .. 3] not a number");
+        assert_eq!(ival.as_num(), 2.0);
+        assert!(ival.as_bool());
+
+        assert_eq!(Value::from_truth(Some(true)), Value::from_bool(true));
+        assert_eq!(Value::from_truth(Some(false)), Value::from_bool(false));
+        let unknown = Value::from_truth(None);
+        assert!(unknown.is_unknown());
+        assert_eq!(unknown.to_string(), "unknown");
+        assert!(!unknown.as_bool());
+        assert!(unknown.to_bool().is_err());
+        assert!(unknown != Value::Unknown);
+        assert_eq!(unknown.partial_cmp(&Value::Unknown), None);
+        assert!(unknown != Value::from_bool(false));
+    }
+
+    #[test]
+    fn test_value_uncertain() {
+        let uval = Value::from_uncertain(Uncertain::new(9.81, 0.02).unwrap());
+        assert!(uval.is_uncertain());
+        assert!(!uval.is_num());
+        assert_eq!(uval.to_string(), "9.81 ± 0.02");
+        assert_eq!(uval.to_uncertain().unwrap().sigma(), 0.02);
+        assert_eq!(Value::from_int(2).to_uncertain().unwrap().sigma(), 0.0);
+        assert!(Value::from_bool(true).to_uncertain().is_err());
+        assert_eq!(format!("{}", uval.to_num().unwrap_err()), "9.81 ± 0.02 not a number");
+        assert_eq!(uval.as_num(), 9.81);
+        assert!(uval.as_bool());
+
+        assert!(uval == Value::from_num(9.81));
+        assert!(uval < Value::from_int(10));
+        assert!(Value::from_int(9) < uval);
+        assert!(uval != Value::from_bool(true));
+    }
+
+    #[test]
+    fn test_value_record() {
+        let inner = Record::new().with("x", Value::from_int(1));
+        let rec = Value::from_record(
+            Record::new()
+                .with("name", Value::from_string(String::from("a b")))
+                .with("inner", Value::from_record(inner))
+        );
+        assert!(rec.is_record());
+        assert!(!rec.is_list());
+        assert_eq!(rec.to_string(), "{name: \"a b\", inner: {x: 1}}");
+        assert_eq!(rec.to_record().unwrap().len(), 2);
+        assert_eq!(format!("{}", Value::from_int(1).to_record().unwrap_err()), "1 not a record");
+        assert!(rec.as_bool());
+        assert!(!Value::from_record(Record::new()).as_bool());
+        assert!(rec.as_num().is_nan());
+        assert!(rec == rec.clone());
+        assert!(rec != Value::from_record(Record::new()));
+        assert!(rec.partial_cmp(&Value::from_record(Record::new())).is_none());
+        assert_eq!(Value::from_record(Record::new()).to_string(), "{}");
+    }
+
+    #[test]
+    fn test_value_vector_matrix() {
+        let list = |items: &[i64]| Value::from_list(items.iter().map(|i| Value::from_int(*i)).collect());
+        let vec = list(&[1, 2, 3]).to_vector().unwrap();
+        assert_eq!(vec.items(), &[1.0, 2.0, 3.0]);
+        assert_eq!(Value::from_vector(vec.clone()).to_vector().unwrap(), vec);
+        assert!(Value::from_vector(vec.clone()).is_vector());
+        assert_eq!(Value::from_vector(vec.clone()).to_string(), "(1, 2, 3)");
+        assert_eq!(format!("{}", Value::from_list(vec![]).to_vector().unwrap_err()), "Empty vector");
+        assert_eq!(format!("{}", Value::from_int(1).to_vector().unwrap_err()), "1 not a vector");
+        assert!(Value::from_list(vec![Value::from_bool(true)]).to_vector().is_err());
+
+        let rows = Value::from_list(vec![list(&[1, 2]), Value::from_vector(list(&[3, 4]).to_vector().unwrap())]);
+        let mat = Value::from_matrix(rows.to_matrix().unwrap());
+        assert!(mat.is_matrix());
+        assert_eq!(mat.to_string(), "[1  2]\n[3  4]");
+        assert_eq!(mat.to_matrix().unwrap().get(1, 0), 3.0);
+        assert_eq!(format!("{}", list(&[1, 2]).to_matrix().unwrap_err()), "1 not a matrix row");
+        assert_eq!(
+            format!("{}", Value::from_list(vec![list(&[1, 2]), list(&[3])]).to_matrix().unwrap_err()),
+            "Matrix rows must have equal length"
+        );
+        assert_eq!(format!("{}", Value::from_int(1).to_matrix().unwrap_err()), "1 not a matrix");
+
+        assert!(Value::from_vector(vec.clone()).as_bool());
+        assert!(!Value::from_vector(list(&[0, 0]).to_vector().unwrap()).as_bool());
+        assert!(mat.as_bool());
+        assert!(mat == Value::from_matrix(rows.to_matrix().unwrap()));
+        assert!(Value::from_vector(vec.clone()) != list(&[1, 2, 3]));
+        assert!(Value::from_vector(vec.clone()).partial_cmp(&Value::from_vector(vec.map(|x| x + 1.0))).is_none());
+    }
+
+    #[test]
+    fn test_value_decimal() {
+        let price = Value::from_decimal(Decimal::parse("19.99").unwrap());
+        assert!(price.is_decimal());
+        assert!(!price.is_num());
+        assert!(!price.is_exact());
+        assert_eq!(price.to_string(), "19.99");
+        assert_eq!(price.to_decimal().unwrap(), Decimal::parse("19.99").unwrap());
+        assert_eq!(Value::from_int(2).to_decimal().unwrap(), Decimal::from_int(2));
+        assert_eq!(
+            format!("{}", Value::from_num(0.5).to_decimal().unwrap_err()),
+            "0.5 not a decimal - convert explicitly with dec"
+        );
+        assert_eq!(format!("{}", Value::from_bool(true).to_decimal().unwrap_err()), "true not a decimal");
+        assert_eq!(price.to_num().unwrap(), 19.99);
+        assert_eq!(Value::from_decimal(Decimal::parse("12.00").unwrap()).to_int().unwrap(), 12);
+        assert!(price.to_int().is_err());
+        assert_eq!(price.as_num(), 19.99);
+        assert!(price.as_bool());
+
+        assert!(price == Value::from_decimal(Decimal::parse("19.990").unwrap()));
+        assert!(price < Value::from_int(20));
+        assert!(Value::from_int(19) < price);
+        assert!(price != Value::from_num(19.99));
+        assert!(Value::from_num(19.99).partial_cmp(&price).is_none());
+    }
+
+    #[test]
+    fn test_value_datetime() {
+        let date = Value::from_datetime(DateTime::parse("2024-03-15").unwrap());
+        assert!(date.is_datetime());
+        assert!(!date.is_num());
+        assert_eq!(date.to_string(), "2024-03-15");
+        assert_eq!(date.to_datetime().unwrap(), DateTime::parse("2024-03-15").unwrap());
+        assert_eq!(format!("{}", Value::from_int(1).to_datetime().unwrap_err()), "1 not a date");
+        assert_eq!(format!("{}", date.to_num().unwrap_err()), "2024-03-15 not a number");
+        assert_eq!(date.as_num(), 1710460800.0);
+        assert!(date.as_bool());
+        assert!(date < Value::from_datetime(DateTime::parse("2024-03-15T00:00:01").unwrap()));
+        assert!(date == Value::from_datetime(DateTime::parse("2024-03-15T00:00").unwrap()));
+        assert!(date != Value::from_num(1710460800.0));
+
+        let dur = Value::from_duration(Duration::new(90));
+        assert!(dur.is_duration());
+        assert_eq!(dur.to_string(), "1m 30s");
+        assert_eq!(dur.to_duration().unwrap(), Duration::new(90));
+        assert_eq!(format!("{}", date.to_duration().unwrap_err()), "2024-03-15 not a duration");
+        assert_eq!(dur.as_num(), 90.0);
+        assert!(dur.as_bool());
+        assert!(!Value::from_duration(Duration::new(0)).as_bool());
+        assert!(dur > Value::from_duration(Duration::new(60)));
+        assert!(dur != date);
+    }
+
     #[test]
     fn test_value_equal() {
         let five1 = Value::from_num(5.0);
@@ -183,6 +962,41 @@ mod tests {
         assert!(yes1 == yes2);
         assert!(yes1 != no);
         assert!(yes1 != five1);
+
+        let int5 = Value::from_int(5);
+        assert!(int5 == five1);
+        assert!(int5 != six);
+        assert!(int5 != yes1);
+        assert!(Value::from_int(i64::MAX) != Value::from_int(i64::MAX - 1));
+        assert!(Value::from_int(i64::MAX) != Value::from_num(i64::MAX as f64));
+
+        let half = Value::from_rational(BigRational::new(BigInt::from(1), BigInt::from(2)));
+        let tenth = Value::from_rational(BigRational::new(BigInt::from(1), BigInt::from(10)));
+        let rat5 = Value::from_rational(BigRational::from_integer(BigInt::from(5)));
+        assert!(half == Value::from_num(0.5));
+        assert!(tenth != Value::from_num(0.1));
+        assert!(rat5 == int5);
+        assert!(rat5 == five1);
+        assert!(rat5 != yes1);
+
+        let z = Value::from_complex(Complex64::new(3.0, 4.0));
+        let real5 = Value::from_complex(Complex64::new(5.0, 0.0));
+        assert!(z == Value::from_complex(Complex64::new(3.0, 4.0)));
+        assert!(z != Value::from_complex(Complex64::new(3.0, -4.0)));
+        assert!(real5 == int5);
+        assert!(five1 == real5);
+        assert!(z != five1);
+        assert!(z != yes1);
+
+        let abc = Value::from_string(String::from("abc"));
+        assert!(abc == Value::from_string(String::from("abc")));
+        assert!(abc != Value::from_string(String::from("abd")));
+        assert!(Value::from_string(String::from("5")) != five1);
+
+        let list12 = Value::from_list(vec![Value::from_int(1), Value::from_int(2)]);
+        assert!(list12 == Value::from_list(vec![Value::from_num(1.0), Value::from_int(2)]));
+        assert!(list12 != Value::from_list(vec![Value::from_int(1)]));
+        assert!(list12 != five1);
     }
 
     #[test]
@@ -203,6 +1017,38 @@ mod tests {
         assert!(yes1 <= yes2);
         assert!(yes1 > no);
         assert!(yes1 >= yes2);
+
+        let int5 = Value::from_int(5);
+        assert!(int5 < six);
+        assert!(int5 <= five1);
+        assert!(six > int5);
+        assert!(Value::from_int(9007199254740993) > Value::from_num(9007199254740992.0));
+        assert!(Value::from_num(5.5) > int5);
+        assert!(int5.partial_cmp(&yes1).is_none());
+
+        let half = Value::from_rational(BigRational::new(BigInt::from(1), BigInt::from(2)));
+        assert!(half < Value::from_int(1));
+        assert!(half > Value::from_num(0.25));
+        assert!(half < Value::from_num(f64::INFINITY));
+        assert!(half > Value::from_num(f64::NEG_INFINITY));
+        assert!(half.partial_cmp(&Value::from_num(f64::NAN)).is_none());
+
+        let z = Value::from_complex(Complex64::new(3.0, 4.0));
+        assert!(z.partial_cmp(&Value::from_complex(Complex64::new(1.0, 1.0))).is_none());
+        assert!(z.partial_cmp(&six).is_none());
+
+        let abc = Value::from_string(String::from("abc"));
+        let abd = Value::from_string(String::from("abd"));
+        assert!(abc < abd);
+        assert!(abd >= abc);
+        assert!(Value::from_string(String::from("B")) < abc);
+        assert!(abc.partial_cmp(&five1).is_none());
+
+        let list12 = Value::from_list(vec![Value::from_int(1), Value::from_int(2)]);
+        let list13 = Value::from_list(vec![Value::from_int(1), Value::from_int(3)]);
+        assert!(list12 < list13);
+        assert!(Value::from_list(vec![Value::from_int(1)]) < list12);
+        assert!(list12.partial_cmp(&five1).is_none());
     }
 
     #[test]
@@ -217,9 +1063,14 @@ mod tests {
         assert_eq!(yes.as_num(), 1.0);
         assert_eq!(no.as_num(), 0.0);
 
-        assert_eq!(five.as_bool(), true);
-        assert_eq!(zero.as_bool(), false);
-        assert_eq!(yes.as_bool(), true);
-        assert_eq!(no.as_bool(), false);
+        assert!(five.as_bool());
+        assert!(!zero.as_bool());
+        assert!(Value::from_int(3).as_bool());
+        assert!(!Value::from_int(0).as_bool());
+        assert_eq!(Value::from_int(3).as_num(), 3.0);
+        assert!(Value::from_rational(BigRational::new(BigInt::from(1), BigInt::from(3))).as_bool());
+        assert!(!Value::from_rational(BigRational::zero()).as_bool());
+        assert!(yes.as_bool());
+        assert!(!no.as_bool());
     }
 }
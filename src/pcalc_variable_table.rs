@@ -1,3 +1,7 @@
+use crate::pcalc_error::ErrorKind;
+use crate::pcalc_format::Format;
+use crate::pcalc_keywords as keywords;
+use crate::pcalc_suggest as suggest;
 use crate::pcalc_value::{Value, ValueError, ValueResult};
 use std::collections::HashMap;
 use std::fmt;
@@ -13,30 +17,48 @@ impl VariableTable {
 
     pub fn get(&self, name: &str) -> ValueResult {
         if let Some(value) = self.table.get(name) {
-            Ok(*value)
+            Ok(value.clone())
         } else {
-            Err(ValueError::new(&format!("Unknown variable '{}'", name)))
+            Err(self.unknown(name))
         }
     }
 
     pub fn def(&mut self, name: &str, value: Value) -> ValueResult {
         if !self.table.contains_key(name) {
-            self.table.insert(String::from(name), value);
+            self.table.insert(String::from(name), value.clone());
             Ok(value)
         } else {
-            Err(ValueError::new(&format!("Duplicate variable definition '{}'", name)))
+            Err(ValueError::from_string(
+                ErrorKind::DuplicateDefinition,
+                format!("Duplicate variable definition '{}'", name)
+            ))
         }
     }
 
     pub fn set(&mut self, name: &str, value: Value) -> ValueResult {
         if let Some(val) = self.table.get_mut(name) {
-            *val = value;
+            *val = value.clone();
             Ok(value)
         } else {
-            Err(ValueError::new(&format!("Unknown variable '{}'", name)))
+            Err(self.unknown(name))
         }
     }
 
+    // Suggests variables and keywords, since a misspelled operator or constant parses as a variable, e.g. sqr
+    fn unknown(&self, name: &str) -> ValueError {
+        let keyword_names = [
+            keywords::binary_ops(),
+            keywords::unary_ops(),
+            keywords::ternary_ops(),
+            keywords::special_ftns(),
+            keywords::constants(),
+            vec![keywords::TRUE, keywords::FALSE, keywords::NONE]
+        ]
+        .concat();
+        ValueError::from_string(ErrorKind::UnknownVariable, format!("Unknown variable '{}'", name))
+            .with_suggestions(suggest::closest(name, self.table.keys().map(String::as_str).chain(keyword_names)))
+    }
+
     #[inline(always)]
     pub fn reset(&mut self) {
         self.table.clear();
@@ -52,12 +74,12 @@ impl VariableTable {
         self.table.is_empty()
     }
 
-    pub fn show(&self) {
+    pub fn show(&self, format: &Format) {
         let width = self.table.iter().map(|kv| kv.0.len()).max().unwrap_or(0);
         Self::prt_name_value(width, "var", "value");
         Self::prt_name_value(width, "---", "-----");
         for (name, value) in &self.table {
-            Self::prt_name_value(width, name, value);
+            Self::prt_name_value(width, name, &format.value(value));
         }
     }
 
@@ -95,6 +117,15 @@ mod tests {
         assert!(!vtab.is_empty());
 
         assert!(vtab.get("y").is_err());
+        assert_eq!(vtab.get("xx").unwrap_err().suggestions(), ["x"]);
+        assert!(vtab.set("y", Value::from_int(1)).unwrap_err().suggestions().is_empty());
+        assert_eq!(vtab.get("sqr").unwrap_err().suggestions(), ["sqrt", "shr", "str"]);
+        assert_eq!(vtab.get("pii").unwrap_err().suggestions(), ["pi", "phi"]);
+
+        vtab.def("n", Value::from_int(i64::MAX)).unwrap();
+        assert!(vtab.get("n").unwrap().is_int());
+        assert_eq!(vtab.get("n").unwrap(), Value::from_int(i64::MAX));
+        assert_eq!(vtab.len(), 2);
 
         vtab.reset();
         assert_eq!(vtab.len(), 0);
@@ -0,0 +1,284 @@
+use std::cell::Cell;
+
+// --------------------------------------------------------------------------------
+// Word Size - Integer width and signedness
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordSize {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64
+}
+
+impl WordSize {
+    pub fn parse(name: &str) -> Option<WordSize> {
+        match name {
+            "u8" => Some(WordSize::U8),
+            "u16" => Some(WordSize::U16),
+            "u32" => Some(WordSize::U32),
+            "u64" => Some(WordSize::U64),
+            "i8" => Some(WordSize::I8),
+            "i16" => Some(WordSize::I16),
+            "i32" => Some(WordSize::I32),
+            "i64" => Some(WordSize::I64),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WordSize::U8 => "u8",
+            WordSize::U16 => "u16",
+            WordSize::U32 => "u32",
+            WordSize::U64 => "u64",
+            WordSize::I8 => "i8",
+            WordSize::I16 => "i16",
+            WordSize::I32 => "i32",
+            WordSize::I64 => "i64"
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            WordSize::U8 | WordSize::I8 => 8,
+            WordSize::U16 | WordSize::I16 => 16,
+            WordSize::U32 | WordSize::I32 => 32,
+            WordSize::U64 | WordSize::I64 => 64
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_signed(&self) -> bool {
+        matches!(self, WordSize::I8 | WordSize::I16 | WordSize::I32 | WordSize::I64)
+    }
+
+    #[inline(always)]
+    pub fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits())
+    }
+
+    pub fn min(&self) -> i128 {
+        if self.is_signed() {
+            -(1i128 << (self.bits() - 1))
+        } else {
+            0
+        }
+    }
+
+    pub fn max(&self) -> i128 {
+        if self.is_signed() {
+            (1i128 << (self.bits() - 1)) - 1
+        } else {
+            self.mask() as i128
+        }
+    }
+
+    // Low bits of the word, sign extended for signed words; u64 words keep their bit pattern in an i64
+    pub fn from_bits(&self, bits: u64) -> i64 {
+        let bits = bits & self.mask();
+        let unused = 64 - self.bits();
+        if self.is_signed() {
+            ((bits << unused) as i64) >> unused
+        } else {
+            bits as i64
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Overflow - What integer arithmetic does with results outside the word
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Check, // Overflow is an error
+    Wrap   // Keep the low bits, e.g. + 255 1 is 0 for u8
+}
+
+impl Overflow {
+    pub fn parse(name: &str) -> Option<Overflow> {
+        match name {
+            "check" => Some(Overflow::Check),
+            "wrap" => Some(Overflow::Wrap),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Overflow::Check => "check",
+            Overflow::Wrap => "wrap"
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Word Context - Word size and overflow used by integer operations
+//
+// Like the decimal context, the word context belongs to the Environment, which binds
+// it while its code runs, so operations keep their plain value signatures. Without a
+// word size (programmer mode off), integers are i64 values.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordContext {
+    size: Option<WordSize>,
+    overflow: Overflow
+}
+
+impl WordContext {
+    pub fn new(size: Option<WordSize>, overflow: Overflow) -> Self {
+        WordContext { size, overflow }
+    }
+
+    #[inline(always)]
+    pub fn size(&self) -> Option<WordSize> {
+        self.size
+    }
+
+    #[inline(always)]
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    #[inline(always)]
+    pub fn word(&self) -> WordSize {
+        self.size.unwrap_or(WordSize::I64)
+    }
+}
+
+impl Default for WordContext {
+    fn default() -> Self {
+        WordContext {
+            size: None,
+            overflow: Overflow::Check
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: Cell<WordContext> = Cell::new(WordContext::default());
+}
+
+#[inline(always)]
+pub fn context() -> WordContext {
+    CONTEXT.with(|ctx| ctx.get())
+}
+
+// Run f with ctx as the context, then restore the previous one
+pub fn with_context<T>(ctx: WordContext, f: impl FnOnce() -> T) -> T {
+    struct Restore(WordContext);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CONTEXT.with(|cell| cell.set(self.0));
+        }
+    }
+    let _restore = Restore(CONTEXT.with(|cell| cell.replace(ctx)));
+    f()
+}
+
+// --------------------------------------------------------------------------------
+// Integer Helpers
+
+// Integer value of i in the current word, reading u64 words as unsigned
+#[inline(always)]
+pub fn widen(i: i64) -> i128 {
+    match context().word() {
+        WordSize::U64 => i as u64 as i128,
+        _ => i as i128
+    }
+}
+
+// Bit pattern of i in the current word
+#[inline(always)]
+pub fn pattern(i: i64) -> u64 {
+    i as u64 & context().word().mask()
+}
+
+// Integer with the given bit pattern in the current word
+#[inline(always)]
+pub fn from_bits(bits: u64) -> i64 {
+    context().word().from_bits(bits)
+}
+
+// Low bits of n in the current word, whatever the overflow mode
+#[inline(always)]
+pub fn wrap(n: i128) -> i64 {
+    context().word().from_bits(n as u64)
+}
+
+#[inline(always)]
+pub fn is_wrapping() -> bool {
+    context().overflow() == Overflow::Wrap
+}
+
+// Fit n to the current word, or None on overflow in check mode
+pub fn fit(n: i128) -> Option<i64> {
+    let ctx = context();
+    let word = ctx.word();
+    if (word.min()..=word.max()).contains(&n) || ctx.overflow() == Overflow::Wrap {
+        Some(word.from_bits(n as u64))
+    } else {
+        None
+    }
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_settings<T>(size: Option<WordSize>, overflow: Overflow, test: impl FnOnce() -> T) -> T {
+        with_context(WordContext::new(size, overflow), test)
+    }
+
+    #[test]
+    fn test_word_size() {
+        assert_eq!(WordSize::parse("u16"), Some(WordSize::U16));
+        assert_eq!(WordSize::parse("i64").unwrap().name(), "i64");
+        assert_eq!(WordSize::parse("u12"), None);
+        assert_eq!(WordSize::U8.bits(), 8);
+        assert_eq!(WordSize::U8.mask(), 0xFF);
+        assert_eq!(WordSize::U64.mask(), u64::MAX);
+        assert_eq!((WordSize::I8.min(), WordSize::I8.max()), (-128, 127));
+        assert_eq!((WordSize::U16.min(), WordSize::U16.max()), (0, 65535));
+        assert_eq!(WordSize::U64.max(), u64::MAX as i128);
+        assert_eq!(WordSize::I8.from_bits(0xFF), -1);
+        assert_eq!(WordSize::U8.from_bits(0x1FF), 255);
+        assert_eq!(WordSize::U64.from_bits(u64::MAX), -1);
+        assert_eq!(Overflow::parse("wrap"), Some(Overflow::Wrap));
+        assert_eq!(Overflow::parse("saturate"), None);
+    }
+
+    #[test]
+    fn test_word_fit() {
+        assert_eq!(fit(i64::MAX as i128), Some(i64::MAX));
+        assert_eq!(fit(i64::MAX as i128 + 1), None);
+        assert_eq!(widen(-1), -1);
+
+        with_settings(Some(WordSize::U8), Overflow::Check, || {
+            assert_eq!(fit(255), Some(255));
+            assert_eq!(fit(256), None);
+            assert_eq!(fit(-1), None);
+            assert_eq!(wrap(-1), 255);
+            assert_eq!(pattern(-1), 0xFF);
+            assert_eq!(from_bits(0x100), 0);
+        });
+
+        with_settings(Some(WordSize::I8), Overflow::Wrap, || {
+            assert_eq!(fit(128), Some(-128));
+            assert_eq!(fit(-129), Some(127));
+            assert_eq!(pattern(-1), 0xFF);
+        });
+
+        with_settings(Some(WordSize::U64), Overflow::Check, || {
+            assert_eq!(widen(-1), u64::MAX as i128);
+            assert_eq!(fit(u64::MAX as i128), Some(-1));
+            assert_eq!(fit(u64::MAX as i128 + 1), None);
+        });
+    }
+}
@@ -15,7 +15,9 @@ use std::process;
 struct PCalcCmd {
     expr: String,
     file: String,
-    expected: String
+    expected: String,
+    options: Vec<String>,
+    success: bool
 }
 
 impl PCalcCmd {
@@ -23,13 +25,20 @@ impl PCalcCmd {
         PCalcCmd {
             expr: String::new(),
             file: String::new(),
-            expected: String::new()
+            expected: String::new(),
+            options: Vec::new(),
+            success: true
         }
     }
 
+    pub fn with_option(&mut self, option: &str) -> &mut Self {
+        self.options.push(option.to_string());
+        self
+    }
+
     pub fn add_expr(&mut self, expr: &str) -> &mut Self {
         if !self.expr.is_empty() {
-            self.expr.push_str(";");
+            self.expr.push(';');
         }
         self.expr.push_str(expr);
         self
@@ -46,6 +55,11 @@ impl PCalcCmd {
         self
     }
 
+    pub fn expect_failure(&mut self) -> &mut Self {
+        self.success = false;
+        self
+    }
+
     pub fn run(&mut self) {
         assert!(!self.expr.is_empty() || !self.file.is_empty());
 
@@ -55,6 +69,7 @@ impl PCalcCmd {
                 let actual = out.stdout.escape_ascii().to_string();
                 let expect = format!("{}{}", self.expected, if self.expected.is_empty() { "" } else { "\\n" });
                 assert_eq!(actual, expect);
+                assert_eq!(out.status.success(), self.success);
             }
             Err(e) => {
                 eprintln!("Failed to run command - {}", e);
@@ -66,6 +81,7 @@ impl PCalcCmd {
         let mut cmd = process::Command::new("target/debug/prefix_calculator");
         cmd.arg("-q");
         cmd.arg("-b");
+        cmd.args(&self.options);
         if !self.expr.is_empty() {
             cmd.arg("-e").arg(self.expr.as_str());
         }
@@ -94,10 +110,8 @@ impl Drop for PCalcCmd {
     fn drop(&mut self) {
         if !self.file.is_empty() {
             let path = PathBuf::from(self.file.as_str());
-            if path.exists() {
-                if fs::remove_file(path.as_path()).is_err() {
-                    eprintln!("Drop - failed to remove {}", self.file);
-                }
+            if path.exists() && fs::remove_file(path.as_path()).is_err() {
+                eprintln!("Drop - failed to remove {}", self.file);
             }
         }
     }
@@ -229,18 +243,528 @@ fn test_pcalc_funcalls2() {
         .add_expr("xprint call near 3 4 3.5 4.5 cend")
         .with_file(
             "test_pcalc_funcalls2",
-            "def dist x1 y1 x2 y2\n\
+            "def dist xa ya xb yb\n\
              begin\n\
-             var dx2 ^ - x2 x1 2\n\
-             var dy2 ^ - y2 y1 2\n\
+             var dx2 ^ - xb xa 2\n\
+             var dy2 ^ - yb ya 2\n\
              sqrt + dx2 dy2\n\
              end\n\
              \n\
-             def near x1 y1 x2 y2\n\
+             def near xa ya xb yb\n\
              begin\n\
-             <= call dist x1 y1 x2 y2 cend 1.0\n\
+             <= call dist xa ya xb yb cend 1.0\n\
              end\n"
         )
         .expect_output("false\\ntrue")
         .run();
 }
+
+#[test]
+fn test_pcalc_integers() {
+    PCalcCmd::new()
+        .add_expr("xprint ^ 2 62")
+        .add_expr("xprint % 17 5")
+        .add_expr("xprint / 7 2")
+        .add_expr("xprint + 9007199254740993 0")
+        // Integer remainder by zero reports an error and prints nothing
+        .add_expr("xprint % 5 0")
+        .expect_output("4611686018427387904\\n2\\n3.5\\n9007199254740993")
+        .run();
+}
+
+#[test]
+fn test_pcalc_rational() {
+    PCalcCmd::new()
+        .with_option("-r")
+        .add_expr("xprint + 0.1 0.2")
+        .add_expr("xprint == + 0.1 0.2 0.3")
+        .add_expr("xprint / 1 3")
+        .add_expr("xprint * / 1 3 3")
+        .expect_output("3/10\\ntrue\\n1/3\\n1")
+        .run();
+}
+
+#[test]
+fn test_pcalc_complex() {
+    PCalcCmd::new()
+        .add_expr("var z + 3 * 4 i")
+        .add_expr("xprint z")
+        .add_expr("xprint cabs z")
+        .add_expr("xprint sqrt -1")
+        .add_expr("xprint * z conj z")
+        .add_expr("xprint rect re z neg im z")
+        .add_expr("xprint * i i")
+        .expect_output("3+4i\\n5\\ni\\n25\\n3-4i\\n-1")
+        .run();
+}
+
+#[test]
+fn test_pcalc_strings() {
+    PCalcCmd::new()
+        .add_expr("var greeting \"hello; world\"")
+        .add_expr("xprint greeting")
+        .add_expr("xprint upper greeting")
+        .add_expr("xprint len greeting")
+        .add_expr("xprint concat \"x = \" str + 1 parse \"41\"")
+        .add_expr("xprint < \"apple\" \"banana\"")
+        .add_expr("xprint == lower \"ABC\" \"abc\"")
+        .expect_output("hello; world\\nHELLO; WORLD\\n12\\nx = 42\\ntrue\\ntrue")
+        .run();
+
+    PCalcCmd::new()
+        .with_file(
+            "pcalc_strings_test.txt",
+            "def greet name begin concat \"Hello, \" name end\nxprint call greet \"Bob\" cend\n"
+        )
+        .expect_output("Hello, Bob")
+        .run();
+}
+
+#[test]
+fn test_pcalc_lists() {
+    PCalcCmd::new()
+        .add_expr("var xs [1 2 3 4]")
+        .add_expr("def sq x begin * x x end")
+        .add_expr("def even x begin == % x 2 0 end")
+        .add_expr("def add a b begin + a b end")
+        .add_expr("xprint xs")
+        .add_expr("xprint map sq xs")
+        .add_expr("xprint filter even range 0 10")
+        .add_expr("xprint fold add 0 xs")
+        .add_expr("xprint sum push xs 5")
+        .add_expr("xprint nth xs 2")
+        .add_expr("xprint [\"a b\" [true] / 1 4]")
+        .expect_output("[1, 2, 3, 4]\\n[1, 4, 9, 16]\\n[0, 2, 4, 6, 8]\\n10\\n15\\n3\\n[\\\"a b\\\", [true], 0.25]")
+        .run();
+}
+
+#[test]
+fn test_pcalc_units() {
+    PCalcCmd::new()
+        .add_expr("xprint / * 3 _m * 2 _s")
+        .add_expr("xprint to 5_km _mi")
+        .add_expr("xprint to 20_degC _degF")
+        .add_expr("xprint * 2_kg 9.81_m/s^2")
+        .add_expr("xprint + 1_km 500_m")
+        .add_expr("xprint asnum to 1_mi _ft")
+        .add_expr("xprint < 1_mi 2_km")
+        .add_expr("xprint * 1_m/s/s 2")
+        .add_expr("xprint 1_kg*m*m/s^2")
+        .expect_output("1.5 m/s\\n3.10685596118667 mi\\n68 degF\\n19.62 N\\n1.5 km\\n5280\\ntrue\\n2 m/s^2\\n1 J")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint 1_m")
+        .add_expr("xprint + 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("1 m")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint == 1_m 1_m")
+        .add_expr("xprint == 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("true")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint != 1_m 1_m")
+        .add_expr("xprint != 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("false")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint < 1_m 1_m")
+        .add_expr("xprint < 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("false")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint <= 1_m 1_m")
+        .add_expr("xprint <= 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("true")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint > 1_m 1_m")
+        .add_expr("xprint > 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("false")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint >= 1_m 1_m")
+        .add_expr("xprint >= 1_m 1_s")
+        .add_expr("xprint 2_m")
+        .expect_output("true")
+        .run();
+}
+
+#[test]
+fn test_pcalc_intervals() {
+    PCalcCmd::new()
+        .add_expr("var part interval 9.9 10.1")
+        .add_expr("xprint part")
+        .add_expr("xprint * 2 part")
+        .add_expr("xprint ^ interval -1 2 2")
+        .add_expr("xprint lo / 1 interval 2 4")
+        .add_expr("xprint width interval 1 3")
+        .add_expr("xprint < part 11")
+        .add_expr("xprint < part 10")
+        .add_expr("xprint == < part 10 < part 10")
+        .add_expr("xprint if > part 9 ? 1 : 0 fi")
+        .expect_output("[9.9 .. 10.1]\\n[19.8 .. 20.2]\\n[0 .. 4]\\n0.25\\n2\\ntrue\\nunknown\\nunknown\\n1")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint 1")
+        .add_expr("xprint if < interval 9 11 10 ? 1 : 0 fi")
+        .add_expr("xprint 2")
+        .expect_output("1")
+        .run();
+}
+
+#[test]
+fn test_pcalc_dates() {
+    PCalcCmd::new()
+        .with_option("--now=2024-03-15T09:00")
+        .add_expr("var start 2024-03-15")
+        .add_expr("xprint + start P3DT4H12M")
+        .add_expr("xprint - 2024-03-18T04:12 start")
+        .add_expr("xprint asnum PT1H")
+        .add_expr("xprint days_between start 2024-12-25")
+        .add_expr("xprint weekday start")
+        .add_expr("xprint add_months 2024-01-31 1")
+        .add_expr("xprint * 3 PT8H")
+        .add_expr("xprint now")
+        .add_expr("xprint - now start")
+        .add_expr("xprint > now start")
+        .add_expr("xprint * P1D -1.5")
+        .add_expr("xprint / P1D 4")
+        // Whole seconds only, so this reports an error and prints nothing
+        .add_expr("xprint / PT1S 3")
+        .expect_output("2024-03-18T04:12:00\\n3d 4h 12m\\n3600\\n285\\nFriday\\n2024-02-29\\n1d\\n2024-03-15T09:00:00\\n9h\\ntrue\\n-(1d 12h)\\n6h")
+        .run();
+}
+
+#[test]
+fn test_pcalc_uncertain() {
+    PCalcCmd::new()
+        .add_expr("var g pm 9.81 0.02")
+        .add_expr("var h pm 2 0.1")
+        .add_expr("xprint g")
+        .add_expr("xprint - g g")
+        .add_expr("xprint * 2 h")
+        .add_expr("xprint + h h")
+        .add_expr("xprint sigma + h pm 1 0.1")
+        .add_expr("xprint asnum g")
+        .add_expr("xprint sqrt pm 4 0.2")
+        .add_expr("xprint > g 9")
+        .add_expr("xprint sigma atan2 pm 1 0.1 1")
+        .add_expr("xprint hypot pm 3 0.1 4")
+        .add_expr("xprint mod pm -7 0.1 3")
+        // Output is ascii escaped, so ± prints as \xc2\xb1
+        .expect_output("9.81 \\xc2\\xb1 0.02\\n0 \\xc2\\xb1 0\\n4 \\xc2\\xb1 0.2\\n4 \\xc2\\xb1 0.2\\n0.1414213562373095\\n9.81\\n2 \\xc2\\xb1 0.05\\ntrue\\n0.05\\n5 \\xc2\\xb1 0.06\\n2 \\xc2\\xb1 0.1")
+        .run();
+}
+
+#[test]
+fn test_pcalc_decimal() {
+    PCalcCmd::new()
+        .add_expr("var price 19.99d")
+        .add_expr("xprint * price 3")
+        .add_expr("xprint == + 0.1d 0.2d 0.3d")
+        .add_expr("xprint / 1 3d")
+        .add_expr("xprint round 2.5d")
+        .add_expr("xprint dec 0.1")
+        .add_expr("xprint asnum price")
+        .expect_output("59.97\\ntrue\\n0.3333333333333333333333333333\\n2\\n0.1\\n19.99")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--decimal")
+        .with_option("--scale=2")
+        .with_option("--rounding=half-up")
+        .add_expr("xprint + 0.1 0.2")
+        .add_expr("xprint / 2 3")
+        .add_expr("xprint round 2.345")
+        .expect_output("0.3\\n0.67\\n2.35")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--decimal")
+        .add_expr("xprint sqrt 2")
+        .add_expr("xprint interval 1 2")
+        .add_expr("xprint + 1 sqrt 2")
+        .add_expr("xprint 1")
+        .expect_output("1.4142135623730951\\n[1 .. 2]")
+        .run();
+}
+
+#[test]
+fn test_pcalc_vector_matrix() {
+    PCalcCmd::new()
+        .add_expr("var a mat [[2 1] [1 3]]")
+        .add_expr("var b vec [3 5]")
+        .add_expr("xprint solve a b")
+        .add_expr("xprint det a")
+        .add_expr("xprint inv mat [[1 2] [3 4]]")
+        .add_expr("xprint * a a")
+        .add_expr("xprint dot b [1 1]")
+        .add_expr("xprint cross [1 0 0] [0 1 0]")
+        .add_expr("xprint norm [3 4]")
+        .add_expr("xprint transpose mat [[1 2 3]]")
+        .add_expr("xprint + b 1")
+        .add_expr("xprint inv mat [[0 1] [1 0]]")
+        .expect_output("(0.8, 1.4)\\n5\\n[ -2     1]\\n[1.5  -0.5]\\n[5   5]\\n[5  10]\\n8\\n(0, 0, 1)\\n5\\n[1]\\n[2]\\n[3]\\n(4, 6)\\n[0  1]\\n[1  0]")
+        .run();
+}
+
+#[test]
+fn test_pcalc_record() {
+    PCalcCmd::new()
+        .add_expr("def stats xs begin var m / sum xs len xs {mean m n len xs} end")
+        .add_expr("var s call stats [1 2 3 6] cend")
+        .add_expr("xprint s")
+        .add_expr("xprint get s mean")
+        .add_expr("xprint with s n 5")
+        .add_expr("xprint with s range {lo 1 hi 6}")
+        .add_expr("xprint get get with s range {lo 1 hi 6} range hi")
+        .expect_output("{mean: 3, n: 4}\\n3\\n{mean: 3, n: 5}\\n{mean: 3, n: 4, range: {lo: 1, hi: 6}}\\n6")
+        .run();
+}
+
+#[test]
+fn test_pcalc_none() {
+    PCalcCmd::new()
+        .add_expr("def empty begin end")
+        .add_expr("+ 1 2")
+        .add_expr("if false ? 5 fi")
+        .add_expr("call empty cend")
+        .add_expr("xprint last")
+        .add_expr("xprint if false ? 5 fi")
+        .add_expr("xprint isnone call empty cend")
+        .add_expr("xprint default if false ? 5 fi 7")
+        .expect_output("3\\nnone\\ntrue\\n7")
+        .run();
+}
+
+#[test]
+fn test_pcalc_word() {
+    PCalcCmd::new()
+        .with_option("--word=u8")
+        .add_expr("xprint band 0xF0 0b10101010")
+        .add_expr("xprint bnot 0x0F")
+        .add_expr("xprint rotl 0x81 1")
+        .add_expr("xprint popcnt 0o377")
+        .add_expr("xprint + 255 1")
+        .expect_output("160\\n240\\n3\\n8")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--word=i8")
+        .with_option("--overflow=wrap")
+        .add_expr("xprint + 127 1")
+        .add_expr("xprint shr 0x80 4")
+        .add_expr("xprint clz 1")
+        .expect_output("-128\\n-8\\n7")
+        .run();
+}
+
+#[test]
+fn test_pcalc_format() {
+    PCalcCmd::new()
+        .with_option("--format=fix 3 sep")
+        .add_expr("xprint * 1000 pi")
+        .add_expr("xprint [1234567 0.5]")
+        .add_expr("xprint {x 2.5}")
+        .add_expr("xprint / 37_m 3")
+        .expect_output("3,141.593\\n[1,234,567, 0.500]\\n{x: 2.500}\\n12.333 m")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--format=hex")
+        .with_option("--word=u16")
+        .add_expr("xprint bnot 0")
+        .add_expr("xprint 0.5")
+        .expect_output("0xFFFF\\n0.5")
+        .run();
+}
+
+#[test]
+fn test_pcalc_strict() {
+    PCalcCmd::new()
+        .add_expr("xprint isnan / 0.0 0.0")
+        .add_expr("xprint isinf ln 0")
+        .add_expr("xprint isfinite 2")
+        .expect_output("true\\ntrue\\ntrue")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--strict")
+        .add_expr("xprint sqrt 4")
+        .add_expr("xprint / 1 0")
+        .add_expr("xprint 3")
+        .expect_output("2")
+        .run();
+}
+
+#[test]
+fn test_pcalc_logical() {
+    PCalcCmd::new()
+        .add_expr("xprint and false undefined")
+        .add_expr("xprint or true undefined")
+        .add_expr("xprint implies false undefined")
+        .add_expr("xprint xor true false")
+        .add_expr("xprint nand true true")
+        .add_expr("xprint nor false false")
+        .add_expr("xprint and 1 true")
+        .add_expr("xprint 3")
+        .expect_output("false\\ntrue\\ntrue\\ntrue\\nfalse\\ntrue")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--truthy")
+        .add_expr("xprint and 1 true")
+        .add_expr("xprint or 0 \"\"")
+        .add_expr("xprint not []")
+        .expect_output("true\\nfalse\\ntrue")
+        .run();
+}
+
+#[test]
+fn test_pcalc_approx_equal() {
+    PCalcCmd::new()
+        .add_expr("xprint == + 0.1 0.2 0.3")
+        .add_expr("xprint ~= + 0.1 0.2 0.3")
+        .add_expr("xprint !~= 1 1.001")
+        .add_expr("xprint approx 100 100.5 0.01")
+        .expect_output("false\\ntrue\\ntrue\\ntrue")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--tolerance=ulp 1")
+        .add_expr("xprint ~= + 0.1 0.2 0.3")
+        .add_expr("xprint ~= 1 + 1 1e-15")
+        .expect_output("true\\nfalse")
+        .run();
+}
+
+#[test]
+fn test_pcalc_ternary_ops() {
+    PCalcCmd::new()
+        .add_expr("xprint clamp 12 0 10")
+        .add_expr("xprint lerp 10 20 0.25")
+        .add_expr("xprint fma 2 3 4")
+        .add_expr("xprint fma 4000000000 4000000000 -9000000000000000000")
+        .add_expr("xprint between 5 0 10")
+        .add_expr("xprint select > 1 2 \"yes\" \"no\"")
+        .add_expr("xprint clamp 1 10 0")
+        .add_expr("xprint 3")
+        .expect_output("10\\n12.5\\n10\\n7000000000000000000\\ntrue\\nno")
+        .run();
+}
+
+#[test]
+fn test_pcalc_angle() {
+    PCalcCmd::new()
+        .with_option("--angle=deg")
+        .add_expr("xprint sin 30")
+        .add_expr("xprint cos 180")
+        .add_expr("xprint tan 45")
+        .add_expr("xprint acos 0")
+        .add_expr("xprint atan2 1 1")
+        .add_expr("xprint deg2rad 180")
+        // Units aren't angles, so this reports an error and prints nothing
+        .add_expr("xprint sin 1_m")
+        .expect_output("0.5\\n-1\\n1\\n90\\n45\\n3.141592653589793")
+        .run();
+
+    PCalcCmd::new()
+        .with_option("--angle=grad")
+        .add_expr("xprint sin 100")
+        .add_expr("xprint rad2deg pi")
+        .expect_output("1\\n180")
+        .run();
+
+    PCalcCmd::new()
+        .add_expr("xprint sin deg2rad 90")
+        .add_expr("xprint atan 1")
+        .expect_output("1\\n0.7853981633974483")
+        .run();
+}
+
+#[test]
+fn test_pcalc_binary_math() {
+    PCalcCmd::new()
+        .add_expr("xprint hypot 3 4")
+        .add_expr("xprint logb 8 2")
+        .add_expr("xprint root -27 3")
+        .add_expr("xprint idiv -7 2")
+        .add_expr("xprint mod -7 3")
+        .add_expr("xprint % -7 3")
+        .add_expr("xprint copysign 3 -1")
+        .add_expr("xprint fdim 3 5")
+        .expect_output("5\\n3\\n-3\\n-4\\n2\\n-1\\n-3\\n0")
+        .run();
+}
+
+#[test]
+fn test_pcalc_number_theory() {
+    PCalcCmd::new()
+        .add_expr("xprint gcd 12 18")
+        .add_expr("xprint lcm 4 6")
+        .add_expr("xprint fact 10")
+        .add_expr("xprint choose 5 2")
+        .add_expr("xprint perm 5 2")
+        .add_expr("xprint isprime 97")
+        .add_expr("xprint nextprime 97")
+        .add_expr("xprint factor 360")
+        .add_expr("xprint modpow 4 13 497")
+        .add_expr("xprint modinv 3 11")
+        .add_expr("xprint fact 2.5")
+        .add_expr("xprint 1")
+        .expect_output("6\\n12\\n3628800\\n10\\n20\\ntrue\\n101\\n[2, 2, 2, 3, 3, 5]\\n445\\n4")
+        .run();
+}
+
+#[test]
+fn test_pcalc_special_math() {
+    PCalcCmd::new()
+        .add_expr("xprint gamma 5")
+        .add_expr("xprint lgamma 1")
+        .add_expr("xprint erf 0")
+        .add_expr("xprint erfc 0")
+        .add_expr("xprint j0 0")
+        .add_expr("xprint beta 1 1")
+        .add_expr("xprint gammainc 1 0")
+        .add_expr("xprint < abs - erfinv erf 0.5 0.5 1e-15")
+        .add_expr("xprint < abs - j0 1 0.7651976865579666 1e-14")
+        .add_expr("xprint < abs - y1 1 -0.7812128213002887 1e-14")
+        .add_expr("xprint gammainc 0 1")
+        .add_expr("xprint 1")
+        .expect_output("24\\n0\\n0\\n1\\n1\\n1\\n0\\ntrue\\ntrue\\ntrue")
+        .run();
+}
+
+#[test]
+fn test_pcalc_invalid_option() {
+    for option in [
+        "--scale=x",
+        "--rounding=x",
+        "--word=x",
+        "--overflow=x",
+        "--format=x",
+        "--tolerance=x",
+        "--angle=x",
+        "--now=x"
+    ] {
+        PCalcCmd::new().with_option(option).add_expr("xprint 1").expect_failure().run();
+    }
+
+    // Decimal and rational modes exclude each other
+    PCalcCmd::new().with_option("-d").with_option("-r").add_expr("xprint 1").expect_failure().run();
+}